@@ -0,0 +1,54 @@
+use crate::babybear_field::BabyBearField;
+use crate::extension::quartic::QuarticExtension;
+use crate::extension::quintic::QuinticExtension;
+use crate::extension::{Extendable, Frobenius};
+use crate::types::Field;
+
+impl Frobenius<1> for BabyBearField {}
+
+impl Extendable<4> for BabyBearField {
+    type Extension = QuarticExtension<Self>;
+
+    // Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^4 - 11).is_irreducible()`.
+    const W: Self = Self(11);
+
+    // DTH_ROOT = W^((ORDER - 1)/4)
+    const DTH_ROOT: Self = Self(1728404513);
+
+    const EXT_MULTIPLICATIVE_GROUP_GENERATOR: [Self; 4] = [
+        Self(754957204),
+        Self(39590217),
+        Self(1582976038),
+        Self(223392859),
+    ];
+
+    const EXT_POWER_OF_TWO_GENERATOR: [Self; 4] = [Self(0), Self(0), Self(0), Self(1394066976)];
+}
+
+impl Extendable<5> for BabyBearField {
+    type Extension = QuinticExtension<Self>;
+
+    // Verifiable in Sage with
+    // `R.<x> = GF(p)[]; assert (x^5 - 2).is_irreducible()`.
+    const W: Self = Self(2);
+
+    // DTH_ROOT = W^((ORDER - 1)/5)
+    const DTH_ROOT: Self = Self(815036133);
+
+    const EXT_MULTIPLICATIVE_GROUP_GENERATOR: [Self; 5] = [
+        Self(1506975253),
+        Self(1186737876),
+        Self(89817943),
+        Self(1646816268),
+        Self(542129906),
+    ];
+
+    const EXT_POWER_OF_TWO_GENERATOR: [Self; 5] = [
+        Self::POWER_OF_TWO_GENERATOR,
+        Self(0),
+        Self(0),
+        Self(0),
+        Self(0),
+    ];
+}
@@ -0,0 +1,232 @@
+use core::fmt::{self, Debug, Display, Formatter};
+use core::hash::Hash;
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num::{BigUint, Integer, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Field, Field64, PrimeField, PrimeField64, Sample};
+
+/// The BabyBear field, whose order is `15 * 2^27 + 1`.
+///
+/// Its elements fit in 31 bits, and its large two-adicity makes it suitable for FFTs.
+/// Elements are always stored in canonical form.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(transparent)]
+pub struct BabyBearField(pub u32);
+
+impl BabyBearField {
+    const ORDER_U32: u32 = 0x78000001;
+}
+
+impl Display for BabyBearField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Debug for BabyBearField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Sample for BabyBearField {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        use rand::Rng;
+        Self::from_canonical_u64(rng.gen_range(0..Self::ORDER))
+    }
+}
+
+impl Field for BabyBearField {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+    const TWO: Self = Self(2);
+    const NEG_ONE: Self = Self(Self::ORDER_U32 - 1);
+
+    const TWO_ADICITY: usize = 27;
+    const CHARACTERISTIC_TWO_ADICITY: usize = Self::TWO_ADICITY;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: Self = Self(31);
+
+    // Sage: `g^((p - 1) / 2^27)`
+    const POWER_OF_TWO_GENERATOR: Self = Self(440564289);
+
+    const BITS: usize = 31;
+
+    fn order() -> BigUint {
+        Self::ORDER.into()
+    }
+    fn characteristic() -> BigUint {
+        Self::order()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // Fermat's little theorem.
+        Some(self.exp_u64(Self::ORDER - 2))
+    }
+
+    fn from_noncanonical_biguint(n: BigUint) -> Self {
+        Self(n.mod_floor(&Self::order()).to_u32().unwrap())
+    }
+
+    #[inline(always)]
+    fn from_canonical_u64(n: u64) -> Self {
+        debug_assert!(n < Self::ORDER);
+        Self(n as u32)
+    }
+
+    fn from_noncanonical_u128(n: u128) -> Self {
+        Self((n % Self::ORDER as u128) as u32)
+    }
+
+    #[inline]
+    fn from_noncanonical_u64(n: u64) -> Self {
+        Self((n % Self::ORDER) as u32)
+    }
+
+    #[inline]
+    fn from_noncanonical_i64(n: i64) -> Self {
+        Self(n.rem_euclid(Self::ORDER as i64) as u32)
+    }
+}
+
+impl PrimeField for BabyBearField {
+    fn to_canonical_biguint(&self) -> BigUint {
+        self.0.into()
+    }
+}
+
+impl Field64 for BabyBearField {
+    const ORDER: u64 = Self::ORDER_U32 as u64;
+}
+
+impl PrimeField64 for BabyBearField {
+    #[inline]
+    fn to_canonical_u64(&self) -> u64 {
+        self.0 as u64
+    }
+
+    #[inline(always)]
+    fn to_noncanonical_u64(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+impl Neg for BabyBearField {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        if self.is_zero() {
+            Self::ZERO
+        } else {
+            Self(Self::ORDER_U32 - self.0)
+        }
+    }
+}
+
+impl Add for BabyBearField {
+    type Output = Self;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        // Both summands are less than 2^31, so this cannot overflow.
+        let sum = self.0 + rhs.0;
+        if sum >= Self::ORDER_U32 {
+            Self(sum - Self::ORDER_U32)
+        } else {
+            Self(sum)
+        }
+    }
+}
+
+impl AddAssign for BabyBearField {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for BabyBearField {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Sub for BabyBearField {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (diff, under) = self.0.overflowing_sub(rhs.0);
+        if under {
+            Self(diff.wrapping_add(Self::ORDER_U32))
+        } else {
+            Self(diff)
+        }
+    }
+}
+
+impl SubAssign for BabyBearField {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for BabyBearField {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self::from_noncanonical_u64(self.0 as u64 * rhs.0 as u64)
+    }
+}
+
+impl MulAssign for BabyBearField {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Product for BabyBearField {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Div for BabyBearField {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl DivAssign for BabyBearField {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_field_arithmetic, test_prime_field_arithmetic};
+
+    test_prime_field_arithmetic!(crate::babybear_field::BabyBearField, small);
+    test_field_arithmetic!(crate::babybear_field::BabyBearField);
+}
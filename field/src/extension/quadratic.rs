@@ -11,6 +11,7 @@ use crate::types::{Field, Sample};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(bound = "")]
+#[repr(transparent)]
 pub struct QuadraticExtension<F: Extendable<2>>(pub [F; 2]);
 
 impl<F: Extendable<2>> Default for QuadraticExtension<F> {
@@ -247,4 +248,27 @@ mod tests {
             >
         );
     }
+
+    mod mersenne31 {
+        use crate::{test_field_arithmetic, test_field_extension};
+
+        test_field_extension!(crate::mersenne31_field::Mersenne31Field, 2);
+        test_field_arithmetic!(
+            crate::extension::quadratic::QuadraticExtension<
+                crate::mersenne31_field::Mersenne31Field,
+            >
+        );
+    }
+
+    mod mersenne31_quartic {
+        use crate::{test_field_arithmetic, test_field_extension};
+
+        test_field_extension!(
+            crate::extension::quadratic::QuadraticExtension<
+                crate::mersenne31_field::Mersenne31Field,
+            >,
+            2
+        );
+        test_field_arithmetic!(crate::mersenne31_extensions::Mersenne31QuarticExtension);
+    }
 }
@@ -12,6 +12,7 @@ use crate::types::{Field, Sample};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(bound = "")]
+#[repr(transparent)]
 pub struct QuarticExtension<F: Extendable<4>>(pub [F; 4]);
 
 impl<F: Extendable<4>> Default for QuarticExtension<F> {
@@ -273,4 +274,15 @@ mod tests {
             >
         );
     }
+
+    mod babybear {
+        use crate::{test_field_arithmetic, test_field_extension};
+
+        test_field_extension!(crate::babybear_field::BabyBearField, 4);
+        test_field_arithmetic!(
+            crate::extension::quartic::QuarticExtension<
+                crate::babybear_field::BabyBearField,
+            >
+        );
+    }
 }
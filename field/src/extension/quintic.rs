@@ -12,6 +12,7 @@ use crate::types::{Field, Sample};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(bound = "")]
+#[repr(transparent)]
 pub struct QuinticExtension<F: Extendable<5>>(pub [F; 5]);
 
 impl<F: Extendable<5>> Default for QuinticExtension<F> {
@@ -288,4 +289,15 @@ mod tests {
             >
         );
     }
+
+    mod babybear {
+        use crate::{test_field_arithmetic, test_field_extension};
+
+        test_field_extension!(crate::babybear_field::BabyBearField, 5);
+        test_field_arithmetic!(
+            crate::extension::quintic::QuinticExtension<
+                crate::babybear_field::BabyBearField,
+            >
+        );
+    }
 }
@@ -1,6 +1,7 @@
 use alloc::vec::Vec;
 use core::cmp::{max, min};
 
+use plonky2_maybe_rayon::*;
 use plonky2_util::{log2_strict, reverse_index_bits_in_place};
 use unroll::unroll_for_loops;
 
@@ -11,6 +12,18 @@ use crate::types::Field;
 
 pub type FftRootTable<F> = Vec<Vec<F>>;
 
+/// The smallest `lg_n` for which we use `fft_six_step` rather than `fft_classic`. Below this size,
+/// the input fits in cache and the transpositions are pure overhead. On a single thread,
+/// `fft_classic` is about as fast at any size, so we only use `fft_six_step` when its row FFTs can
+/// run in parallel.
+const SIX_STEP_MIN_LG_N: usize = 16;
+
+/// The number of elements processed by each task in the element-wise passes of `fft_six_step`.
+const SIX_STEP_CHUNK_SIZE: usize = 1 << 12;
+
+/// The log of the side length of the blocks swapped by each step of `par_transpose_square_in_place`.
+const TRANSPOSE_LG_BLOCK_SIZE: usize = 5;
+
 pub fn fft_root_table<F: Field>(n: usize) -> FftRootTable<F> {
     let lg_n = log2_strict(n);
     // bases[i] = g^2^i, for i = 0, ..., lg_n - 1
@@ -41,7 +54,12 @@ fn fft_dispatch<F: Field>(
     let computed_root_table = root_table.is_none().then(|| fft_root_table(input.len()));
     let used_root_table = root_table.or(computed_root_table.as_ref()).unwrap();
 
-    fft_classic(input, zero_factor.unwrap_or(0), used_root_table);
+    let r = zero_factor.unwrap_or(0);
+    if input.len() >= 1 << SIX_STEP_MIN_LG_N && current_num_threads() > 1 {
+        fft_six_step(input, r, used_root_table);
+    } else {
+        fft_classic(input, r, used_root_table);
+    }
 }
 
 #[inline]
@@ -96,7 +114,7 @@ fn fft_classic_simd<P: PackedField>(
     values: &mut [P::Scalar],
     r: usize,
     lg_n: usize,
-    root_table: &FftRootTable<P::Scalar>,
+    root_table: &[Vec<P::Scalar>],
 ) {
     let lg_packed_width = log2_strict(P::WIDTH); // 0 when P is a scalar.
     let packed_values = P::pack_slice_mut(values);
@@ -162,7 +180,7 @@ fn fft_classic_simd<P: PackedField>(
 /// The parameter r signifies that the first 1/2^r of the entries of
 /// input may be non-zero, but the last 1 - 1/2^r entries are
 /// definitely zero.
-pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &FftRootTable<F>) {
+pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
     reverse_index_bits_in_place(values);
 
     let n = values.len();
@@ -201,16 +219,158 @@ pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &Fft
     }
 }
 
+/// Cache-friendly FFT, based on the "six-step" algorithm from Bailey's "FFTs in External or
+/// Hierarchical Memory".
+///
+/// When `lg_n` is even, we view `values` as a square matrix of size `sqrt(n)`, so that the FFT
+/// reduces to FFTs of its rows, which are contiguous in memory and processed in parallel,
+/// separated by transpositions and a multiplication by twiddle factors. When `lg_n` is odd, a
+/// radix-2 decimation-in-frequency step first splits the problem into two FFTs of half the size.
+///
+/// As in `fft_classic`, the parameter `r` signifies that only the first `1/2^r` of the entries of
+/// `values` may be non-zero.
+pub(crate) fn fft_six_step<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
+    let n = values.len();
+    let lg_n = log2_strict(n);
+
+    if root_table.len() != lg_n {
+        panic!(
+            "Expected root table of length {}, but it was {}.",
+            lg_n,
+            root_table.len()
+        );
+    }
+
+    if lg_n.is_multiple_of(2) {
+        fft_square(values, r, root_table);
+        return;
+    }
+
+    // With `omega` a primitive `n`-th root of unity, the FFT of `values` at even indices is the FFT
+    // of `u_j = values_j + values_{j + n/2}`, and at odd indices, the FFT of
+    // `v_j = (values_j - values_{j + n/2}) omega^j`.
+    let half_n = n / 2;
+    let omegas = &root_table[lg_n - 1][..half_n];
+    let (us, vs) = values.split_at_mut(half_n);
+    us.par_chunks_mut(SIX_STEP_CHUNK_SIZE)
+        .zip(vs.par_chunks_mut(SIX_STEP_CHUNK_SIZE))
+        .zip(omegas.par_chunks(SIX_STEP_CHUNK_SIZE))
+        .for_each(|((us, vs), omegas)| {
+            for ((u, v), &omega) in us.iter_mut().zip(vs).zip(omegas) {
+                if r == 0 {
+                    (*u, *v) = (*u + *v, (*u - *v) * omega);
+                } else {
+                    // The second half is zero.
+                    *v = *u * omega;
+                }
+            }
+        });
+
+    let half_root_table = &root_table[..lg_n - 1];
+    fft_square(us, r.saturating_sub(1), half_root_table);
+    fft_square(vs, r.saturating_sub(1), half_root_table);
+
+    // Interleave the two halves, i.e. move the entry at index `b 2^(lg_n - 1) + x` to `2 x + b`.
+    // Reversing the bits of `x` within each half and then all bits of the index does just that.
+    reverse_index_bits_in_place(us);
+    reverse_index_bits_in_place(vs);
+    reverse_index_bits_in_place(values);
+}
+
+/// The six-step FFT for `lg_n` even. See `fft_six_step`.
+fn fft_square<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
+    let lg_n = log2_strict(values.len());
+    debug_assert!(lg_n.is_multiple_of(2));
+    if lg_n == 0 {
+        return;
+    }
+
+    // We write each index `j` of the input as `j = j_1 m + j_2`, and each index `k` of the output
+    // as `k = k_1 + k_2 m`, where `m = sqrt(n)`.
+    let lg_m = lg_n / 2;
+    let m = 1 << lg_m;
+    let row_root_table = &root_table[..lg_m];
+    let omegas = &root_table[lg_n - 1];
+
+    // Make `j_1` the column index.
+    par_transpose_square_in_place(values, lg_m);
+
+    // Only the first `1/2^r` of the entries of each row may be non-zero. Then multiply by the
+    // twiddle factors `omega^(j_2 k_1)`.
+    values
+        .par_chunks_exact_mut(m)
+        .enumerate()
+        .for_each(|(j_2, row)| {
+            fft_classic(row, min(r, lg_m), row_root_table);
+            let omega = omegas[j_2];
+            let mut twiddle = F::ONE;
+            for x in row {
+                *x *= twiddle;
+                twiddle *= omega;
+            }
+        });
+
+    // Make `j_2` the column index.
+    par_transpose_square_in_place(values, lg_m);
+
+    values
+        .par_chunks_exact_mut(m)
+        .for_each(|row| fft_classic(row, 0, row_root_table));
+
+    // Make `k_1` the column index, so that entries are in the order of `k_1 + k_2 m`.
+    par_transpose_square_in_place(values, lg_m);
+}
+
+/// Transposes the square matrix `values`, of side length `2^lg_m`, in place. The matrix is split
+/// into square blocks, and each task swaps the blocks of a row of blocks, from the diagonal onwards,
+/// with the corresponding column of blocks.
+fn par_transpose_square_in_place<F: Field>(values: &mut [F], lg_m: usize) {
+    assert_eq!(values.len(), 1 << (2 * lg_m), "Not a square matrix");
+    let m = 1 << lg_m;
+    let block_size = 1 << min(lg_m, TRANSPOSE_LG_BLOCK_SIZE);
+    let num_blocks = m / block_size;
+
+    let values = SharedMutPtr(values.as_mut_ptr());
+    (0..num_blocks).into_par_iter().for_each(|block_row| {
+        let values = &values;
+        let rows = block_row * block_size..(block_row + 1) * block_size;
+        for block_col in block_row..num_blocks {
+            for i in rows.clone() {
+                let start = if block_col == block_row {
+                    i + 1
+                } else {
+                    block_col * block_size
+                };
+                for j in start..(block_col + 1) * block_size {
+                    // SAFETY: `i, j < m`, so both indices are in bounds. Each pair `{i, j}` with
+                    // `i < j` is only visited by the task of the row of blocks containing `i`, so
+                    // no two tasks access the same entry.
+                    unsafe {
+                        core::ptr::swap(values.0.add(i * m + j), values.0.add(j * m + i));
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// A pointer to the entries of a slice, which tasks may share as long as they access disjoint
+/// entries.
+struct SharedMutPtr<T>(*mut T);
+
+unsafe impl<T: Send> Send for SharedMutPtr<T> {}
+unsafe impl<T: Send> Sync for SharedMutPtr<T> {}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec::Vec;
 
     use plonky2_util::{log2_ceil, log2_strict};
 
-    use crate::fft::{fft, fft_with_options, ifft};
+    use crate::fft::{fft, fft_classic, fft_root_table, fft_six_step, fft_with_options, ifft};
     use crate::goldilocks_field::GoldilocksField;
     use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
-    use crate::types::Field;
+    use crate::types::{Field, Sample};
 
     #[test]
     fn fft_and_ifft() {
@@ -248,6 +408,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn fft_six_step_matches_classic() {
+        type F = GoldilocksField;
+        for lg_n in 0..=14 {
+            let n = 1 << lg_n;
+            let root_table = fft_root_table::<F>(n);
+            for r in 0..=lg_n.min(3) {
+                let mut values = F::rand_vec(n >> r);
+                values.resize(n, F::ZERO);
+
+                let mut expected = values.clone();
+                fft_classic(&mut expected, r, &root_table);
+                fft_six_step(&mut values, r, &root_table);
+                assert_eq!(values, expected, "lg_n = {lg_n}, r = {r}");
+            }
+        }
+    }
+
     fn evaluate_naive<F: Field>(coefficients: &PolynomialCoeffs<F>) -> PolynomialValues<F> {
         let degree = coefficients.len();
         let degree_padded = 1 << log2_ceil(degree);
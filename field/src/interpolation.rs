@@ -1,29 +1,16 @@
 use alloc::vec::Vec;
 
-use plonky2_util::log2_ceil;
-
-use crate::fft::ifft;
-use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
+use crate::polynomial::subproduct_tree::SubproductTree;
+use crate::polynomial::PolynomialCoeffs;
 use crate::types::Field;
 
 /// Computes the unique degree < n interpolant of an arbitrary list of n (point, value) pairs.
 ///
-/// Note that the implementation assumes that `F` is two-adic, in particular that
-/// `2^{F::TWO_ADICITY} >= points.len()`. This leads to a simple FFT-based implementation.
+/// This builds a subproduct tree over the points, which takes `O(n log^2 n)` operations, assuming
+/// that `F` is two-adic, with `2^{F::TWO_ADICITY} >= 2n`, so that products can use FFTs.
 pub fn interpolant<F: Field>(points: &[(F, F)]) -> PolynomialCoeffs<F> {
-    let n = points.len();
-    let n_log = log2_ceil(n);
-
-    let subgroup = F::two_adic_subgroup(n_log);
-    let barycentric_weights = barycentric_weights(points);
-    let subgroup_evals = subgroup
-        .into_iter()
-        .map(|x| interpolate(points, x, &barycentric_weights))
-        .collect();
-
-    let mut coeffs = ifft(PolynomialValues::new(subgroup_evals));
-    coeffs.trim();
-    coeffs
+    let (xs, ys): (Vec<F>, Vec<F>) = points.iter().copied().unzip();
+    SubproductTree::new(&xs).interpolate(&ys)
 }
 
 /// Interpolate the polynomial defined by an arbitrary set of (point, value) pairs at the given
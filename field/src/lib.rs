@@ -11,6 +11,8 @@ extern crate alloc;
 
 pub(crate) mod arch;
 
+pub mod babybear_extensions;
+pub mod babybear_field;
 pub mod batch_util;
 pub mod cosets;
 pub mod extension;
@@ -18,9 +20,12 @@ pub mod fft;
 pub mod goldilocks_extensions;
 pub mod goldilocks_field;
 pub mod interpolation;
+pub mod mersenne31_extensions;
+pub mod mersenne31_field;
 pub mod ops;
 pub mod packable;
 pub mod packed;
+pub mod packed_extension;
 pub mod polynomial;
 pub mod secp256k1_base;
 pub mod secp256k1_scalar;
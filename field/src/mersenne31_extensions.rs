@@ -0,0 +1,400 @@
+//! Extensions of the Mersenne31 field.
+//!
+//! Since `p = 3 mod 4` and `5` does not divide `p - 1`, there is no irreducible binomial `X^4 - W`
+//! or `X^5 - W` over this field, so it has no optimal extension of degree 4 or 5 and does not
+//! implement `Extendable<4>` or `Extendable<5>`. Instead, this module provides:
+//! - the quadratic extension `F[i]/(i^2 + 1)`, through `Extendable<2>`;
+//! - the quartic extension [`Mersenne31QuarticExtension`], built as a binomial quadratic extension
+//!   of the quadratic extension;
+//! - the quintic extension [`Mersenne31QuinticExtension`], defined by the trinomial
+//!   `X^5 - X - 6`.
+
+use core::fmt::{self, Debug, Display, Formatter};
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num::bigint::BigUint;
+use num::traits::Pow;
+use serde::{Deserialize, Serialize};
+
+use crate::extension::quadratic::QuadraticExtension;
+use crate::extension::{Extendable, FieldExtension, Frobenius};
+use crate::mersenne31_field::Mersenne31Field;
+use crate::types::{Field, Sample};
+
+impl Frobenius<1> for Mersenne31Field {}
+
+impl Extendable<2> for Mersenne31Field {
+    type Extension = QuadraticExtension<Self>;
+
+    // Since `p = 3 mod 4`, `-1` is not a square, and this extension is the field of Gaussian
+    // integers modulo `p`.
+    const W: Self = Self::NEG_ONE;
+
+    // DTH_ROOT = W^((ORDER - 1)/2)
+    const DTH_ROOT: Self = Self::NEG_ONE;
+
+    const EXT_MULTIPLICATIVE_GROUP_GENERATOR: [Self; 2] = [Self(12), Self(1)];
+
+    const EXT_POWER_OF_TWO_GENERATOR: [Self; 2] = [Self(0), Self::NEG_ONE];
+}
+
+/// The degree 4 extension of the Mersenne31 field, seen as the quadratic extension
+/// `F[i][u]/(u^2 - (2 + i))` of the Gaussian integers modulo `p`.
+///
+/// Its base field, in the sense of [`FieldExtension`], is the quadratic extension rather than
+/// [`Mersenne31Field`] itself.
+pub type Mersenne31QuarticExtension = QuadraticExtension<QuadraticExtension<Mersenne31Field>>;
+
+impl Extendable<2> for QuadraticExtension<Mersenne31Field> {
+    type Extension = QuadraticExtension<Self>;
+
+    // The norm of `2 + i` is `5`, which is not a square modulo `p`, so `2 + i` is not a square in
+    // the quadratic extension.
+    const W: Self = QuadraticExtension([Mersenne31Field(2), Mersenne31Field(1)]);
+
+    // DTH_ROOT = W^((ORDER - 1)/2)
+    const DTH_ROOT: Self = <Self as Field>::NEG_ONE;
+
+    const EXT_MULTIPLICATIVE_GROUP_GENERATOR: [Self; 2] = [
+        QuadraticExtension([Mersenne31Field(8), Mersenne31Field(0)]),
+        <Self as Field>::ONE,
+    ];
+
+    const EXT_POWER_OF_TWO_GENERATOR: [Self; 2] = [
+        QuadraticExtension([Mersenne31Field(2147450879), Mersenne31Field(32768)]),
+        <Self as Field>::ZERO,
+    ];
+}
+
+/// The degree 5 extension `F[X]/(X^5 - X - 6)` of the Mersenne31 field.
+///
+/// Unlike `QuinticExtension`, the modulus is not a binomial, so there is no cheap Frobenius map
+/// and inversion falls back to exponentiation.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(transparent)]
+pub struct Mersenne31QuinticExtension(pub [Mersenne31Field; 5]);
+
+impl Mersenne31QuinticExtension {
+    /// The constant `W` such that the modulus is `X^5 - X - W`.
+    ///
+    /// Verifiable in Sage with
+    /// `R.<x> = GF(p)[]; assert (x^5 - x - 6).is_irreducible()`.
+    const W: Mersenne31Field = Mersenne31Field(6);
+}
+
+impl Default for Mersenne31QuinticExtension {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl FieldExtension<5> for Mersenne31QuinticExtension {
+    type BaseField = Mersenne31Field;
+
+    fn to_basefield_array(&self) -> [Mersenne31Field; 5] {
+        self.0
+    }
+
+    fn from_basefield_array(arr: [Mersenne31Field; 5]) -> Self {
+        Self(arr)
+    }
+
+    fn from_basefield(x: Mersenne31Field) -> Self {
+        x.into()
+    }
+}
+
+impl From<Mersenne31Field> for Mersenne31QuinticExtension {
+    fn from(x: Mersenne31Field) -> Self {
+        Self([
+            x,
+            Mersenne31Field::ZERO,
+            Mersenne31Field::ZERO,
+            Mersenne31Field::ZERO,
+            Mersenne31Field::ZERO,
+        ])
+    }
+}
+
+impl Sample for Mersenne31QuinticExtension {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self([
+            Mersenne31Field::sample(rng),
+            Mersenne31Field::sample(rng),
+            Mersenne31Field::sample(rng),
+            Mersenne31Field::sample(rng),
+            Mersenne31Field::sample(rng),
+        ])
+    }
+}
+
+impl Field for Mersenne31QuinticExtension {
+    const ZERO: Self = Self([Mersenne31Field::ZERO; 5]);
+    const ONE: Self = Self([
+        Mersenne31Field::ONE,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+    ]);
+    const TWO: Self = Self([
+        Mersenne31Field::TWO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+    ]);
+    const NEG_ONE: Self = Self([
+        Mersenne31Field::NEG_ONE,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+        Mersenne31Field::ZERO,
+    ]);
+
+    // `p^5 - 1 = (p - 1)(p^4 + p^3 + p^2 + p + 1)`, where the second factor is odd, so the
+    // two-adicity is the same as for the base field.
+    const TWO_ADICITY: usize = Mersenne31Field::TWO_ADICITY;
+    const CHARACTERISTIC_TWO_ADICITY: usize = Mersenne31Field::CHARACTERISTIC_TWO_ADICITY;
+
+    const MULTIPLICATIVE_GROUP_GENERATOR: Self = Self([
+        Mersenne31Field(4),
+        Mersenne31Field(1),
+        Mersenne31Field(0),
+        Mersenne31Field(0),
+        Mersenne31Field(0),
+    ]);
+    const POWER_OF_TWO_GENERATOR: Self = Self::NEG_ONE;
+
+    const BITS: usize = Mersenne31Field::BITS * 5;
+
+    fn order() -> BigUint {
+        Mersenne31Field::order().pow(5u32)
+    }
+    fn characteristic() -> BigUint {
+        Mersenne31Field::characteristic()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // a^(r - 2) = a^-1, by Fermat's little theorem.
+        Some(self.exp_biguint(&(Self::order() - 2u32)))
+    }
+
+    fn from_noncanonical_biguint(n: BigUint) -> Self {
+        Mersenne31Field::from_noncanonical_biguint(n).into()
+    }
+
+    fn from_canonical_u64(n: u64) -> Self {
+        Mersenne31Field::from_canonical_u64(n).into()
+    }
+
+    fn from_noncanonical_u128(n: u128) -> Self {
+        Mersenne31Field::from_noncanonical_u128(n).into()
+    }
+
+    fn from_noncanonical_i64(n: i64) -> Self {
+        Mersenne31Field::from_noncanonical_i64(n).into()
+    }
+
+    fn from_noncanonical_u64(n: u64) -> Self {
+        Mersenne31Field::from_noncanonical_u64(n).into()
+    }
+}
+
+impl Display for Mersenne31QuinticExtension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} + {}*a + {}*a^2 + {}*a^3 + {}*a^4",
+            self.0[0], self.0[1], self.0[2], self.0[3], self.0[4]
+        )
+    }
+}
+
+impl Debug for Mersenne31QuinticExtension {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl Neg for Mersenne31QuinticExtension {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self([-self.0[0], -self.0[1], -self.0[2], -self.0[3], -self.0[4]])
+    }
+}
+
+impl Add for Mersenne31QuinticExtension {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] + rhs.0[0],
+            self.0[1] + rhs.0[1],
+            self.0[2] + rhs.0[2],
+            self.0[3] + rhs.0[3],
+            self.0[4] + rhs.0[4],
+        ])
+    }
+}
+
+impl AddAssign for Mersenne31QuinticExtension {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Mersenne31QuinticExtension {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Sub for Mersenne31QuinticExtension {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self([
+            self.0[0] - rhs.0[0],
+            self.0[1] - rhs.0[1],
+            self.0[2] - rhs.0[2],
+            self.0[3] - rhs.0[3],
+            self.0[4] - rhs.0[4],
+        ])
+    }
+}
+
+impl SubAssign for Mersenne31QuinticExtension {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Mersenne31QuinticExtension {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let mut c = [Mersenne31Field::ZERO; 9];
+        for (i, &a) in self.0.iter().enumerate() {
+            for (j, &b) in rhs.0.iter().enumerate() {
+                c[i + j] += a * b;
+            }
+        }
+
+        // Reduce using `X^k = X^(k - 4) + W * X^(k - 5)` for `k >= 5`, starting from the highest
+        // power so that the terms folded into `X^5..X^8` get reduced as well.
+        for k in (5..9).rev() {
+            let t = c[k];
+            c[k - 4] += t;
+            c[k - 5] += Self::W * t;
+        }
+
+        Self([c[0], c[1], c[2], c[3], c[4]])
+    }
+}
+
+impl MulAssign for Mersenne31QuinticExtension {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Product for Mersenne31QuinticExtension {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Div for Mersenne31QuinticExtension {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl DivAssign for Mersenne31QuinticExtension {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod quintic {
+        use crate::mersenne31_extensions::Mersenne31QuinticExtension;
+        use crate::mersenne31_field::Mersenne31Field;
+        use crate::test_field_arithmetic;
+        use crate::types::{Field, Sample};
+
+        type F = Mersenne31QuinticExtension;
+
+        test_field_arithmetic!(crate::mersenne31_extensions::Mersenne31QuinticExtension);
+
+        #[test]
+        #[allow(clippy::eq_op)]
+        fn test_add_neg_sub_mul() {
+            let x = F::rand();
+            let y = F::rand();
+            let z = F::rand();
+            assert_eq!(x + (-x), F::ZERO);
+            assert_eq!(x + x, x * F::TWO);
+            assert_eq!(x * y, y * x);
+            assert_eq!(x * (y * z), (x * y) * z);
+            assert_eq!(x * (y + z), x * y + x * z);
+        }
+
+        #[test]
+        fn test_inv_div() {
+            let x = F::rand();
+            let y = F::rand();
+            let z = F::rand();
+            assert_eq!(x * x.inverse(), F::ONE);
+            assert_eq!((x / y) * y, x);
+            assert_eq!(x / (y * z), (x / y) / z);
+        }
+
+        #[test]
+        fn test_modulus() {
+            let x = Mersenne31QuinticExtension([
+                Mersenne31Field::ZERO,
+                Mersenne31Field::ONE,
+                Mersenne31Field::ZERO,
+                Mersenne31Field::ZERO,
+                Mersenne31Field::ZERO,
+            ]);
+            assert_eq!(x.exp_u64(5), x + F::from_canonical_u64(6));
+        }
+
+        #[test]
+        fn test_field_order() {
+            let x = F::rand();
+            assert_eq!(x.exp_biguint(&(F::order() - 1u8)), F::ONE);
+        }
+
+        #[test]
+        fn test_power_of_two_gen() {
+            assert_eq!(
+                F::MULTIPLICATIVE_GROUP_GENERATOR.exp_biguint(&(F::order() >> F::TWO_ADICITY)),
+                F::POWER_OF_TWO_GENERATOR,
+            );
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use core::fmt::{self, Debug, Display, Formatter};
+use core::hash::Hash;
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num::{BigUint, Integer, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Field, Field64, PrimeField, PrimeField64, Sample};
+
+/// The Mersenne31 field, whose order is the Mersenne prime `2^31 - 1`.
+///
+/// Reduction modulo a Mersenne prime is particularly cheap. However, the two-adicity of this
+/// field is only 1, so it is not suitable for FFTs over its multiplicative subgroups.
+/// Elements are always stored in canonical form.
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[repr(transparent)]
+pub struct Mersenne31Field(pub u32);
+
+impl Mersenne31Field {
+    const ORDER_U32: u32 = (1 << 31) - 1;
+}
+
+impl Display for Mersenne31Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Debug for Mersenne31Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Sample for Mersenne31Field {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        use rand::Rng;
+        Self::from_canonical_u64(rng.gen_range(0..Self::ORDER))
+    }
+}
+
+impl Field for Mersenne31Field {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+    const TWO: Self = Self(2);
+    const NEG_ONE: Self = Self(Self::ORDER_U32 - 1);
+
+    const TWO_ADICITY: usize = 1;
+    const CHARACTERISTIC_TWO_ADICITY: usize = Self::TWO_ADICITY;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: Self = Self(7);
+
+    // The only element of order 2 is -1.
+    const POWER_OF_TWO_GENERATOR: Self = Self::NEG_ONE;
+
+    const BITS: usize = 31;
+
+    fn order() -> BigUint {
+        Self::ORDER.into()
+    }
+    fn characteristic() -> BigUint {
+        Self::order()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // Fermat's little theorem.
+        Some(self.exp_u64(Self::ORDER - 2))
+    }
+
+    fn from_noncanonical_biguint(n: BigUint) -> Self {
+        Self(n.mod_floor(&Self::order()).to_u32().unwrap())
+    }
+
+    #[inline(always)]
+    fn from_canonical_u64(n: u64) -> Self {
+        debug_assert!(n < Self::ORDER);
+        Self(n as u32)
+    }
+
+    fn from_noncanonical_u128(n: u128) -> Self {
+        Self((n % Self::ORDER as u128) as u32)
+    }
+
+    #[inline]
+    fn from_noncanonical_u64(n: u64) -> Self {
+        // Since `2^31 = 1 mod p`, we can fold the high bits onto the low ones. After two folds,
+        // the value is at most `p + 4`, so at most one subtraction is needed.
+        let n = (n & Self::ORDER) + (n >> 31);
+        let n = (n & Self::ORDER) + (n >> 31);
+        Self::from_canonical_u64(if n >= Self::ORDER { n - Self::ORDER } else { n })
+    }
+
+    #[inline]
+    fn from_noncanonical_i64(n: i64) -> Self {
+        Self(n.rem_euclid(Self::ORDER as i64) as u32)
+    }
+}
+
+impl PrimeField for Mersenne31Field {
+    fn to_canonical_biguint(&self) -> BigUint {
+        self.0.into()
+    }
+}
+
+impl Field64 for Mersenne31Field {
+    const ORDER: u64 = Self::ORDER_U32 as u64;
+}
+
+impl PrimeField64 for Mersenne31Field {
+    #[inline]
+    fn to_canonical_u64(&self) -> u64 {
+        self.0 as u64
+    }
+
+    #[inline(always)]
+    fn to_noncanonical_u64(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+impl Neg for Mersenne31Field {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        if self.is_zero() {
+            Self::ZERO
+        } else {
+            Self(Self::ORDER_U32 - self.0)
+        }
+    }
+}
+
+impl Add for Mersenne31Field {
+    type Output = Self;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self {
+        // Both summands are less than 2^31, so this cannot overflow.
+        let sum = self.0 + rhs.0;
+        if sum >= Self::ORDER_U32 {
+            Self(sum - Self::ORDER_U32)
+        } else {
+            Self(sum)
+        }
+    }
+}
+
+impl AddAssign for Mersenne31Field {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Mersenne31Field {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Sub for Mersenne31Field {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let (diff, under) = self.0.overflowing_sub(rhs.0);
+        if under {
+            Self(diff.wrapping_add(Self::ORDER_U32))
+        } else {
+            Self(diff)
+        }
+    }
+}
+
+impl SubAssign for Mersenne31Field {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Mersenne31Field {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self::from_noncanonical_u64(self.0 as u64 * rhs.0 as u64)
+    }
+}
+
+impl MulAssign for Mersenne31Field {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl Product for Mersenne31Field {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Div for Mersenne31Field {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl DivAssign for Mersenne31Field {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_field_arithmetic, test_prime_field_arithmetic};
+
+    test_prime_field_arithmetic!(crate::mersenne31_field::Mersenne31Field, small);
+    test_field_arithmetic!(crate::mersenne31_field::Mersenne31Field);
+}
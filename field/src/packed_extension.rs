@@ -0,0 +1,466 @@
+use core::fmt::Debug;
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+use core::slice;
+
+use crate::extension::quadratic::QuadraticExtension;
+use crate::extension::quartic::QuarticExtension;
+use crate::extension::quintic::QuinticExtension;
+use crate::extension::{Extendable, FieldExtension};
+use crate::packed::PackedField;
+use crate::types::Field;
+
+/// `P::WIDTH` elements of the degree `D` extension of `P::Scalar`, packed so that arithmetic on
+/// them is vectorized by `P`.
+///
+/// To satisfy the layout requirements of `PackedField`, the elements are stored one after the
+/// other, so that a `PackedExtension` can be cast to and from `[F::Extension; P::WIDTH]`.
+/// Multiplication transposes its operands so that each of the `D` vectors holds one coefficient
+/// of every element, computes the product on these vectors, and transposes the result back.
+#[derive(Copy, Clone, Debug)]
+#[repr(transparent)]
+pub struct PackedExtension<P: PackedField, const D: usize>([P; D]);
+
+pub type PackedQuadraticExtension<P> = PackedExtension<P, 2>;
+pub type PackedQuarticExtension<P> = PackedExtension<P, 4>;
+pub type PackedQuinticExtension<P> = PackedExtension<P, 5>;
+
+impl<P: PackedField, const D: usize> PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    /// The packed element whose every entry is the extension element with the given coefficients.
+    fn broadcast(coeffs: [P::Scalar; D]) -> Self {
+        Self::from_coefficient_vectors(coeffs.map(P::from))
+    }
+
+    /// Returns `D` vectors holding the coefficients of each entry. The entries may be permuted,
+    /// in the same way for all packed elements, which is undone by `from_coefficient_vectors`.
+    fn to_coefficient_vectors(self) -> [P; D] {
+        if D == 2 {
+            // The coefficients of each entry are adjacent, so interleaving pairs of adjacent
+            // scalars separates them.
+            let (c0, c1) = self.0[0].interleave(self.0[1], 1);
+            let mut coeffs = self.0;
+            (coeffs[0], coeffs[1]) = (c0, c1);
+            return coeffs;
+        }
+
+        let mut coeffs = [P::ZEROS; D];
+        for (i, &x) in self.0.iter().flat_map(|p| p.as_slice()).enumerate() {
+            coeffs[i % D].as_slice_mut()[i / D] = x;
+        }
+        coeffs
+    }
+
+    /// The inverse of `to_coefficient_vectors`.
+    fn from_coefficient_vectors(coeffs: [P; D]) -> Self {
+        if D == 2 {
+            // `interleave` is its own inverse.
+            let (p0, p1) = coeffs[0].interleave(coeffs[1], 1);
+            let mut res = coeffs;
+            (res[0], res[1]) = (p0, p1);
+            return Self(res);
+        }
+
+        let mut res = [P::ZEROS; D];
+        for (i, x) in res.iter_mut().flat_map(|p| p.as_slice_mut()).enumerate() {
+            *x = coeffs[i % D].as_slice()[i / D];
+        }
+        Self(res)
+    }
+
+    /// The entries of `P::ONES` laid out as extension elements, i.e. `[1, 0, ..., 0, 1, 0, ...]`.
+    const ONES: Self = {
+        let mut res = [P::ZEROS; D];
+        let ptr = (&mut res as *mut [P; D]).cast::<P::Scalar>();
+        let mut i = 0;
+        while i < P::WIDTH {
+            // SAFETY: `P` can be cast to `[P::Scalar; P::WIDTH]`, so `res` holds `D * P::WIDTH`
+            // scalars.
+            unsafe {
+                ptr.add(i * D).write(P::Scalar::ONE);
+            }
+            i += 1;
+        }
+        Self(res)
+    };
+}
+
+impl<P: PackedField, const D: usize> Default for PackedExtension<P, D> {
+    fn default() -> Self {
+        Self([P::ZEROS; D])
+    }
+}
+
+impl<P: PackedField, const D: usize> Add for PackedExtension<P, D> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(core::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl<P: PackedField, const D: usize> AddAssign for PackedExtension<P, D> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<P: PackedField, const D: usize> Sub for PackedExtension<P, D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(core::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl<P: PackedField, const D: usize> SubAssign for PackedExtension<P, D> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<P: PackedField, const D: usize> Neg for PackedExtension<P, D> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0.map(|p| -p))
+    }
+}
+
+impl<P: PackedField, const D: usize> Mul for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let a = self.to_coefficient_vectors();
+        let b = rhs.to_coefficient_vectors();
+
+        // Reduce modulo `X^D - W`.
+        let mut lo = [P::ZEROS; D];
+        let mut hi = [P::ZEROS; D];
+        for i in 0..D {
+            for j in 0..D {
+                if i + j < D {
+                    lo[i + j] += a[i] * b[j];
+                } else {
+                    hi[i + j - D] += a[i] * b[j];
+                }
+            }
+        }
+        let w = <P::Scalar as Extendable<D>>::W;
+        for (l, h) in lo.iter_mut().zip(hi) {
+            *l += h * w;
+        }
+
+        Self::from_coefficient_vectors(lo)
+    }
+}
+
+impl<P: PackedField, const D: usize> MulAssign for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<P: PackedField, const D: usize> Sum for PackedExtension<P, D> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, x| acc + x)
+    }
+}
+
+impl<P: PackedField, const D: usize> Product for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONES, |acc, x| acc * x)
+    }
+}
+
+/// Implements `PackedField` for the packing of a particular extension type. The operations with
+/// scalars cannot be implemented for `F::Extension` generically, since their `Self` type (or trait
+/// parameter) would be an associated type.
+macro_rules! impl_packed_extension {
+    ($ext:ident, $d:literal) => {
+        unsafe impl<P: PackedField> PackedField for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Scalar = $ext<P::Scalar>;
+
+            const WIDTH: usize = P::WIDTH;
+            const ZEROS: Self = Self([P::ZEROS; $d]);
+            const ONES: Self = Self::ONES;
+
+            fn from_slice(slice: &[Self::Scalar]) -> &Self {
+                assert_eq!(slice.len(), Self::WIDTH);
+                unsafe { &*slice.as_ptr().cast() }
+            }
+            fn from_slice_mut(slice: &mut [Self::Scalar]) -> &mut Self {
+                assert_eq!(slice.len(), Self::WIDTH);
+                unsafe { &mut *slice.as_mut_ptr().cast() }
+            }
+            fn as_slice(&self) -> &[Self::Scalar] {
+                unsafe { slice::from_raw_parts((self as *const Self).cast(), Self::WIDTH) }
+            }
+            fn as_slice_mut(&mut self) -> &mut [Self::Scalar] {
+                unsafe { slice::from_raw_parts_mut((self as *mut Self).cast(), Self::WIDTH) }
+            }
+
+            fn interleave(&self, other: Self, block_len: usize) -> (Self, Self) {
+                assert!(
+                    block_len.is_power_of_two() && block_len <= Self::WIDTH,
+                    "unsupported block length"
+                );
+                if block_len == Self::WIDTH {
+                    return (*self, other);
+                }
+
+                let (a, b) = (self.as_slice(), other.as_slice());
+                let mut res0 = Self::ZEROS;
+                let mut res1 = Self::ZEROS;
+                for (i, (x, y)) in res0
+                    .as_slice_mut()
+                    .iter_mut()
+                    .zip(res1.as_slice_mut())
+                    .enumerate()
+                {
+                    (*x, *y) = if (i / block_len) % 2 == 0 {
+                        (a[i], a[i + block_len])
+                    } else {
+                        (b[i - block_len], b[i])
+                    };
+                }
+                (res0, res1)
+            }
+        }
+
+        impl<P: PackedField> From<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            fn from(x: $ext<P::Scalar>) -> Self {
+                Self::broadcast(x.to_basefield_array())
+            }
+        }
+
+        impl<P: PackedField> Add<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = Self;
+
+            fn add(self, rhs: $ext<P::Scalar>) -> Self {
+                self + Self::from(rhs)
+            }
+        }
+
+        impl<P: PackedField> Add<PackedExtension<P, $d>> for $ext<P::Scalar>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = PackedExtension<P, $d>;
+
+            fn add(self, rhs: Self::Output) -> Self::Output {
+                rhs + self
+            }
+        }
+
+        impl<P: PackedField> AddAssign<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            fn add_assign(&mut self, rhs: $ext<P::Scalar>) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<P: PackedField> Sub<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = Self;
+
+            fn sub(self, rhs: $ext<P::Scalar>) -> Self {
+                self - Self::from(rhs)
+            }
+        }
+
+        impl<P: PackedField> Sub<PackedExtension<P, $d>> for $ext<P::Scalar>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = PackedExtension<P, $d>;
+
+            fn sub(self, rhs: Self::Output) -> Self::Output {
+                Self::Output::from(self) - rhs
+            }
+        }
+
+        impl<P: PackedField> SubAssign<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            fn sub_assign(&mut self, rhs: $ext<P::Scalar>) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<P: PackedField> Mul<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = Self;
+
+            fn mul(self, rhs: $ext<P::Scalar>) -> Self {
+                self * Self::from(rhs)
+            }
+        }
+
+        impl<P: PackedField> Mul<PackedExtension<P, $d>> for $ext<P::Scalar>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = PackedExtension<P, $d>;
+
+            fn mul(self, rhs: Self::Output) -> Self::Output {
+                rhs * self
+            }
+        }
+
+        impl<P: PackedField> MulAssign<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            fn mul_assign(&mut self, rhs: $ext<P::Scalar>) {
+                *self = *self * rhs;
+            }
+        }
+
+        impl<P: PackedField> Div<$ext<P::Scalar>> for PackedExtension<P, $d>
+        where
+            P::Scalar: Extendable<$d>,
+        {
+            type Output = Self;
+
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn div(self, rhs: $ext<P::Scalar>) -> Self {
+                self * rhs.inverse()
+            }
+        }
+    };
+}
+
+impl_packed_extension!(QuadraticExtension, 2);
+impl_packed_extension!(QuarticExtension, 4);
+impl_packed_extension!(QuinticExtension, 5);
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::ops::Square;
+    use crate::packable::Packable;
+    use crate::packed::PackedField;
+    use crate::packed_extension::PackedExtension;
+    use crate::types::Sample;
+
+    type P = <GoldilocksField as Packable>::Packing;
+
+    fn test_packed_extension<PE: PackedField>() {
+        let n = PE::WIDTH;
+        let xs = PE::Scalar::rand_vec(n);
+        let ys = PE::Scalar::rand_vec(n);
+        let s = PE::Scalar::rand();
+        let x = *PE::from_slice(&xs);
+        let y = *PE::from_slice(&ys);
+
+        let check = |packed: PE, f: &dyn Fn(PE::Scalar, PE::Scalar) -> PE::Scalar| {
+            let expected = xs
+                .iter()
+                .zip(&ys)
+                .map(|(&x, &y)| f(x, y))
+                .collect::<Vec<_>>();
+            assert_eq!(packed.as_slice(), expected);
+        };
+        check(x + y, &|x, y| x + y);
+        check(x - y, &|x, y| x - y);
+        check(x * y, &|x, y| x * y);
+        check(-x, &|x, _| -x);
+        check(x.square(), &|x, _| x.square());
+        check(x + s, &|x, _| x + s);
+        check(s - x, &|x, _| s - x);
+        check(x * s, &|x, _| x * s);
+        check(x / s, &|x, _| x / s);
+        check(PE::ONES * x, &|x, _| x);
+        check([x, y].into_iter().product(), &|x, y| x * y);
+
+        for block_len in (0..).map(|i| 1 << i).take_while(|&b| b <= n) {
+            let (a, b) = x.interleave(y, block_len);
+            let (c, d) = a.interleave(b, block_len);
+            assert_eq!(c.as_slice(), x.as_slice());
+            assert_eq!(d.as_slice(), y.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_packed_quadratic_extension() {
+        test_packed_extension::<PackedExtension<P, 2>>();
+        test_packed_extension::<PackedExtension<GoldilocksField, 2>>();
+    }
+
+    #[test]
+    fn test_packed_quartic_extension() {
+        test_packed_extension::<PackedExtension<P, 4>>();
+    }
+
+    #[test]
+    fn test_packed_quintic_extension() {
+        test_packed_extension::<PackedExtension<P, 5>>();
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        target_feature = "avx2",
+        not(all(
+            target_feature = "avx512bw",
+            target_feature = "avx512cd",
+            target_feature = "avx512dq",
+            target_feature = "avx512f",
+            target_feature = "avx512vl"
+        ))
+    ))]
+    #[test]
+    fn test_avx2_packed_extensions() {
+        use crate::arch::x86_64::avx2_goldilocks_field::Avx2GoldilocksField;
+
+        test_packed_extension::<PackedExtension<Avx2GoldilocksField, 2>>();
+        test_packed_extension::<PackedExtension<Avx2GoldilocksField, 4>>();
+        test_packed_extension::<PackedExtension<Avx2GoldilocksField, 5>>();
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        target_feature = "avx512bw",
+        target_feature = "avx512cd",
+        target_feature = "avx512dq",
+        target_feature = "avx512f",
+        target_feature = "avx512vl"
+    ))]
+    #[test]
+    fn test_avx512_packed_extensions() {
+        use crate::arch::x86_64::avx512_goldilocks_field::Avx512GoldilocksField;
+
+        test_packed_extension::<PackedExtension<Avx512GoldilocksField, 2>>();
+        test_packed_extension::<PackedExtension<Avx512GoldilocksField, 4>>();
+        test_packed_extension::<PackedExtension<Avx512GoldilocksField, 5>>();
+    }
+}
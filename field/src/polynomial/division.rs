@@ -1,8 +1,6 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
-use plonky2_util::log2_ceil;
-
 use crate::polynomial::PolynomialCoeffs;
 use crate::types::Field;
 
@@ -88,47 +86,33 @@ impl<F: Field> PolynomialCoeffs<F> {
     }
 
     /// Computes the inverse of `self` modulo `x^n`.
+    ///
+    /// This uses Newton iteration: if `a` is the inverse of `self` modulo `x^l`, then
+    /// `a (2 - self a)` is its inverse modulo `x^{2l}`. Each step costs a constant number of
+    /// multiplications of polynomials of length `O(l)`, for a total cost of `O(n log n)`.
     pub fn inv_mod_xn(&self, n: usize) -> Self {
         assert!(n > 0, "`n` needs to be nonzero");
         assert!(self.coeffs[0].is_nonzero(), "Inverse doesn't exist.");
 
-        // If polynomial is constant, return the inverse of the constant.
-        if self.degree_plus_one() == 1 {
-            return Self::new(vec![self.coeffs[0].inverse()]);
-        }
-
-        let h = if self.len() < n {
-            self.padded(n)
-        } else {
-            self.clone()
-        };
+        let mut inv = vec![self.coeffs[0].inverse()];
+        while inv.len() < n {
+            let l = inv.len();
+            let a = Self::new(inv.clone());
+            let h = Self::new(self.coeffs[..self.len().min(2 * l)].to_vec());
 
-        let mut a = Self::empty();
-        a.coeffs.push(h.coeffs[0].inverse());
-        for i in 0..log2_ceil(n) {
-            let l = 1 << i;
-            let h0 = h.coeffs[..l].to_vec().into();
-            let mut h1: Self = h.coeffs[l..].to_vec().into();
-            let mut c = &a * &h0;
-            if l == c.len() {
-                c = Self::zero(1);
-            } else {
-                c.coeffs.drain(0..l);
-            }
-            h1.trim();
-            let mut tmp = &a * &h1;
-            tmp = &tmp + &c;
-            tmp.coeffs.iter_mut().for_each(|x| *x = -(*x));
-            tmp.trim();
-            let mut b = &a * &tmp;
-            b.trim();
-            if b.len() > l {
-                b.coeffs.drain(l..);
-            }
-            a.coeffs.extend_from_slice(&b.coeffs);
+            // `a h = 1 + e x^l mod x^{2l}`, so the next `l` coefficients of the inverse are those of
+            // `-a e mod x^l`.
+            let ah = &a * &h;
+            let e = Self::new(
+                (l..2 * l)
+                    .map(|i| ah.coeffs.get(i).copied().unwrap_or(F::ZERO))
+                    .collect(),
+            );
+            let ae = &a * &e;
+            inv.extend((0..l).map(|i| -ae.coeffs.get(i).copied().unwrap_or(F::ZERO)));
         }
-        a.coeffs.drain(n..);
-        a
+        inv.truncate(n);
+        Self::new(inv)
     }
 }
 
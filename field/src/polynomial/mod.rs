@@ -1,4 +1,6 @@
 pub(crate) mod division;
+pub mod multilinear;
+pub mod subproduct_tree;
 
 use alloc::vec;
 use alloc::vec::Vec;
@@ -15,6 +17,10 @@ use crate::extension::{Extendable, FieldExtension};
 use crate::fft::{fft, fft_with_options, ifft, FftRootTable};
 use crate::types::Field;
 
+/// Products in which one of the polynomials has at most this many coefficients are computed by
+/// the schoolbook method rather than by FFTs.
+const SCHOOLBOOK_MUL_MAX_LEN: usize = 32;
+
 /// A polynomial in point-value form.
 ///
 /// The points are implicitly `g^i`, where `g` generates the subgroup whose size equals the number
@@ -259,6 +265,33 @@ impl<F: Field> PolynomialCoeffs<F> {
         Self::new(self.trimmed().coeffs.into_iter().rev().collect())
     }
 
+    /// Multiplies two polynomials by the quadratic schoolbook method, which beats the FFT when one
+    /// of them is short.
+    fn mul_schoolbook(&self, rhs: &Self) -> Self {
+        if self.coeffs.is_empty() || rhs.coeffs.is_empty() {
+            return Self::empty();
+        }
+        let mut coeffs = vec![F::ZERO; self.len() + rhs.len() - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (c, &b) in coeffs[i..].iter_mut().zip(&rhs.coeffs) {
+                *c += a * b;
+            }
+        }
+        Self::new(coeffs)
+    }
+
+    /// The formal derivative of this polynomial.
+    pub fn derivative(&self) -> Self {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &c)| F::from_canonical_usize(i) * c)
+            .collect();
+        Self::new(coeffs)
+    }
+
     pub fn fft(self) -> PolynomialValues<F> {
         fft(self)
     }
@@ -420,7 +453,12 @@ impl<F: Field> Mul for &PolynomialCoeffs<F> {
 
     #[allow(clippy::suspicious_arithmetic_impl)]
     fn mul(self, rhs: Self) -> Self::Output {
-        let new_len = (self.len() + rhs.len()).next_power_of_two();
+        if self.len().min(rhs.len()) <= SCHOOLBOOK_MUL_MAX_LEN {
+            return self.mul_schoolbook(rhs);
+        }
+
+        let product_len = self.len() + rhs.len() - 1;
+        let new_len = product_len.next_power_of_two();
         let a = self.padded(new_len);
         let b = rhs.padded(new_len);
         let a_evals = a.fft();
@@ -432,7 +470,10 @@ impl<F: Field> Mul for &PolynomialCoeffs<F> {
             .zip(b_evals.values)
             .map(|(pa, pb)| pa * pb)
             .collect();
-        ifft(mul_evals.into())
+        let mut product = ifft(mul_evals.into());
+        // Drop the zero padding, so that the length matches the schoolbook method.
+        product.coeffs.truncate(product_len);
+        product
     }
 }
 
@@ -524,6 +565,7 @@ mod tests {
         let b = PolynomialCoeffs::new(F::rand_vec(b_deg));
         let m1 = &a * &b;
         let m2 = &a * &b;
+        assert_eq!(m1.len(), a_deg + b_deg - 1);
         for _ in 0..1000 {
             let x = F::rand();
             assert_eq!(m1.eval(x), a.eval(x) * b.eval(x));
@@ -0,0 +1,165 @@
+use alloc::vec::Vec;
+
+use plonky2_util::log2_strict;
+use serde::{Deserialize, Serialize};
+
+use crate::extension::Extendable;
+use crate::types::Field;
+
+/// A multilinear polynomial in `n` variables, given by its evaluations on the boolean hypercube
+/// `{0, 1}^n`.
+///
+/// The evaluation at `(x_0, ..., x_{n-1})` is stored at the index whose binary representation is
+/// `x_0 x_1 ... x_{n-1}`, i.e. `x_0` is the most significant bit.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MultilinearPolynomial<F: Field> {
+    pub evals: Vec<F>,
+}
+
+impl<F: Field> MultilinearPolynomial<F> {
+    pub fn new(evals: Vec<F>) -> Self {
+        assert!(
+            evals.len().is_power_of_two(),
+            "The number of evaluations must be a power of two"
+        );
+        Self { evals }
+    }
+
+    pub fn num_vars(&self) -> usize {
+        log2_strict(self.evals.len())
+    }
+
+    /// The polynomial `eq(point, X) = prod_i (point_i X_i + (1 - point_i) (1 - X_i))`, which is
+    /// the multilinear extension of the indicator function of `point` when `point` is boolean.
+    pub fn eq(point: &[F]) -> Self {
+        let mut evals = Vec::with_capacity(1 << point.len());
+        evals.push(F::ONE);
+        for &r in point {
+            // Each variable becomes the new least significant bit, so that the first one ends up
+            // being the most significant.
+            evals = evals
+                .into_iter()
+                .flat_map(|e| {
+                    let e_r = e * r;
+                    [e - e_r, e_r]
+                })
+                .collect();
+        }
+        Self { evals }
+    }
+
+    /// The sum of the evaluations of the polynomial over the boolean hypercube.
+    pub fn sum(&self) -> F {
+        self.evals.iter().copied().sum()
+    }
+
+    /// Substitutes `r` for the first variable, returning a polynomial in the remaining ones.
+    pub fn fix_first_variable(&self, r: F) -> Self {
+        let mut res = self.clone();
+        res.fix_first_variable_in_place(r);
+        res
+    }
+
+    /// Substitutes `r` for the first variable in place.
+    pub fn fix_first_variable_in_place(&mut self, r: F) {
+        assert!(self.evals.len() > 1, "The polynomial has no variables left");
+        let half = self.evals.len() / 2;
+        let (lo, hi) = self.evals.split_at_mut(half);
+        for (l, &h) in lo.iter_mut().zip(hi.iter()) {
+            *l += r * (h - *l);
+        }
+        self.evals.truncate(half);
+    }
+
+    /// Evaluates the polynomial at `point`, which must have one coordinate per variable.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars());
+        let Some((&r, rest)) = point.split_first() else {
+            return self.evals[0];
+        };
+        let mut poly = self.fix_first_variable(r);
+        for &r in rest {
+            poly.fix_first_variable_in_place(r);
+        }
+        poly.evals[0]
+    }
+
+    pub fn to_extension<const D: usize>(&self) -> MultilinearPolynomial<F::Extension>
+    where
+        F: Extendable<D>,
+    {
+        MultilinearPolynomial::new(self.evals.iter().map(|&e| e.into()).collect())
+    }
+}
+
+impl<F: Field> From<Vec<F>> for MultilinearPolynomial<F> {
+    fn from(evals: Vec<F>) -> Self {
+        Self::new(evals)
+    }
+}
+
+/// Evaluates `eq(a, b) = prod_i (a_i b_i + (1 - a_i) (1 - b_i))`.
+pub fn eval_eq<F: Field>(a: &[F], b: &[F]) -> F {
+    assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b)
+        .map(|(&a_i, &b_i)| a_i * b_i + (F::ONE - a_i) * (F::ONE - b_i))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::types::Sample;
+
+    type F = GoldilocksField;
+
+    /// Returns the boolean point whose coordinates are the bits of `index`, most significant first.
+    fn hypercube_point<F: Field>(index: usize, num_vars: usize) -> Vec<F> {
+        (0..num_vars)
+            .rev()
+            .map(|i| F::from_bool((index >> i) & 1 == 1))
+            .collect()
+    }
+
+    #[test]
+    fn evaluate_on_hypercube() {
+        let poly = MultilinearPolynomial::new(F::rand_vec(1 << 5));
+        for (i, &e) in poly.evals.iter().enumerate() {
+            assert_eq!(poly.evaluate(&hypercube_point(i, 5)), e);
+        }
+    }
+
+    #[test]
+    fn fix_first_variable() {
+        let poly = MultilinearPolynomial::new(F::rand_vec(1 << 6));
+        let point = F::rand_vec(6);
+        let fixed = poly.fix_first_variable(point[0]);
+        assert_eq!(fixed.num_vars(), 5);
+        assert_eq!(fixed.evaluate(&point[1..]), poly.evaluate(&point));
+
+        // The polynomial is linear in each variable.
+        let mut other_point = point.clone();
+        other_point[0] = F::ONE - point[0];
+        assert_eq!(
+            poly.evaluate(&point) + poly.evaluate(&other_point),
+            poly.evaluate(&[vec![F::ZERO], point[1..].to_vec()].concat())
+                + poly.evaluate(&[vec![F::ONE], point[1..].to_vec()].concat())
+        );
+    }
+
+    #[test]
+    fn eq() {
+        let r = F::rand_vec(4);
+        let eq = MultilinearPolynomial::eq(&r);
+        assert_eq!(eq.sum(), F::ONE);
+        for i in 0..1 << 4 {
+            assert_eq!(eq.evals[i], eval_eq(&r, &hypercube_point(i, 4)));
+        }
+
+        let x = F::rand_vec(4);
+        assert_eq!(eq.evaluate(&x), eval_eq(&r, &x));
+    }
+}
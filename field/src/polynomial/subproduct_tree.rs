@@ -0,0 +1,201 @@
+//! Fast multipoint evaluation and interpolation on arbitrary sets of points, using subproduct trees.
+//! See e.g. chapter 10 of "Modern Computer Algebra" by von zur Gathen and Gerhard.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::polynomial::PolynomialCoeffs;
+use crate::types::Field;
+
+/// The number of points handled by each leaf of a subproduct tree. Below this size, quadratic
+/// algorithms are faster than recursing further.
+const LEAF_SIZE: usize = 32;
+
+/// A binary tree of products of the polynomials `X - x_i`, for a list of points `x_i`.
+///
+/// Building the tree costs `O(M(n) log n)`, where `M(n)` is the cost of multiplying two
+/// polynomials of degree `n`. It can then be used to evaluate polynomials on the points, or to
+/// interpolate values on the points, for the same cost.
+#[derive(Clone, Debug)]
+pub struct SubproductTree<F: Field> {
+    points: Vec<F>,
+    /// The first layer holds the vanishing polynomials of consecutive chunks of `LEAF_SIZE`
+    /// points. Each other layer holds the products of consecutive pairs of polynomials of the
+    /// previous layer, with the last polynomial carried over unchanged if there is an odd number
+    /// of them. The last layer holds the vanishing polynomial of all points.
+    layers: Vec<Vec<PolynomialCoeffs<F>>>,
+}
+
+impl<F: Field> SubproductTree<F> {
+    pub fn new(points: &[F]) -> Self {
+        let leaves = points
+            .chunks(LEAF_SIZE)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(PolynomialCoeffs::new(vec![F::ONE]), |acc, &x| {
+                        &acc * &PolynomialCoeffs::new(vec![-x, F::ONE])
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let mut layers = vec![leaves];
+        while layers.last().unwrap().len() > 1 {
+            let next_layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => {
+                        let mut product = a * b;
+                        product.trim();
+                        product
+                    }
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next_layer);
+        }
+
+        Self {
+            points: points.to_vec(),
+            layers,
+        }
+    }
+
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// The polynomial of degree `n` vanishing on all `n` points.
+    pub fn vanishing_poly(&self) -> PolynomialCoeffs<F> {
+        self.layers
+            .last()
+            .unwrap()
+            .first()
+            .cloned()
+            .unwrap_or_else(|| PolynomialCoeffs::new(vec![F::ONE]))
+    }
+
+    /// Evaluates `poly` on all points.
+    pub fn evaluate(&self, poly: &PolynomialCoeffs<F>) -> Vec<F> {
+        // Going down the tree, reduce `poly` modulo the polynomial of each node. The remainder
+        // agrees with `poly` on the points below that node.
+        let mut remainders = vec![poly.clone()];
+        for layer in self.layers.iter().rev() {
+            remainders = layer
+                .iter()
+                .enumerate()
+                .map(|(i, m)| remainders[i / 2].div_rem(m).1)
+                .collect();
+        }
+
+        self.points
+            .chunks(LEAF_SIZE)
+            .zip(remainders)
+            .flat_map(|(chunk, r)| chunk.iter().map(move |&x| r.eval(x)))
+            .collect()
+    }
+
+    /// Returns the unique polynomial of degree less than `n` taking the given values on the `n`
+    /// points, which must be distinct.
+    pub fn interpolate(&self, values: &[F]) -> PolynomialCoeffs<F> {
+        assert_eq!(values.len(), self.points.len());
+        if values.is_empty() {
+            return PolynomialCoeffs::empty();
+        }
+
+        // The interpolant is `sum_i c_i M(X) / (X - x_i)`, where `M` is the vanishing polynomial and
+        // `c_i = y_i / M'(x_i)`.
+        let weights =
+            F::batch_multiplicative_inverse(&self.evaluate(&self.vanishing_poly().derivative()));
+        let scaled_values = values
+            .iter()
+            .zip(weights)
+            .map(|(&y, w)| y * w)
+            .collect::<Vec<_>>();
+
+        // At the leaves, compute the sums directly, dividing by each `X - x_i` in linear time.
+        let mut sums = self
+            .points
+            .chunks(LEAF_SIZE)
+            .zip(scaled_values.chunks(LEAF_SIZE))
+            .zip(&self.layers[0])
+            .map(|((xs, cs), m)| {
+                let mut sum = PolynomialCoeffs::zero(xs.len());
+                for (&x, &c) in xs.iter().zip(cs) {
+                    let quotient = m.divide_by_linear(x);
+                    for (s, q) in sum.coeffs.iter_mut().zip(quotient.coeffs) {
+                        *s += c * q;
+                    }
+                }
+                sum
+            })
+            .collect::<Vec<_>>();
+
+        // Going up the tree, the sum over the points below a node is `s_0 m_1 + s_1 m_0`, where the
+        // `s_i` are the sums over its children and the `m_i` their polynomials.
+        for layer in &self.layers[..self.layers.len() - 1] {
+            sums = sums
+                .chunks(2)
+                .zip(layer.chunks(2))
+                .map(|pair| match pair {
+                    ([s0, s1], [m0, m1]) => &(s0 * m1) + &(s1 * m0),
+                    ([s0], _) => s0.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        let mut interpolant = sums.pop().unwrap();
+        interpolant.trim();
+        interpolant
+    }
+}
+
+impl<F: Field> PolynomialCoeffs<F> {
+    /// Evaluates the polynomial on each of the given points. To evaluate several polynomials on
+    /// the same points, build a [`SubproductTree`] once and use [`SubproductTree::evaluate`].
+    pub fn eval_many(&self, points: &[F]) -> Vec<F> {
+        SubproductTree::new(points).evaluate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::extension::quadratic::QuadraticExtension;
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::types::Sample;
+
+    fn test_subproduct_tree<F: Field + Sample>(num_points: usize, poly_len: usize) {
+        let points = F::rand_vec(num_points);
+        let tree = SubproductTree::new(&points);
+        let m = tree.vanishing_poly();
+        assert_eq!(m.degree_plus_one(), num_points + 1);
+        assert!(points.iter().all(|&x| m.eval(x).is_zero()));
+
+        let poly = PolynomialCoeffs::new(F::rand_vec(poly_len));
+        let evals = tree.evaluate(&poly);
+        let expected = points.iter().map(|&x| poly.eval(x)).collect::<Vec<_>>();
+        assert_eq!(evals, expected);
+
+        let values = F::rand_vec(num_points);
+        let interpolant = tree.interpolate(&values);
+        assert!(interpolant.len() <= num_points);
+        assert_eq!(tree.evaluate(&interpolant), values);
+    }
+
+    #[test]
+    fn subproduct_tree() {
+        type F = GoldilocksField;
+        for num_points in [0, 1, 2, 31, 32, 33, 100, 1000] {
+            test_subproduct_tree::<F>(num_points, OsRng.gen_range(0..2000));
+        }
+        test_subproduct_tree::<QuadraticExtension<F>>(300, 500);
+    }
+}
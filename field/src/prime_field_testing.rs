@@ -68,80 +68,93 @@ where
 
 #[macro_export]
 macro_rules! test_prime_field_arithmetic {
-    ($field:ty) => {
-        mod prime_field_arithmetic {
-            use core::ops::{Add, Mul, Neg, Sub};
-
-            use $crate::ops::Square;
-            use $crate::types::{Field, Field64};
-
-            #[test]
-            fn arithmetic_addition() {
-                let modulus = <$field>::ORDER;
-                $crate::prime_field_testing::run_binaryop_test_cases(<$field>::add, |x, y| {
-                    ((x as u128 + y as u128) % (modulus as u128)) as u64
-                })
-            }
+    (@common $field:ty) => {
+        use core::ops::{Add, Mul, Neg, Sub};
+
+        use $crate::ops::Square;
+        use $crate::types::{Field, Field64};
+
+        #[test]
+        fn arithmetic_addition() {
+            let modulus = <$field>::ORDER;
+            $crate::prime_field_testing::run_binaryop_test_cases(<$field>::add, |x, y| {
+                ((x as u128 + y as u128) % (modulus as u128)) as u64
+            })
+        }
 
-            #[test]
-            fn arithmetic_subtraction() {
-                let modulus = <$field>::ORDER;
-                $crate::prime_field_testing::run_binaryop_test_cases(<$field>::sub, |x, y| {
-                    if x >= y {
-                        x - y
-                    } else {
-                        modulus - y + x
-                    }
-                })
-            }
+        #[test]
+        fn arithmetic_subtraction() {
+            let modulus = <$field>::ORDER;
+            $crate::prime_field_testing::run_binaryop_test_cases(<$field>::sub, |x, y| {
+                if x >= y {
+                    x - y
+                } else {
+                    modulus - y + x
+                }
+            })
+        }
 
-            #[test]
-            fn arithmetic_negation() {
-                let modulus = <$field>::ORDER;
-                $crate::prime_field_testing::run_unaryop_test_cases(<$field>::neg, |x| {
-                    if x == 0 {
-                        0
-                    } else {
-                        modulus - x
-                    }
-                })
-            }
+        #[test]
+        fn arithmetic_negation() {
+            let modulus = <$field>::ORDER;
+            $crate::prime_field_testing::run_unaryop_test_cases(<$field>::neg, |x| {
+                if x == 0 {
+                    0
+                } else {
+                    modulus - x
+                }
+            })
+        }
 
-            #[test]
-            fn arithmetic_multiplication() {
-                let modulus = <$field>::ORDER;
-                $crate::prime_field_testing::run_binaryop_test_cases(<$field>::mul, |x, y| {
-                    ((x as u128) * (y as u128) % (modulus as u128)) as u64
-                })
-            }
+        #[test]
+        fn arithmetic_multiplication() {
+            let modulus = <$field>::ORDER;
+            $crate::prime_field_testing::run_binaryop_test_cases(<$field>::mul, |x, y| {
+                ((x as u128) * (y as u128) % (modulus as u128)) as u64
+            })
+        }
 
-            #[test]
-            fn arithmetic_square() {
-                let modulus = <$field>::ORDER;
-                $crate::prime_field_testing::run_unaryop_test_cases(
-                    |x: $field| x.square(),
-                    |x| ((x as u128 * x as u128) % (modulus as u128)) as u64,
-                )
-            }
+        #[test]
+        fn arithmetic_square() {
+            let modulus = <$field>::ORDER;
+            $crate::prime_field_testing::run_unaryop_test_cases(
+                |x: $field| x.square(),
+                |x| ((x as u128 * x as u128) % (modulus as u128)) as u64,
+            )
+        }
 
-            #[test]
-            fn inversion() {
-                let zero = <$field>::ZERO;
-                let one = <$field>::ONE;
-                let modulus = <$field>::ORDER;
+        #[test]
+        fn inversion() {
+            let zero = <$field>::ZERO;
+            let one = <$field>::ONE;
+            let modulus = <$field>::ORDER;
 
-                assert_eq!(zero.try_inverse(), None);
+            assert_eq!(zero.try_inverse(), None);
 
-                let inputs = $crate::prime_field_testing::test_inputs(modulus);
+            let inputs = $crate::prime_field_testing::test_inputs(modulus);
 
-                for x in inputs {
-                    if x != 0 {
-                        let x = <$field>::from_canonical_u64(x);
-                        let inv = x.inverse();
-                        assert_eq!(x * inv, one);
-                    }
+            for x in inputs {
+                if x != 0 {
+                    let x = <$field>::from_canonical_u64(x);
+                    let inv = x.inverse();
+                    assert_eq!(x * inv, one);
                 }
             }
+        }
+
+        #[test]
+        fn subtraction_double_wraparound() {
+            type F = $field;
+
+            let (a, b) = (F::from_canonical_u64(F::ORDER.div_ceil(2u64)), F::TWO);
+            let x = a * b;
+            assert_eq!(x, F::ONE);
+            assert_eq!(F::ZERO - x, F::NEG_ONE);
+        }
+    };
+    ($field:ty) => {
+        mod prime_field_arithmetic {
+            $crate::test_prime_field_arithmetic!(@common $field);
 
             #[test]
             fn inverse_2exp() {
@@ -157,26 +170,50 @@ macro_rules! test_prime_field_arithmetic {
             }
 
             #[test]
-            fn subtraction_double_wraparound() {
+            fn addition_double_wraparound() {
+                type F = $field;
+
+                let a = F::from_canonical_u64(u64::MAX - F::ORDER);
+                let b = F::NEG_ONE;
+
+                let c = (a + a) + (b + b);
+                let d = (a + b) + (a + b);
+
+                assert_eq!(c, d);
+            }
+        }
+    };
+    // Fields whose order fits in 32 bits. Their two-adicity may be below 2, and `u64::MAX - ORDER`
+    // is not a canonical element, so the edge cases above are replaced by 32-bit ones.
+    ($field:ty, small) => {
+        mod prime_field_arithmetic {
+            $crate::test_prime_field_arithmetic!(@common $field);
+
+            #[test]
+            fn inverse_2exp() {
                 type F = $field;
 
-                let (a, b) = (F::from_canonical_u64(F::ORDER.div_ceil(2u64)), F::TWO);
-                let x = a * b;
-                assert_eq!(x, F::ONE);
-                assert_eq!(F::ZERO - x, F::NEG_ONE);
+                let v = <F as Field>::TWO_ADICITY;
+
+                for e in [0, 1, 2, 3, 4, v.saturating_sub(2), v - 1, v, v + 1, v + 2, 123 * v] {
+                    let x = F::TWO.exp_u64(e as u64);
+                    let y = F::inverse_2exp(e);
+                    assert_eq!(x * y, F::ONE);
+                }
             }
 
             #[test]
             fn addition_double_wraparound() {
                 type F = $field;
 
-                let a = F::from_canonical_u64(u64::MAX - F::ORDER);
+                let a = F::from_canonical_u64(F::ORDER - 2);
                 let b = F::NEG_ONE;
 
                 let c = (a + a) + (b + b);
                 let d = (a + b) + (a + b);
 
                 assert_eq!(c, d);
+                assert_eq!(c, F::from_canonical_u64(F::ORDER - 6));
             }
         }
     };
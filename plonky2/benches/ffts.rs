@@ -18,6 +18,21 @@ pub(crate) fn bench_ffts<F: Field>(c: &mut Criterion) {
     }
 }
 
+/// Sizes large enough for `fft_with_options` to use the cache-friendly six-step FFT when several
+/// threads are available.
+pub(crate) fn bench_large_ffts<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("fft-large<{}>", type_name::<F>()));
+    group.sample_size(10);
+
+    for size_log in [18, 20, 22] {
+        let size = 1 << size_log;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            let coeffs = PolynomialCoeffs::new(F::rand_vec(size));
+            b.iter(|| coeffs.clone().fft_with_options(None, None));
+        });
+    }
+}
+
 pub(crate) fn bench_ldes<F: Field>(c: &mut Criterion) {
     const RATE_BITS: usize = 3;
 
@@ -39,6 +54,7 @@ pub(crate) fn bench_ldes<F: Field>(c: &mut Criterion) {
 
 fn criterion_benchmark(c: &mut Criterion) {
     bench_ffts::<GoldilocksField>(c);
+    bench_large_ffts::<GoldilocksField>(c);
     bench_ldes::<GoldilocksField>(c);
 }
 
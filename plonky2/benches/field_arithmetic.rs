@@ -1,10 +1,12 @@
 mod allocator;
 
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use plonky2::field::babybear_field::BabyBearField;
 use plonky2::field::extension::quadratic::QuadraticExtension;
 use plonky2::field::extension::quartic::QuarticExtension;
 use plonky2::field::extension::quintic::QuinticExtension;
 use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::mersenne31_field::Mersenne31Field;
 use plonky2::field::types::Field;
 use tynm::type_name;
 
@@ -176,6 +178,13 @@ fn criterion_benchmark(c: &mut Criterion) {
     bench_field::<QuadraticExtension<GoldilocksField>>(c);
     bench_field::<QuarticExtension<GoldilocksField>>(c);
     bench_field::<QuinticExtension<GoldilocksField>>(c);
+
+    bench_field::<BabyBearField>(c);
+    bench_field::<QuarticExtension<BabyBearField>>(c);
+    bench_field::<QuinticExtension<BabyBearField>>(c);
+
+    bench_field::<Mersenne31Field>(c);
+    bench_field::<QuadraticExtension<Mersenne31Field>>(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
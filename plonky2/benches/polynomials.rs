@@ -0,0 +1,74 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::interpolation::interpolant;
+use plonky2::field::polynomial::subproduct_tree::SubproductTree;
+use plonky2::field::polynomial::PolynomialCoeffs;
+use plonky2::field::types::Field;
+use tynm::type_name;
+
+pub(crate) fn bench_mul<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("poly-mul<{}>", type_name::<F>()));
+    group.sample_size(10);
+
+    for size_log in [12, 16, 20] {
+        let size = 1 << size_log;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            let p = PolynomialCoeffs::new(F::rand_vec(size));
+            let q = PolynomialCoeffs::new(F::rand_vec(size));
+            b.iter(|| &p * &q);
+        });
+    }
+}
+
+pub(crate) fn bench_div_rem<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("poly-div-rem<{}>", type_name::<F>()));
+    group.sample_size(10);
+
+    // Divide a polynomial of degree `2n` by one of degree `n`.
+    for size_log in [12, 16, 20] {
+        let size = 1 << size_log;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            let p = PolynomialCoeffs::new(F::rand_vec(2 * size));
+            let q = PolynomialCoeffs::new(F::rand_vec(size));
+            b.iter(|| p.div_rem(&q));
+        });
+    }
+}
+
+pub(crate) fn bench_multipoint<F: Field>(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("multipoint<{}>", type_name::<F>()));
+    group.sample_size(10);
+
+    for size_log in [10, 14, 16] {
+        let size = 1 << size_log;
+        let points = F::rand_vec(size);
+        group.bench_with_input(BenchmarkId::new("tree", size), &size, |b, _| {
+            b.iter(|| SubproductTree::new(&points));
+        });
+
+        let tree = SubproductTree::new(&points);
+        group.bench_with_input(BenchmarkId::new("evaluate", size), &size, |b, _| {
+            let p = PolynomialCoeffs::new(F::rand_vec(size));
+            b.iter(|| tree.evaluate(&p));
+        });
+        group.bench_with_input(BenchmarkId::new("interpolant", size), &size, |b, _| {
+            let points = points
+                .iter()
+                .copied()
+                .zip(F::rand_vec(size))
+                .collect::<Vec<_>>();
+            b.iter(|| interpolant(&points));
+        });
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_mul::<GoldilocksField>(c);
+    bench_div_rem::<GoldilocksField>(c);
+    bench_multipoint::<GoldilocksField>(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::curve::curve_multiplication::to_digits;
+use crate::curve::curve_types::{Curve, ProjectivePoint};
+use crate::field::types::Field;
+
+/// Computes `sum_i scalars[i] * points[i]` with Pippenger's bucket method, using windows of
+/// `window_bits` bits.
+pub fn msm<C: Curve>(
+    scalars: &[C::ScalarField],
+    points: &[ProjectivePoint<C>],
+    window_bits: usize,
+) -> ProjectivePoint<C> {
+    assert_eq!(scalars.len(), points.len());
+
+    let digits = scalars
+        .iter()
+        .map(|x| to_digits::<C>(x, window_bits))
+        .collect::<Vec<_>>();
+    let num_windows = C::ScalarField::BITS.div_ceil(window_bits);
+
+    let mut result = ProjectivePoint::ZERO;
+    for i in (0..num_windows).rev() {
+        for _ in 0..window_bits {
+            result = result.double();
+        }
+
+        // Bucket `j` accumulates the points whose digit in this window is `j + 1`.
+        let mut buckets = vec![ProjectivePoint::ZERO; (1 << window_bits) - 1];
+        for (point_digits, &point) in digits.iter().zip(points) {
+            let digit = point_digits[i];
+            if digit != 0 {
+                buckets[digit - 1] = buckets[digit - 1] + point;
+            }
+        }
+
+        // Compute `sum_j (j + 1) * buckets[j]` with running sums.
+        let mut running_sum = ProjectivePoint::ZERO;
+        let mut window_sum = ProjectivePoint::ZERO;
+        for &bucket in buckets.iter().rev() {
+            running_sum = running_sum + bucket;
+            window_sum = window_sum + running_sum;
+        }
+        result = result + window_sum;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::curve_msm::msm;
+    use crate::curve::curve_types::{Curve, CurveScalar, ProjectivePoint};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+
+    #[test]
+    fn test_msm() {
+        let scalars = Secp256K1Scalar::rand_vec(10);
+        let points = Secp256K1Scalar::rand_vec(10)
+            .into_iter()
+            .map(|x| CurveScalar(x) * Secp256K1::GENERATOR_PROJECTIVE)
+            .collect::<Vec<_>>();
+
+        let expected = scalars
+            .iter()
+            .zip(&points)
+            .fold(ProjectivePoint::ZERO, |acc, (&x, &p)| {
+                acc + CurveScalar(x) * p
+            });
+        for window_bits in [1, 4, 5] {
+            assert_eq!(msm(&scalars, &points, window_bits), expected);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::ops::Mul;
+
+use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar, ProjectivePoint};
+use crate::field::types::{Field, PrimeField};
+
+const WINDOW_BITS: usize = 4;
+
+/// Splits a scalar into little-endian digits of `window_bits` bits each.
+pub(crate) fn to_digits<C: Curve>(x: &C::ScalarField, window_bits: usize) -> Vec<usize> {
+    debug_assert!(window_bits > 0 && window_bits < usize::BITS as usize);
+    let num_digits = C::ScalarField::BITS.div_ceil(window_bits);
+    let bits = x.to_canonical_biguint();
+
+    (0..num_digits)
+        .map(|i| {
+            (0..window_bits)
+                .map(|j| i * window_bits + j)
+                .filter(|&bit| bits.bit(bit as u64))
+                .fold(0, |acc, bit| acc | 1 << (bit - i * window_bits))
+        })
+        .collect()
+}
+
+impl<C: Curve> ProjectivePoint<C> {
+    /// Computes `[0, p, 2p, ..., (2^window_bits - 1) p]`.
+    pub(crate) fn small_multiples(&self, window_bits: usize) -> Vec<Self> {
+        let mut multiples = vec![Self::ZERO; 1 << window_bits];
+        for i in 1..multiples.len() {
+            multiples[i] = multiples[i - 1] + *self;
+        }
+        multiples
+    }
+}
+
+impl<C: Curve> Mul<ProjectivePoint<C>> for CurveScalar<C> {
+    type Output = ProjectivePoint<C>;
+
+    /// Fixed-window scalar multiplication, processing `WINDOW_BITS` bits of the scalar at a time.
+    fn mul(self, rhs: ProjectivePoint<C>) -> Self::Output {
+        if self.0.is_zero() {
+            return ProjectivePoint::ZERO;
+        }
+
+        let multiples = rhs.small_multiples(WINDOW_BITS);
+        let mut result = ProjectivePoint::ZERO;
+        for &digit in to_digits::<C>(&self.0, WINDOW_BITS).iter().rev() {
+            for _ in 0..WINDOW_BITS {
+                result = result.double();
+            }
+            if digit != 0 {
+                result = result + multiples[digit];
+            }
+        }
+        result
+    }
+}
+
+impl<C: Curve> Mul<AffinePoint<C>> for CurveScalar<C> {
+    type Output = ProjectivePoint<C>;
+
+    fn mul(self, rhs: AffinePoint<C>) -> Self::Output {
+        self * rhs.to_projective()
+    }
+}
@@ -0,0 +1,314 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::ops::{Add, Neg};
+
+use crate::field::ops::Square;
+use crate::field::types::{Field, PrimeField};
+
+/// A short Weierstrass curve `y^2 = x^3 + A x + B` over `BaseField`, whose group of points has
+/// prime order `|ScalarField|`.
+pub trait Curve: 'static + Sync + Sized + Copy + Debug {
+    type BaseField: PrimeField;
+    type ScalarField: PrimeField;
+
+    const A: Self::BaseField;
+    const B: Self::BaseField;
+
+    const GENERATOR_AFFINE: AffinePoint<Self>;
+
+    const GENERATOR_PROJECTIVE: ProjectivePoint<Self> = ProjectivePoint {
+        x: Self::GENERATOR_AFFINE.x,
+        y: Self::GENERATOR_AFFINE.y,
+        z: Self::BaseField::ONE,
+    };
+
+    fn convert(x: Self::ScalarField) -> CurveScalar<Self> {
+        CurveScalar(x)
+    }
+
+    /// Checks that the curve is non-singular, i.e. that `4 A^3 + 27 B^2` is nonzero.
+    fn is_safe_curve() -> bool {
+        (Self::A.cube().double().double() + Self::B.square().triple().triple().triple())
+            .is_nonzero()
+    }
+}
+
+/// A point on a short Weierstrass curve, represented in affine coordinates.
+#[derive(Copy, Clone, Debug)]
+pub struct AffinePoint<C: Curve> {
+    pub x: C::BaseField,
+    pub y: C::BaseField,
+    pub zero: bool,
+}
+
+impl<C: Curve> AffinePoint<C> {
+    pub const ZERO: Self = Self {
+        x: C::BaseField::ZERO,
+        y: C::BaseField::ZERO,
+        zero: true,
+    };
+
+    pub const fn nonzero(x: C::BaseField, y: C::BaseField) -> Self {
+        Self { x, y, zero: false }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let Self { x, y, zero } = *self;
+        zero || y.square() == x.cube() + C::A * x + C::B
+    }
+
+    pub fn to_projective(&self) -> ProjectivePoint<C> {
+        let Self { x, y, zero } = *self;
+        let z = if zero {
+            C::BaseField::ZERO
+        } else {
+            C::BaseField::ONE
+        };
+
+        ProjectivePoint { x, y, z }
+    }
+
+    pub fn batch_to_projective(affine_points: &[Self]) -> Vec<ProjectivePoint<C>> {
+        affine_points.iter().map(Self::to_projective).collect()
+    }
+
+    pub fn double(&self) -> Self {
+        let Self { x, y, zero } = *self;
+        if zero || y.is_zero() {
+            return Self::ZERO;
+        }
+
+        let lambda = (x.square().triple() + C::A) / y.double();
+        let x3 = lambda.square() - x.double();
+        let y3 = lambda * (x - x3) - y;
+        Self::nonzero(x3, y3)
+    }
+}
+
+impl<C: Curve> PartialEq for AffinePoint<C> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.zero || other.zero {
+            return self.zero == other.zero;
+        }
+        self.x == other.x && self.y == other.y
+    }
+}
+
+impl<C: Curve> Eq for AffinePoint<C> {}
+
+impl<C: Curve> Neg for AffinePoint<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let Self { x, y, zero } = self;
+        Self { x, y: -y, zero }
+    }
+}
+
+impl<C: Curve> Add for AffinePoint<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.zero {
+            return rhs;
+        }
+        if rhs.zero {
+            return self;
+        }
+
+        let Self { x: x1, y: y1, .. } = self;
+        let Self { x: x2, y: y2, .. } = rhs;
+        if x1 == x2 {
+            return if y1 == y2 { self.double() } else { Self::ZERO };
+        }
+
+        let lambda = (y2 - y1) / (x2 - x1);
+        let x3 = lambda.square() - x1 - x2;
+        let y3 = lambda * (x1 - x3) - y1;
+        Self::nonzero(x3, y3)
+    }
+}
+
+/// A point on a short Weierstrass curve, represented in projective coordinates.
+#[derive(Copy, Clone, Debug)]
+pub struct ProjectivePoint<C: Curve> {
+    pub x: C::BaseField,
+    pub y: C::BaseField,
+    pub z: C::BaseField,
+}
+
+impl<C: Curve> ProjectivePoint<C> {
+    pub const ZERO: Self = Self {
+        x: C::BaseField::ZERO,
+        y: C::BaseField::ONE,
+        z: C::BaseField::ZERO,
+    };
+
+    pub const fn nonzero(x: C::BaseField, y: C::BaseField, z: C::BaseField) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let Self { x, y, z } = *self;
+        z.is_zero() || y.square() * z == x.cube() + C::A * x * z.square() + C::B * z.cube()
+    }
+
+    pub fn to_affine(&self) -> AffinePoint<C> {
+        let Self { x, y, z } = *self;
+        if z.is_zero() {
+            AffinePoint::ZERO
+        } else {
+            let z_inv = z.inverse();
+            AffinePoint::nonzero(x * z_inv, y * z_inv)
+        }
+    }
+
+    pub fn batch_to_affine(proj_points: &[Self]) -> Vec<AffinePoint<C>> {
+        let zs = proj_points
+            .iter()
+            .map(|p| p.z)
+            .filter(|z| z.is_nonzero())
+            .collect::<Vec<_>>();
+        let mut z_invs = C::BaseField::batch_multiplicative_inverse(&zs).into_iter();
+
+        proj_points
+            .iter()
+            .map(|&Self { x, y, z }| {
+                if z.is_zero() {
+                    AffinePoint::ZERO
+                } else {
+                    let z_inv = z_invs.next().unwrap();
+                    AffinePoint::nonzero(x * z_inv, y * z_inv)
+                }
+            })
+            .collect()
+    }
+
+    // From https://www.hyperelliptic.org/EFD/g1p/data/shortw/projective/doubling/dbl-2007-bl
+    pub fn double(&self) -> Self {
+        let Self { x, y, z } = *self;
+        if z.is_zero() {
+            return Self::ZERO;
+        }
+
+        let xx = x.square();
+        let zz = z.square();
+        let w = C::A * zz + xx.triple();
+        let s = y.double() * z;
+        let r = y * s;
+        let rr = r.square();
+        let b = (x + r).square() - xx - rr;
+        let h = w.square() - b.double();
+        let x3 = h * s;
+        let y3 = w * (b - h) - rr.double();
+        let z3 = s.cube();
+        Self::nonzero(x3, y3, z3)
+    }
+}
+
+impl<C: Curve> PartialEq for ProjectivePoint<C> {
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            x: x1,
+            y: y1,
+            z: z1,
+        } = *self;
+        let Self {
+            x: x2,
+            y: y2,
+            z: z2,
+        } = *other;
+        if z1.is_zero() || z2.is_zero() {
+            return z1.is_zero() == z2.is_zero();
+        }
+
+        // We want to compare (x1/z1, y1/z1) == (x2/z2, y2/z2).
+        // But to avoid field division, it is better to compare (x1*z2, y1*z2) == (x2*z1, y2*z1).
+        x1 * z2 == x2 * z1 && y1 * z2 == y2 * z1
+    }
+}
+
+impl<C: Curve> Eq for ProjectivePoint<C> {}
+
+impl<C: Curve> Neg for ProjectivePoint<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let Self { x, y, z } = self;
+        Self { x, y: -y, z }
+    }
+}
+
+impl<C: Curve> Add for ProjectivePoint<C> {
+    type Output = Self;
+
+    // From https://www.hyperelliptic.org/EFD/g1p/data/shortw/projective/addition/add-1998-cmo-2
+    fn add(self, rhs: Self) -> Self {
+        let Self {
+            x: x1,
+            y: y1,
+            z: z1,
+        } = self;
+        let Self {
+            x: x2,
+            y: y2,
+            z: z2,
+        } = rhs;
+        if z1.is_zero() {
+            return rhs;
+        }
+        if z2.is_zero() {
+            return self;
+        }
+
+        let x1z2 = x1 * z2;
+        let y1z2 = y1 * z2;
+        let x2z1 = x2 * z1;
+        let y2z1 = y2 * z1;
+
+        // Check if we're doubling or adding inverses.
+        if x1z2 == x2z1 {
+            return if y1z2 == y2z1 {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+
+        let z1z2 = z1 * z2;
+        let u = y2z1 - y1z2;
+        let uu = u.square();
+        let v = x2z1 - x1z2;
+        let vv = v.square();
+        let vvv = v * vv;
+        let r = vv * x1z2;
+        let a = uu * z1z2 - vvv - r.double();
+        let x3 = v * a;
+        let y3 = u * (r - a) - vvv * y1z2;
+        let z3 = vvv * z1z2;
+        Self::nonzero(x3, y3, z3)
+    }
+}
+
+impl<C: Curve> Add<AffinePoint<C>> for ProjectivePoint<C> {
+    type Output = Self;
+
+    fn add(self, rhs: AffinePoint<C>) -> Self {
+        self + rhs.to_projective()
+    }
+}
+
+/// An element of the scalar field of `C`, which acts on the points of `C` by multiplication.
+#[derive(Copy, Clone, Debug)]
+pub struct CurveScalar<C: Curve>(pub C::ScalarField);
+
+/// Reduces an element of the base field modulo the order of the scalar field.
+pub fn base_to_scalar<C: Curve>(x: C::BaseField) -> C::ScalarField {
+    C::ScalarField::from_noncanonical_biguint(x.to_canonical_biguint() % C::ScalarField::order())
+}
+
+/// Reduces an element of the scalar field modulo the order of the base field.
+pub fn scalar_to_base<C: Curve>(x: C::ScalarField) -> C::BaseField {
+    C::BaseField::from_noncanonical_biguint(x.to_canonical_biguint() % C::BaseField::order())
+}
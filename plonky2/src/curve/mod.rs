@@ -0,0 +1,5 @@
+pub mod curve_msm;
+pub mod curve_multiplication;
+pub mod curve_types;
+pub mod ecdsa;
+pub mod secp256k1;
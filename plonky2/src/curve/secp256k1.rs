@@ -0,0 +1,106 @@
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::Field;
+
+/// The secp256k1 curve `y^2 = x^3 + 7`, as used in Bitcoin and Ethereum.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct Secp256K1;
+
+impl Curve for Secp256K1 {
+    type BaseField = Secp256K1Base;
+    type ScalarField = Secp256K1Scalar;
+
+    const A: Secp256K1Base = Secp256K1Base::ZERO;
+    const B: Secp256K1Base = Secp256K1Base([7, 0, 0, 0]);
+    const GENERATOR_AFFINE: AffinePoint<Self> = AffinePoint {
+        x: SECP256K1_GENERATOR_X,
+        y: SECP256K1_GENERATOR_Y,
+        zero: false,
+    };
+}
+
+// 55066263022277343669578718895168534326250603453777594175500187360389116729240
+const SECP256K1_GENERATOR_X: Secp256K1Base = Secp256K1Base([
+    0x59F2815B16F81798,
+    0x029BFCDB2DCE28D9,
+    0x55A06295CE870B07,
+    0x79BE667EF9DCBBAC,
+]);
+
+// 32670510020758816978083085130507043184471273380659243275938904335757337482424
+const SECP256K1_GENERATOR_Y: Secp256K1Base = Secp256K1Base([
+    0x9C47D08FFB10D4B8,
+    0xFD17B448A6855419,
+    0x5DA4FBFC0E1108A8,
+    0x483ADA7726A3C465,
+]);
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar, ProjectivePoint};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, PrimeField, Sample};
+
+    #[test]
+    fn test_generator() {
+        assert!(Secp256K1::is_safe_curve());
+        assert!(Secp256K1::GENERATOR_AFFINE.is_valid());
+        assert!(Secp256K1::GENERATOR_PROJECTIVE.is_valid());
+
+        let neg_one = Secp256K1Scalar::NEG_ONE;
+        let neg_g = CurveScalar(neg_one) * Secp256K1::GENERATOR_PROJECTIVE;
+        assert_eq!(neg_g, -Secp256K1::GENERATOR_PROJECTIVE);
+        assert!((neg_g + Secp256K1::GENERATOR_PROJECTIVE).to_affine().zero);
+    }
+
+    #[test]
+    fn test_affine_and_projective_arithmetic() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let g_proj = Secp256K1::GENERATOR_PROJECTIVE;
+
+        let two_g = g.double();
+        assert!(two_g.is_valid());
+        assert_eq!(g_proj.double().to_affine(), two_g);
+        assert_eq!((g_proj + g_proj).to_affine(), two_g);
+
+        let three_g = two_g + g;
+        assert!(three_g.is_valid());
+        assert_eq!((g_proj.double() + g).to_affine(), three_g);
+        assert_eq!(three_g + -three_g, AffinePoint::ZERO);
+
+        let points = [g_proj, ProjectivePoint::ZERO, g_proj.double()];
+        assert_eq!(
+            ProjectivePoint::batch_to_affine(&points),
+            [g, AffinePoint::ZERO, two_g]
+        );
+    }
+
+    #[test]
+    fn test_scalar_mul() {
+        let g = Secp256K1::GENERATOR_PROJECTIVE;
+        let x = Secp256K1Scalar::rand();
+        let y = Secp256K1Scalar::rand();
+
+        let xg = CurveScalar(x) * g;
+        assert!(xg.is_valid());
+        assert_eq!(CurveScalar(x + y) * g, xg + CurveScalar(y) * g);
+        assert_eq!(CurveScalar(x * y) * g, CurveScalar(y) * xg);
+        assert_eq!(
+            CurveScalar(Secp256K1Scalar::ZERO) * g,
+            ProjectivePoint::ZERO
+        );
+
+        // Compare against double-and-add.
+        let bits = x.to_canonical_biguint();
+        let mut expected = ProjectivePoint::ZERO;
+        for i in (0..bits.bits()).rev() {
+            expected = expected.double();
+            if bits.bit(i) {
+                expected = expected + g;
+            }
+        }
+        assert_eq!(xg, expected);
+    }
+}
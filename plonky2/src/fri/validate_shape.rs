@@ -1,3 +1,6 @@
+//! Checks that FRI proofs have the shape expected by the verifier, so that malformed proofs are
+//! rejected with an error before any of their components get accessed.
+
 #[cfg(not(feature = "std"))]
 use alloc::vec;
 
@@ -11,7 +14,9 @@ use crate::hash::hash_types::RichField;
 use crate::plonk::config::GenericConfig;
 use crate::plonk::plonk_common::salt_size;
 
-pub(crate) fn validate_fri_proof_shape<F, C, const D: usize>(
+/// Checks that a [`FriProof`] for the given instance has the shape expected with the given
+/// [`FriParams`].
+pub fn validate_fri_proof_shape<F, C, const D: usize>(
     proof: &FriProof<F, C::Hasher, D>,
     instance: &FriInstanceInfo<F, D>,
     params: &FriParams,
@@ -23,7 +28,9 @@ where
     validate_batch_fri_proof_shape::<F, C, D>(proof, &[instance.clone()], params)
 }
 
-pub(crate) fn validate_batch_fri_proof_shape<F, C, const D: usize>(
+/// Checks that a batch [`FriProof`] for the given instances has the shape expected with the given
+/// [`FriParams`].
+pub fn validate_batch_fri_proof_shape<F, C, const D: usize>(
     proof: &FriProof<F, C::Hasher, D>,
     instances: &[FriInstanceInfo<F, D>],
     params: &FriParams,
@@ -40,10 +47,12 @@ where
     } = proof;
 
     let cap_height = params.config.cap_height;
+    ensure!(commit_phase_merkle_caps.len() == params.reduction_arity_bits.len());
     for cap in commit_phase_merkle_caps {
-        ensure!(cap.height() == cap_height);
+        ensure!(cap.len() == params.config.num_cap_elements());
     }
 
+    ensure!(query_round_proofs.len() == params.config.num_query_rounds);
     for query_round in query_round_proofs {
         let FriQueryRound {
             initial_trees_proof,
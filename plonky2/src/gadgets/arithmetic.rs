@@ -353,6 +353,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         BoolTarget::new_unsafe(self.add(res_minus_b2, b2.target))
     }
 
+    /// Computes the logical XOR through the arithmetic expression: `b1 + b2 - 2 * b1 * b2`.
+    pub fn xor(&mut self, b1: BoolTarget, b2: BoolTarget) -> BoolTarget {
+        let res_minus_b2 = self.arithmetic(-F::TWO, F::ONE, b1.target, b2.target, b1.target);
+        BoolTarget::new_unsafe(self.add(res_minus_b2, b2.target))
+    }
+
     /// Outputs `x` if `b` is true, and else `y`, through the formula: `b*x + (1-b)*y`.
     pub fn _if(&mut self, b: BoolTarget, x: Target, y: Target) -> Target {
         let not_b = self.not(b);
@@ -0,0 +1,425 @@
+//! Gadgets for arbitrary-precision unsigned integers, represented by their 32-bit limbs.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use anyhow::{ensure, Result};
+use num::{BigUint, Integer, Zero};
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField64};
+use crate::gadgets::uint::{U32Target, WitnessU32, WitnessWriteU32};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// An unsigned integer, given by its 32-bit limbs in little-endian order.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BigUintTarget {
+    pub limbs: Vec<U32Target>,
+}
+
+impl BigUintTarget {
+    pub fn num_limbs(&self) -> usize {
+        self.limbs.len()
+    }
+
+    pub fn get_limb(&self, i: usize) -> U32Target {
+        self.limbs[i]
+    }
+
+    pub(crate) fn targets(&self) -> Vec<Target> {
+        self.limbs.iter().map(|limb| limb.target).collect()
+    }
+
+    /// Wraps the given targets as limbs, without range-checking them.
+    pub(crate) fn from_targets(targets: Vec<Target>) -> Self {
+        Self {
+            limbs: targets.into_iter().map(U32Target::new_unsafe).collect(),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a new virtual integer of `num_limbs` limbs, which are range-checked.
+    pub fn add_virtual_biguint_target(&mut self, num_limbs: usize) -> BigUintTarget {
+        BigUintTarget {
+            limbs: self.add_virtual_u32_targets(num_limbs),
+        }
+    }
+
+    pub fn constant_biguint(&mut self, value: &BigUint) -> BigUintTarget {
+        let limbs = value
+            .to_u32_digits()
+            .into_iter()
+            .map(|limb| self.constant_u32(limb))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    pub fn zero_biguint(&mut self) -> BigUintTarget {
+        self.constant_biguint(&BigUint::zero())
+    }
+
+    /// Connects two integers, which may have different numbers of limbs.
+    pub fn connect_biguint(&mut self, lhs: &BigUintTarget, rhs: &BigUintTarget) {
+        let (lhs, rhs) = self.pad_biguints(lhs, rhs);
+        for (&l, &r) in lhs.limbs.iter().zip(&rhs.limbs) {
+            self.connect_u32(l, r);
+        }
+    }
+
+    /// Pads the given integers with zero limbs, so that they have the same number of limbs.
+    pub fn pad_biguints(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let zero = self.constant_u32(0);
+        let pad = |x: &BigUintTarget| {
+            let mut limbs = x.limbs.clone();
+            limbs.resize(num_limbs, zero);
+            BigUintTarget { limbs }
+        };
+        (pad(a), pad(b))
+    }
+
+    /// Returns whether `a <= b`.
+    pub fn le_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let (a, b) = self.pad_biguints(a, b);
+        // `b - a` underflows if and only if `a > b`.
+        let mut borrow = self._false();
+        for (&a_limb, &b_limb) in a.limbs.iter().zip(&b.limbs) {
+            borrow = self.sub_u32(b_limb, a_limb, borrow).1;
+        }
+        self.not(borrow)
+    }
+
+    /// Returns whether `a < b`.
+    pub fn lt_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let ge = self.le_biguint(b, a);
+        self.not(ge)
+    }
+
+    pub fn add_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let (a, b) = self.pad_biguints(a, b);
+        let mut carry = self._false();
+        let mut limbs = Vec::with_capacity(a.num_limbs() + 1);
+        for (&a_limb, &b_limb) in a.limbs.iter().zip(&b.limbs) {
+            let (sum, new_carry) = self.add_u32_with_carry(a_limb, b_limb, carry);
+            limbs.push(sum);
+            carry = new_carry;
+        }
+        limbs.push(U32Target::new_unsafe(carry.target));
+        BigUintTarget { limbs }
+    }
+
+    /// Computes `a - b`. The circuit is unsatisfiable if `a < b`.
+    pub fn sub_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let (a, b) = self.pad_biguints(a, b);
+        let mut borrow = self._false();
+        let mut limbs = Vec::with_capacity(a.num_limbs());
+        for (&a_limb, &b_limb) in a.limbs.iter().zip(&b.limbs) {
+            let (diff, new_borrow) = self.sub_u32(a_limb, b_limb, borrow);
+            limbs.push(diff);
+            borrow = new_borrow;
+        }
+        self.assert_zero(borrow.target);
+        BigUintTarget { limbs }
+    }
+
+    pub fn mul_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let total_limbs = a.num_limbs() + b.num_limbs();
+        if a.num_limbs() == 0 || b.num_limbs() == 0 {
+            return self.zero_biguint();
+        }
+
+        // The terms of the product contributing to each limb, before carries.
+        let mut columns = vec![vec![]; total_limbs];
+        for (i, &a_limb) in a.limbs.iter().enumerate() {
+            for (j, &b_limb) in b.limbs.iter().enumerate() {
+                let (low, high) = self.mul_u32(a_limb, b_limb);
+                columns[i + j].push(low);
+                columns[i + j + 1].push(high);
+            }
+        }
+
+        let mut limbs = Vec::with_capacity(total_limbs);
+        let mut carry = self.constant_u32(0);
+        for mut column in columns {
+            column.push(carry);
+            let (limb, new_carry) = self.add_many_u32(&column);
+            limbs.push(limb);
+            carry = new_carry;
+        }
+        // The product has at most `total_limbs` limbs, so the last carry is zero.
+        self.assert_zero(carry.target);
+
+        BigUintTarget { limbs }
+    }
+
+    /// Computes `a * b`, where `b` is a bit.
+    pub fn mul_biguint_by_bool(&mut self, a: &BigUintTarget, b: BoolTarget) -> BigUintTarget {
+        let limbs = a
+            .limbs
+            .iter()
+            .map(|&limb| U32Target::new_unsafe(self.mul(limb.target, b.target)))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Computes `a * b + c`.
+    pub fn mul_add_biguint(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        c: &BigUintTarget,
+    ) -> BigUintTarget {
+        let product = self.mul_biguint(a, b);
+        self.add_biguint(&product, c)
+    }
+
+    /// Computes the quotient and remainder of the division of `a` by `b`. The circuit is
+    /// unsatisfiable if `b` is zero.
+    pub fn div_rem_biguint(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let quotient = self.add_virtual_biguint_target(a.num_limbs());
+        let remainder = self.add_virtual_biguint_target(b.num_limbs());
+        self.add_simple_generator(BigUintDivRemGenerator {
+            a: a.clone(),
+            b: b.clone(),
+            quotient: quotient.clone(),
+            remainder: remainder.clone(),
+        });
+
+        let combined = self.mul_add_biguint(&quotient, b, &remainder);
+        self.connect_biguint(a, &combined);
+
+        let remainder_lt_b = self.lt_biguint(&remainder, b);
+        self.assert_one(remainder_lt_b.target);
+
+        (quotient, remainder)
+    }
+
+    pub fn div_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        self.div_rem_biguint(a, b).0
+    }
+
+    pub fn rem_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        self.div_rem_biguint(a, b).1
+    }
+}
+
+/// Setters for [`BigUintTarget`]s, for any [`WitnessWrite`].
+pub trait WitnessWriteBigUint<F: Field>: WitnessWrite<F> {
+    /// Sets the limbs of `target` to the base-`2^32` digits of `value`, failing if `value` does
+    /// not fit in the limbs of `target`.
+    fn set_biguint_target(&mut self, target: &BigUintTarget, value: &BigUint) -> Result<()>;
+}
+
+impl<F: Field, W: WitnessWrite<F>> WitnessWriteBigUint<F> for W {
+    fn set_biguint_target(&mut self, target: &BigUintTarget, value: &BigUint) -> Result<()> {
+        let digits = value.to_u32_digits();
+        ensure!(
+            digits.len() <= target.num_limbs(),
+            "Value does not fit in {} limbs.",
+            target.num_limbs()
+        );
+        for (i, &limb) in target.limbs.iter().enumerate() {
+            self.set_u32_target(limb, digits.get(i).copied().unwrap_or(0))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Getters for [`BigUintTarget`]s, for any [`Witness`].
+pub trait WitnessBigUint<F: PrimeField64>: Witness<F> {
+    /// Returns the value of `target`, recombined from its limbs.
+    fn get_biguint_target(&self, target: &BigUintTarget) -> BigUint;
+}
+
+impl<F: PrimeField64, W: Witness<F>> WitnessBigUint<F> for W {
+    fn get_biguint_target(&self, target: &BigUintTarget) -> BigUint {
+        BigUint::new(
+            target
+                .limbs
+                .iter()
+                .map(|&limb| self.get_u32_target(limb))
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BigUintDivRemGenerator {
+    a: BigUintTarget,
+    b: BigUintTarget,
+    quotient: BigUintTarget,
+    remainder: BigUintTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintDivRemGenerator
+{
+    fn id(&self) -> String {
+        "BigUintDivRemGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a
+            .limbs
+            .iter()
+            .chain(&self.b.limbs)
+            .map(|limb| limb.target)
+            .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let a = witness.get_biguint_target(&self.a);
+        let b = witness.get_biguint_target(&self.b);
+        ensure!(!b.is_zero(), "Division by zero.");
+        let (quotient, remainder) = a.div_rem(&b);
+
+        out_buffer.set_biguint_target(&self.quotient, &quotient)?;
+        out_buffer.set_biguint_target(&self.remainder, &remainder)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a.targets())?;
+        dst.write_target_vec(&self.b.targets())?;
+        dst.write_target_vec(&self.quotient.targets())?;
+        dst.write_target_vec(&self.remainder.targets())
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = BigUintTarget::from_targets(src.read_target_vec()?);
+        let b = BigUintTarget::from_targets(src.read_target_vec()?);
+        let quotient = BigUintTarget::from_targets(src.read_target_vec()?);
+        let remainder = BigUintTarget::from_targets(src.read_target_vec()?);
+        Ok(Self {
+            a,
+            b,
+            quotient,
+            remainder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::{BigUint, FromPrimitive, Integer};
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::WitnessWriteBigUint;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn random_biguint(num_limbs: usize) -> BigUint {
+        let mut rng = OsRng;
+        BigUint::new((0..num_limbs).map(|_| rng.gen()).collect())
+    }
+
+    #[test]
+    fn test_biguint_arithmetic() -> Result<()> {
+        let x = random_biguint(4);
+        let y = random_biguint(3);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_biguint_target(4);
+        let yt = builder.add_virtual_biguint_target(3);
+
+        let sum = builder.add_biguint(&xt, &yt);
+        let expected_sum = builder.constant_biguint(&(&x + &y));
+        builder.connect_biguint(&sum, &expected_sum);
+
+        let diff = builder.sub_biguint(&xt, &yt);
+        let expected_diff = builder.constant_biguint(&(&x - &y));
+        builder.connect_biguint(&diff, &expected_diff);
+
+        let product = builder.mul_biguint(&xt, &yt);
+        let expected_product = builder.constant_biguint(&(&x * &y));
+        builder.connect_biguint(&product, &expected_product);
+
+        let le = builder.le_biguint(&yt, &xt);
+        builder.assert_one(le.target);
+        let lt = builder.lt_biguint(&xt, &xt);
+        builder.assert_zero(lt.target);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_biguint_target(&xt, &x)?;
+        pw.set_biguint_target(&yt, &y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_biguint_div_rem() -> Result<()> {
+        let x = random_biguint(5);
+        let y = random_biguint(2) + BigUint::from_u8(1).unwrap();
+        let (q, r) = x.div_rem(&y);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_biguint_target(5);
+        let yt = builder.add_virtual_biguint_target(2);
+        let (qt, rt) = builder.div_rem_biguint(&xt, &yt);
+        let expected_q = builder.constant_biguint(&q);
+        let expected_r = builder.constant_biguint(&r);
+        builder.connect_biguint(&qt, &expected_q);
+        builder.connect_biguint(&rt, &expected_r);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_biguint_target(&xt, &x)?;
+        pw.set_biguint_target(&yt, &y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_biguint_sub_underflow() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_biguint_target(2);
+        let yt = builder.add_virtual_biguint_target(2);
+        builder.sub_biguint(&xt, &yt);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_biguint_target(&xt, &BigUint::from_u8(1).unwrap())
+            .unwrap();
+        pw.set_biguint_target(&yt, &BigUint::from_u8(2).unwrap())
+            .unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! Bitwise operations on values split into little-endian limbs of up to 8 bits, using lookup
+//! tables.
+//!
+//! Binary operations use a table of `2^(2 * limb_bits)` entries, whose input packs both operands
+//! as `x * 2^limb_bits + y`. Shifts and rotations use tables extracting the low bits of a limb.
+//! Tables are registered the first time they are needed, and reused afterwards.
+//!
+//! The limbs given to these gadgets are assumed to be range-checked, e.g. with
+//! [`CircuitBuilder::split_le_limbs`]; the limbs they return are range-checked as well.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The maximum number of bits of a limb, so that binary operations fit in `u16` lookup tables.
+pub const MAX_LIMB_BITS: usize = 8;
+
+/// The lookup tables used by bitwise gadgets.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum BitwiseTable {
+    /// Maps `x * 2^limb_bits + y` to `x & y`.
+    And { limb_bits: usize },
+    /// Maps `x * 2^limb_bits + y` to `x ^ y`.
+    Xor { limb_bits: usize },
+    /// Maps a limb to its `num_low_bits` low bits. With `num_low_bits = limb_bits`, this is a range
+    /// check.
+    LowBits {
+        limb_bits: usize,
+        num_low_bits: usize,
+    },
+}
+
+impl BitwiseTable {
+    fn pairs(self) -> Vec<(u16, u16)> {
+        match self {
+            Self::And { limb_bits } => Self::binary_op_pairs(limb_bits, |x, y| x & y),
+            Self::Xor { limb_bits } => Self::binary_op_pairs(limb_bits, |x, y| x ^ y),
+            Self::LowBits {
+                limb_bits,
+                num_low_bits,
+            } => (0..1u32 << limb_bits)
+                .map(|x| (x as u16, (x & ((1 << num_low_bits) - 1)) as u16))
+                .collect(),
+        }
+    }
+
+    fn binary_op_pairs(limb_bits: usize, op: fn(u32, u32) -> u32) -> Vec<(u16, u16)> {
+        let mask = (1 << limb_bits) - 1;
+        (0..1u32 << (2 * limb_bits))
+            .map(|input| (input as u16, op(input >> limb_bits, input & mask) as u16))
+            .collect()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Splits `x` into little-endian limbs of `limb_bits` bits, which are range-checked with a
+    /// lookup table. `num_bits` must be a multiple of `limb_bits`.
+    pub fn split_le_limbs(&mut self, x: Target, num_bits: usize, limb_bits: usize) -> Vec<Target> {
+        Self::assert_limb_bits(limb_bits);
+        assert_eq!(
+            num_bits % limb_bits,
+            0,
+            "Bits must split evenly into limbs."
+        );
+        assert!(num_bits < F::BITS, "Too many bits for the field.");
+
+        let limbs = self.add_virtual_targets(num_bits / limb_bits);
+        self.add_simple_generator(LimbSplitGenerator {
+            integer: x,
+            limb_bits,
+            limbs: limbs.clone(),
+        });
+
+        let range_check = self.bitwise_lut(BitwiseTable::LowBits {
+            limb_bits,
+            num_low_bits: limb_bits,
+        });
+        for &limb in &limbs {
+            let checked = self.add_lookup_from_index(limb, range_check);
+            self.connect(limb, checked);
+        }
+
+        let sum = self.le_sum_limbs(&limbs, limb_bits);
+        self.connect(x, sum);
+        limbs
+    }
+
+    /// Returns the value of the given little-endian limbs of `limb_bits` bits.
+    pub fn le_sum_limbs(&mut self, limbs: &[Target], limb_bits: usize) -> Target {
+        let base = F::from_canonical_u64(1 << limb_bits);
+        let zero = self.zero();
+        limbs
+            .iter()
+            .rev()
+            .fold(zero, |acc, &limb| self.mul_const_add(base, acc, limb))
+    }
+
+    /// Computes the bitwise AND of two values given by their limbs of `limb_bits` bits.
+    pub fn and_limbs(&mut self, x: &[Target], y: &[Target], limb_bits: usize) -> Vec<Target> {
+        self.binary_op_limbs(BitwiseTable::And { limb_bits }, x, y, limb_bits)
+    }
+
+    /// Computes the bitwise XOR of two values given by their limbs of `limb_bits` bits.
+    pub fn xor_limbs(&mut self, x: &[Target], y: &[Target], limb_bits: usize) -> Vec<Target> {
+        self.binary_op_limbs(BitwiseTable::Xor { limb_bits }, x, y, limb_bits)
+    }
+
+    /// Computes the bitwise NOT of a value given by its limbs of `limb_bits` bits. This does not
+    /// need any lookup.
+    pub fn not_limbs(&mut self, x: &[Target], limb_bits: usize) -> Vec<Target> {
+        Self::assert_limb_bits(limb_bits);
+        let mask = self.constant(F::from_canonical_u64((1 << limb_bits) - 1));
+        x.iter().map(|&limb| self.sub(mask, limb)).collect()
+    }
+
+    /// Rotates right by `rotation` bits a value given by its limbs of `limb_bits` bits.
+    pub fn rotate_right_limbs(
+        &mut self,
+        x: &[Target],
+        limb_bits: usize,
+        rotation: usize,
+    ) -> Vec<Target> {
+        let n = x.len();
+        let rotation = rotation % (n * limb_bits);
+        self.shift_right_with(n, limb_bits, rotation, |i| Some(x[i % n]))
+    }
+
+    /// Shifts right by `shift` bits a value given by its limbs of `limb_bits` bits.
+    pub fn shift_right_limbs(
+        &mut self,
+        x: &[Target],
+        limb_bits: usize,
+        shift: usize,
+    ) -> Vec<Target> {
+        self.shift_right_with(x.len(), limb_bits, shift, |i| x.get(i).copied())
+    }
+
+    /// Shifts left by `shift` bits a value given by its limbs of `limb_bits` bits. The bits
+    /// shifted past the most significant limb are dropped.
+    pub fn shift_left_limbs(
+        &mut self,
+        x: &[Target],
+        limb_bits: usize,
+        shift: usize,
+    ) -> Vec<Target> {
+        let n = x.len();
+        if shift >= n * limb_bits {
+            let zero = self.zero();
+            return vec![zero; n];
+        }
+        // Shifting left by `shift` bits is shifting right by `n * limb_bits - shift` bits, with
+        // the input placed in the upper half of a value of `2n` limbs.
+        let shift = n * limb_bits - shift;
+        self.shift_right_with(n, limb_bits, shift, |i| {
+            i.checked_sub(n).and_then(|i| x.get(i).copied())
+        })
+    }
+
+    /// Computes the `num_limbs` low limbs of a value shifted right by `shift` bits, where
+    /// `limb(i)` returns the `i`-th limb of the value to shift, or `None` for zero limbs.
+    fn shift_right_with(
+        &mut self,
+        num_limbs: usize,
+        limb_bits: usize,
+        shift: usize,
+        limb: impl Fn(usize) -> Option<Target>,
+    ) -> Vec<Target> {
+        Self::assert_limb_bits(limb_bits);
+        let (limb_shift, bit_shift) = (shift / limb_bits, shift % limb_bits);
+        let zero = self.zero();
+        if bit_shift == 0 {
+            return (0..num_limbs)
+                .map(|i| limb(i + limb_shift).unwrap_or(zero))
+                .collect();
+        }
+
+        // Each output limb combines the high bits of a limb with the low bits of the next one.
+        let mut splits = HashMap::new();
+        let mut split = |builder: &mut Self, l: Target| {
+            *splits
+                .entry(l)
+                .or_insert_with(|| builder.split_limb(l, limb_bits, bit_shift))
+        };
+        let high_base = F::from_canonical_u64(1 << (limb_bits - bit_shift));
+        (0..num_limbs)
+            .map(|i| {
+                let high = match limb(i + limb_shift) {
+                    Some(l) => split(self, l).1,
+                    None => zero,
+                };
+                match limb(i + limb_shift + 1) {
+                    Some(l) => {
+                        let low = split(self, l).0;
+                        self.mul_const_add(high_base, low, high)
+                    }
+                    None => high,
+                }
+            })
+            .collect()
+    }
+
+    /// Splits a limb of `limb_bits` bits into its `num_low_bits` low bits and the remaining high
+    /// bits.
+    fn split_limb(
+        &mut self,
+        limb: Target,
+        limb_bits: usize,
+        num_low_bits: usize,
+    ) -> (Target, Target) {
+        let lut = self.bitwise_lut(BitwiseTable::LowBits {
+            limb_bits,
+            num_low_bits,
+        });
+        let low = self.add_lookup_from_index(limb, lut);
+        let diff = self.sub(limb, low);
+        let high = self.mul_const(F::from_canonical_u64(1 << num_low_bits).inverse(), diff);
+        (low, high)
+    }
+
+    fn binary_op_limbs(
+        &mut self,
+        table: BitwiseTable,
+        x: &[Target],
+        y: &[Target],
+        limb_bits: usize,
+    ) -> Vec<Target> {
+        Self::assert_limb_bits(limb_bits);
+        assert_eq!(
+            x.len(),
+            y.len(),
+            "Operands must have the same number of limbs."
+        );
+        let lut = self.bitwise_lut(table);
+        let base = F::from_canonical_u64(1 << limb_bits);
+        x.iter()
+            .zip(y)
+            .map(|(&x_limb, &y_limb)| {
+                let packed = self.mul_const_add(base, x_limb, y_limb);
+                self.add_lookup_from_index(packed, lut)
+            })
+            .collect()
+    }
+
+    /// Returns the index of the given bitwise lookup table, registering it if needed.
+    fn bitwise_lut(&mut self, table: BitwiseTable) -> usize {
+        if let Some(&index) = self.bitwise_luts.get(&table) {
+            return index;
+        }
+        let index = self.add_lookup_table_from_pairs(Arc::new(table.pairs()));
+        self.bitwise_luts.insert(table, index);
+        index
+    }
+
+    fn assert_limb_bits(limb_bits: usize) {
+        assert!(
+            (1..=MAX_LIMB_BITS).contains(&limb_bits),
+            "Limbs must have between 1 and {MAX_LIMB_BITS} bits."
+        );
+    }
+}
+
+/// Splits an integer into little-endian limbs of `limb_bits` bits.
+#[derive(Debug, Default)]
+pub struct LimbSplitGenerator {
+    integer: Target,
+    limb_bits: usize,
+    limbs: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for LimbSplitGenerator {
+    fn id(&self) -> String {
+        "LimbSplitGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        let mask = (1 << self.limb_bits) - 1;
+        for &limb in &self.limbs {
+            out_buffer.set_target(limb, F::from_canonical_u64(integer_value & mask))?;
+            integer_value >>= self.limb_bits;
+        }
+
+        ensure!(
+            integer_value == 0,
+            "Integer too large to fit in given number of limbs"
+        );
+        Ok(())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_usize(self.limb_bits)?;
+        dst.write_target_vec(&self.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let limb_bits = src.read_usize()?;
+        let limbs = src.read_target_vec()?;
+        Ok(Self {
+            integer,
+            limb_bits,
+            limbs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::types::Field;
+    use crate::iop::target::Target;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn connect_limbs(builder: &mut CircuitBuilder<F, D>, limbs: &[Target], expected: u32) {
+        let sum = builder.le_sum_limbs(limbs, 32 / limbs.len());
+        let expected = builder.constant(F::from_canonical_u32(expected));
+        builder.connect(sum, expected);
+    }
+
+    #[test]
+    fn test_binary_ops() -> Result<()> {
+        let mut rng = OsRng;
+        let (x, y) = (rng.gen::<u32>(), rng.gen::<u32>());
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_target();
+        let yt = builder.add_virtual_target();
+        let x_limbs = builder.split_le_limbs(xt, 32, 8);
+        let y_limbs = builder.split_le_limbs(yt, 32, 8);
+
+        let and = builder.and_limbs(&x_limbs, &y_limbs, 8);
+        connect_limbs(&mut builder, &and, x & y);
+        let xor = builder.xor_limbs(&x_limbs, &y_limbs, 8);
+        connect_limbs(&mut builder, &xor, x ^ y);
+        // The AND table is reused.
+        let and_not = builder.not_limbs(&y_limbs, 8);
+        let and_not = builder.and_limbs(&x_limbs, &and_not, 8);
+        connect_limbs(&mut builder, &and_not, x & !y);
+        assert_eq!(builder.num_luts(), 3);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(xt, F::from_canonical_u32(x))?;
+        pw.set_target(yt, F::from_canonical_u32(y))?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_shifts() -> Result<()> {
+        let mut rng = OsRng;
+        let x = rng.gen::<u32>();
+        let amounts = [0, 3, 4, 13, 31];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_target();
+        let x_limbs = builder.split_le_limbs(xt, 32, 4);
+        for s in amounts {
+            let rotated = builder.rotate_right_limbs(&x_limbs, 4, s);
+            connect_limbs(&mut builder, &rotated, x.rotate_right(s as u32));
+            let shifted = builder.shift_right_limbs(&x_limbs, 4, s);
+            connect_limbs(&mut builder, &shifted, x >> s);
+            let shifted = builder.shift_left_limbs(&x_limbs, 4, s);
+            connect_limbs(&mut builder, &shifted, x << s);
+        }
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(xt, F::from_canonical_u32(x))?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_split_le_limbs_out_of_range() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_target();
+        builder.split_le_limbs(xt, 16, 8);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(xt, F::from_canonical_u32(1 << 16)).unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+}
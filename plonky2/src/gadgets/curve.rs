@@ -0,0 +1,263 @@
+//! Gadgets for arithmetic on the points of a short Weierstrass curve, whose coordinates are
+//! non-native field elements.
+//!
+//! Points are represented in affine coordinates, so the point at infinity cannot be represented.
+//! The addition and doubling gadgets use incomplete formulas: the circuit is unsatisfiable if an
+//! exceptional case (such as adding two points with the same `x` coordinate) is hit.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::extension::Extendable;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::BoolTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A point on the curve `C`, represented in affine coordinates.
+#[derive(Clone, Debug)]
+pub struct AffinePointTarget<C: Curve> {
+    pub x: NonNativeTarget<C::BaseField>,
+    pub y: NonNativeTarget<C::BaseField>,
+}
+
+impl<C: Curve> AffinePointTarget<C> {
+    pub fn to_vec(&self) -> Vec<NonNativeTarget<C::BaseField>> {
+        vec![self.x.clone(), self.y.clone()]
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_affine_point<C: Curve>(
+        &mut self,
+        point: AffinePoint<C>,
+    ) -> AffinePointTarget<C> {
+        assert!(!point.zero, "The point at infinity cannot be represented");
+        AffinePointTarget {
+            x: self.constant_nonnative(point.x),
+            y: self.constant_nonnative(point.y),
+        }
+    }
+
+    pub fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    ) {
+        self.connect_nonnative(&lhs.x, &rhs.x);
+        self.connect_nonnative(&lhs.y, &rhs.y);
+    }
+
+    /// Adds a new virtual point, whose coordinates are canonical. This does not check that the
+    /// point is on the curve; see [`Self::curve_assert_valid`].
+    pub fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C> {
+        let x = self.add_virtual_nonnative_target();
+        let y = self.add_virtual_nonnative_target();
+
+        AffinePointTarget { x, y }
+    }
+
+    /// Asserts that `p` satisfies the curve equation `y^2 = x^3 + A x + B`.
+    pub fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        let a = self.constant_nonnative(C::A);
+        let b = self.constant_nonnative(C::B);
+
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let x_cubed = self.mul_nonnative(&x_squared, &p.x);
+        let a_x = self.mul_nonnative(&a, &p.x);
+        let a_x_plus_b = self.add_nonnative(&a_x, &b);
+        let rhs = self.add_nonnative(&x_cubed, &a_x_plus_b);
+
+        self.connect_nonnative(&y_squared, &rhs);
+    }
+
+    pub fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: neg_y,
+        }
+    }
+
+    /// Returns `-p` if `b` is true, and `p` otherwise.
+    pub fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: self.select_nonnative(b, &neg_y, &p.y),
+        }
+    }
+
+    /// Selects `p` or `q` based on `b`, i.e., this returns `if b { p } else { q }`.
+    pub fn select_affine_point<C: Curve>(
+        &mut self,
+        b: BoolTarget,
+        p: &AffinePointTarget<C>,
+        q: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: self.select_nonnative(b, &p.x, &q.x),
+            y: self.select_nonnative(b, &p.y, &q.y),
+        }
+    }
+
+    /// Doubles `p`. The circuit is unsatisfiable if the `y` coordinate of `p` is zero.
+    pub fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        let AffinePointTarget { x, y } = p;
+
+        // lambda = (3 x^2 + A) / (2 y)
+        let a = self.constant_nonnative(C::A);
+        let x_squared = self.mul_nonnative(x, x);
+        let two_x_squared = self.add_nonnative(&x_squared, &x_squared);
+        let three_x_squared = self.add_nonnative(&two_x_squared, &x_squared);
+        let numerator = self.add_nonnative(&three_x_squared, &a);
+        let two_y = self.add_nonnative(y, y);
+        let two_y_inv = self.inv_nonnative(&two_y);
+        let lambda = self.mul_nonnative(&numerator, &two_y_inv);
+
+        self.curve_add_with_slope(p, x, &lambda)
+    }
+
+    pub fn curve_repeated_double<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: usize,
+    ) -> AffinePointTarget<C> {
+        let mut result = p.clone();
+        for _ in 0..n {
+            result = self.curve_double(&result);
+        }
+        result
+    }
+
+    /// Adds `p1` and `p2`. The circuit is unsatisfiable if they have the same `x` coordinate.
+    pub fn curve_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        // lambda = (y2 - y1) / (x2 - x1)
+        let dy = self.sub_nonnative(&p2.y, &p1.y);
+        let dx = self.sub_nonnative(&p2.x, &p1.x);
+        let dx_inv = self.inv_nonnative(&dx);
+        let lambda = self.mul_nonnative(&dy, &dx_inv);
+
+        self.curve_add_with_slope(p1, &p2.x, &lambda)
+    }
+
+    /// Returns `p1 + p2` if `b` is true, and `p1` otherwise. The sum is computed in either case,
+    /// so the same restrictions as in [`Self::curve_add`] apply.
+    pub fn curve_conditional_add<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        p2: &AffinePointTarget<C>,
+        b: BoolTarget,
+    ) -> AffinePointTarget<C> {
+        let sum = self.curve_add(p1, p2);
+        self.select_affine_point(b, &sum, p1)
+    }
+
+    /// Given `p1`, the `x` coordinate `x2` of a second point and the slope `lambda` of the line
+    /// through them (or of the tangent, when doubling), computes the third point of intersection
+    /// of that line with the curve, negated.
+    fn curve_add_with_slope<C: Curve>(
+        &mut self,
+        p1: &AffinePointTarget<C>,
+        x2: &NonNativeTarget<C::BaseField>,
+        lambda: &NonNativeTarget<C::BaseField>,
+    ) -> AffinePointTarget<C> {
+        let AffinePointTarget { x: x1, y: y1 } = p1;
+
+        // x3 = lambda^2 - x1 - x2
+        let lambda_squared = self.mul_nonnative(lambda, lambda);
+        let x1_plus_x2 = self.add_nonnative(x1, x2);
+        let x3 = self.sub_nonnative(&lambda_squared, &x1_plus_x2);
+
+        // y3 = lambda (x1 - x3) - y1
+        let x1_minus_x3 = self.sub_nonnative(x1, &x3);
+        let lambda_times_dx = self.mul_nonnative(lambda, &x1_minus_x3);
+        let y3 = self.sub_nonnative(&lambda_times_dx, y1);
+
+        AffinePointTarget { x: x3, y: y3 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::nonnative::WitnessWriteNonNative;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_curve_arithmetic() -> Result<()> {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p =
+            (CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE).to_affine();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        builder.curve_assert_valid(&pt);
+        let gt = builder.constant_affine_point(g);
+
+        let sum = builder.curve_add(&gt, &pt);
+        let expected_sum = builder.constant_affine_point(g + p);
+        builder.connect_affine_point(&sum, &expected_sum);
+
+        let double = builder.curve_double(&pt);
+        let expected_double = builder.constant_affine_point(p.double());
+        builder.connect_affine_point(&double, &expected_double);
+
+        let neg = builder.curve_neg(&pt);
+        let expected_neg = builder.constant_affine_point(-p);
+        builder.connect_affine_point(&neg, &expected_neg);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&pt.x, p.x)?;
+        pw.set_nonnative_target(&pt.y, p.y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_curve_point_is_not_valid() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let not_a_point = AffinePoint::<Secp256K1>::nonzero(g.x, g.y + Secp256K1Base::ONE);
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        builder.curve_assert_valid(&pt);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&pt.x, not_a_point.x).unwrap();
+        pw.set_nonnative_target(&pt.y, not_a_point.y).unwrap();
+
+        assert!(data.prove(pw).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use keccak_hash::keccak;
+use num::BigUint;
+
+use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gadgets::biguint::BigUintTarget;
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::gadgets::uint::U32Target;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+const WINDOW_SIZE: usize = 4;
+
+/// The initial value of the accumulator in [`CircuitBuilder::curve_scalar_mul_windowed`]. Starting
+/// from a point unrelated to the inputs avoids the exceptional cases of incomplete addition, such
+/// as adding a point to the point at infinity, which cannot be represented.
+fn starting_point<C: Curve>() -> AffinePoint<C> {
+    let hash = keccak(b"plonky2 windowed scalar multiplication").0;
+    let scalar = C::ScalarField::from_noncanonical_biguint(
+        BigUint::from_bytes_le(&hash) % C::ScalarField::order(),
+    );
+    (CurveScalar(scalar) * C::GENERATOR_PROJECTIVE).to_affine()
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes `[q, p, 2p, ..., (2^WINDOW_SIZE - 1) p]`, where `q` is an arbitrary point which is
+    /// never added to the accumulator.
+    pub fn precompute_window<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+    ) -> Vec<AffinePointTarget<C>> {
+        let mut multiples = Vec::with_capacity(1 << WINDOW_SIZE);
+        multiples.push(self.constant_affine_point(C::GENERATOR_AFFINE));
+        multiples.push(p.clone());
+        multiples.push(self.curve_double(p));
+        for i in 3..1 << WINDOW_SIZE {
+            let multiple = self.curve_add(&multiples[i - 1], p);
+            multiples.push(multiple);
+        }
+        multiples
+    }
+
+    /// Returns `v[access_index]`, limb by limb.
+    pub fn random_access_curve_points<C: Curve>(
+        &mut self,
+        access_index: Target,
+        v: &[AffinePointTarget<C>],
+    ) -> AffinePointTarget<C> {
+        let num_limbs = v[0].x.value.num_limbs();
+        let mut random_access_coordinate =
+            |coordinate: &dyn Fn(&AffinePointTarget<C>) -> &NonNativeTarget<C::BaseField>| {
+                let limbs = (0..num_limbs)
+                    .map(|i| {
+                        let limb_i = v
+                            .iter()
+                            .map(|p| coordinate(p).value.get_limb(i).target)
+                            .collect();
+                        U32Target::new_unsafe(self.random_access(access_index, limb_i))
+                    })
+                    .collect();
+                NonNativeTarget::new_unsafe(BigUintTarget { limbs })
+            };
+
+        let x = random_access_coordinate(&|p| &p.x);
+        let y = random_access_coordinate(&|p| &p.y);
+        AffinePointTarget { x, y }
+    }
+
+    /// Splits `x` into little-endian windows of `WINDOW_SIZE` bits. A base-16 decomposition would
+    /// need a gate of degree 16, so we decompose in base 4 and then combine pairs of digits.
+    fn split_u32_to_windows(&mut self, x: U32Target) -> Vec<Target> {
+        let base_4_digits = self.split_le_base::<4>(x.target, 16);
+        let four = self.constant(F::from_canonical_usize(4));
+        base_4_digits
+            .chunks(2)
+            .map(|pair| self.mul_add(four, pair[1], pair[0]))
+            .collect()
+    }
+
+    /// Computes `n * p` with a fixed-window method, processing `WINDOW_SIZE` bits of `n` at a
+    /// time.
+    ///
+    /// The accumulator starts from a fixed point, whose contribution is subtracted at the end. The
+    /// circuit is unsatisfiable if an intermediate addition hits an exceptional case, which can
+    /// only happen for negligibly few inputs, or if `n * p` is the point at infinity.
+    pub fn curve_scalar_mul_windowed<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        let windows = n
+            .value
+            .limbs
+            .iter()
+            .flat_map(|limb| self.split_u32_to_windows(*limb))
+            .collect::<Vec<_>>();
+        self.curve_scalar_mul_windows(p, &windows)
+    }
+
+    /// Computes `n * p`, where `n` is given by its little-endian windows of `WINDOW_SIZE` bits.
+    fn curve_scalar_mul_windows<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        windows: &[Target],
+    ) -> AffinePointTarget<C> {
+        let start = starting_point::<C>();
+        let start_multiplied = {
+            let num_bits = WINDOW_SIZE * windows.len();
+            let mut x = start.to_projective();
+            for _ in 0..num_bits {
+                x = x.double();
+            }
+            x.to_affine()
+        };
+
+        let precomputation = self.precompute_window(p);
+
+        let zero = self.zero();
+        let mut result = self.constant_affine_point(start);
+        for &window in windows.iter().rev() {
+            result = self.curve_repeated_double(&result, WINDOW_SIZE);
+            let to_add = self.random_access_curve_points(window, &precomputation);
+            let is_zero = self.is_equal(window, zero);
+            let should_add = self.not(is_zero);
+            result = self.curve_conditional_add(&result, &to_add, should_add);
+        }
+
+        let to_subtract = self.constant_affine_point(-start_multiplied);
+        self.curve_add(&result, &to_subtract)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::nonnative::WitnessWriteNonNative;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_random_access_curve_points() -> Result<()> {
+        let points = Secp256K1Scalar::rand_vec(4)
+            .into_iter()
+            .map(|x| (CurveScalar(x) * Secp256K1::GENERATOR_PROJECTIVE).to_affine())
+            .collect::<Vec<_>>();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let point_targets = points
+            .iter()
+            .map(|&p| builder.constant_affine_point(p))
+            .collect::<Vec<_>>();
+        let index = builder.add_virtual_target();
+        let selected = builder.random_access_curve_points(index, &point_targets);
+        let expected = builder.constant_affine_point(points[2]);
+        builder.connect_affine_point(&selected, &expected);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(index, F::from_canonical_usize(2))?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_curve_scalar_mul_single_window() -> Result<()> {
+        let p =
+            (CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE).to_affine();
+        let n = 11;
+
+        let config = CircuitConfig::wide_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let window = builder.add_virtual_target();
+        let product = builder.curve_scalar_mul_windows(&pt, &[window]);
+        let expected = builder.constant_affine_point(
+            (CurveScalar(Secp256K1Scalar::from_canonical_usize(n)) * p).to_affine(),
+        );
+        builder.connect_affine_point(&product, &expected);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&pt.x, p.x)?;
+        pw.set_nonnative_target(&pt.y, p.y)?;
+        pw.set_target(window, F::from_canonical_usize(n))?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_curve_scalar_mul_windowed() -> Result<()> {
+        let p =
+            (CurveScalar(Secp256K1Scalar::rand()) * Secp256K1::GENERATOR_PROJECTIVE).to_affine();
+        let n = Secp256K1Scalar::from_canonical_u32(0x9e37_79b9);
+
+        let config = CircuitConfig::wide_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        // A constant scalar only has as many limbs as its value needs, so this multiplication
+        // processes the windows of a single limb rather than those of a full scalar, which keeps
+        // the circuit small enough to be proven in tests.
+        let nt = builder.constant_nonnative(n);
+        let product = builder.curve_scalar_mul_windowed(&pt, &nt);
+        let expected = builder.constant_affine_point((CurveScalar(n) * p).to_affine());
+        builder.connect_affine_point(&product, &expected);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&pt.x, p.x)?;
+        pw.set_nonnative_target(&pt.y, p.y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -0,0 +1,159 @@
+use crate::curve::curve_types::Curve;
+use crate::field::extension::Extendable;
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+#[derive(Clone, Debug)]
+pub struct ECDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSASignatureTarget<C: Curve> {
+    pub r: NonNativeTarget<C::ScalarField>,
+    pub s: NonNativeTarget<C::ScalarField>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_ecdsa_public_key_target<C: Curve>(&mut self) -> ECDSAPublicKeyTarget<C> {
+        ECDSAPublicKeyTarget(self.add_virtual_affine_point_target())
+    }
+
+    pub fn add_virtual_ecdsa_signature_target<C: Curve>(&mut self) -> ECDSASignatureTarget<C> {
+        ECDSASignatureTarget {
+            r: self.add_virtual_nonnative_target(),
+            s: self.add_virtual_nonnative_target(),
+        }
+    }
+
+    /// Verifies an ECDSA signature of `msg`, which is assumed to already be hashed, under the
+    /// public key `pk`. The public key is checked to be on the curve.
+    ///
+    /// The circuit is unsatisfiable if the signature is invalid. It may also be unsatisfiable for
+    /// a negligible fraction of valid signatures, for which the incomplete curve arithmetic hits an
+    /// exceptional case.
+    pub fn verify_ecdsa_message<C: Curve>(
+        &mut self,
+        msg: &NonNativeTarget<C::ScalarField>,
+        sig: &ECDSASignatureTarget<C>,
+        pk: &ECDSAPublicKeyTarget<C>,
+    ) {
+        let ECDSASignatureTarget { r, s } = sig;
+
+        self.curve_assert_valid(&pk.0);
+
+        let c = self.inv_nonnative(s);
+        let u1 = self.mul_nonnative(msg, &c);
+        let u2 = self.mul_nonnative(r, &c);
+
+        let g = self.constant_affine_point(C::GENERATOR_AFFINE);
+        let point1 = self.curve_scalar_mul_windowed(&g, &u1);
+        let point2 = self.curve_scalar_mul_windowed(&pk.0, &u2);
+        let point = self.curve_add(&point1, &point2);
+
+        let x = self.nonnative_to_canonical_biguint(&point.x);
+        let x = self.biguint_to_nonnative::<C::ScalarField>(&x);
+        self.connect_nonnative(r, &x);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::ecdsa::{sign_message, ECDSASecretKey};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::nonnative::WitnessWriteNonNative;
+    use crate::iop::generator::generate_partial_witness;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn test_ecdsa_circuit_with_config(config: CircuitConfig) -> Result<()> {
+        type Curve = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<Curve>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+        let sig = sign_message(msg, sk);
+
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let msg_target = builder.add_virtual_nonnative_target();
+        let pk_target = builder.add_virtual_ecdsa_public_key_target::<Curve>();
+        let sig_target = builder.add_virtual_ecdsa_signature_target::<Curve>();
+        builder.verify_ecdsa_message(&msg_target, &sig_target, &pk_target);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&msg_target, msg)?;
+        pw.set_nonnative_target(&pk_target.0.x, pk.0.x)?;
+        pw.set_nonnative_target(&pk_target.0.y, pk.0.y)?;
+        pw.set_nonnative_target(&sig_target.r, sig.r)?;
+        pw.set_nonnative_target(&sig_target.s, sig.s)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_ecdsa_witness_generation() -> Result<()> {
+        type Curve = Secp256K1;
+
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<Curve>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+        let sig = sign_message(msg, sk);
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::wide_ecc_config());
+        let msg_target = builder.add_virtual_nonnative_target();
+        let pk_target = builder.add_virtual_ecdsa_public_key_target::<Curve>();
+        let sig_target = builder.add_virtual_ecdsa_signature_target::<Curve>();
+        builder.verify_ecdsa_message(&msg_target, &sig_target, &pk_target);
+        let data = builder.mock_build::<C>();
+
+        let witness = |msg| -> Result<PartialWitness<F>> {
+            let mut pw = PartialWitness::new();
+            pw.set_nonnative_target(&msg_target, msg)?;
+            pw.set_nonnative_target(&pk_target.0.x, pk.0.x)?;
+            pw.set_nonnative_target(&pk_target.0.y, pk.0.y)?;
+            pw.set_nonnative_target(&sig_target.r, sig.r)?;
+            pw.set_nonnative_target(&sig_target.s, sig.s)?;
+            Ok(pw)
+        };
+
+        // The recomputed `r` is connected to the claimed one, so that witness generation fails
+        // for a signature of another message.
+        generate_partial_witness(witness(msg)?, &data.prover_only, &data.common)?;
+        assert!(generate_partial_witness(
+            witness(msg + Secp256K1Scalar::ONE)?,
+            &data.prover_only,
+            &data.common
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    // Both of these circuits have 2^18 rows or more, and building and proving them needs
+    // considerably more memory than a CI runner has. `test_ecdsa_witness_generation` covers the
+    // same circuit without proving it.
+    #[test]
+    #[ignore]
+    fn test_ecdsa_circuit_narrow() -> Result<()> {
+        test_ecdsa_circuit_with_config(CircuitConfig::standard_ecc_config())
+    }
+
+    #[test]
+    #[ignore]
+    fn test_ecdsa_circuit_wide() -> Result<()> {
+        test_ecdsa_circuit_with_config(CircuitConfig::wide_ecc_config())
+    }
+}
@@ -0,0 +1,225 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::field::extension::Extendable;
+use crate::gates::keccak::{KeccakSliceGate, NUM_LANES};
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of rounds of Keccak-f\[1600\].
+const NUM_ROUNDS: usize = 24;
+
+/// The number of bits in a lane of the Keccak-f\[1600\] state.
+const LANE_BITS: usize = 64;
+
+/// The rate of Keccak-256, in bytes.
+const KECCAK256_RATE_BYTES: usize = 136;
+
+/// The round constants added by the ι step.
+const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the ρ step, for lane `(x, y)` at index `x + 5y`.
+const ROTATION_OFFSETS: [usize; NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// A Keccak-f\[1600\] state, as 25 lanes of 64 bits in little-endian order, with lane `(x, y)` at
+/// index `x + 5y`.
+pub type KeccakStateTarget = [[BoolTarget; LANE_BITS]; NUM_LANES];
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the Keccak-256 digest of the given bytes, as used in Ethereum. The inputs are
+    /// range-checked to be bytes.
+    pub fn keccak256(&mut self, bytes: &[Target]) -> [Target; 32] {
+        // Apply the `pad10*1` rule, with the domain separation bit of the original Keccak.
+        let num_padding_bytes = KECCAK256_RATE_BYTES - bytes.len() % KECCAK256_RATE_BYTES;
+        let mut padding = vec![0u8; num_padding_bytes];
+        padding[0] = 0x01;
+        padding[num_padding_bytes - 1] |= 0x80;
+
+        let mut bits = Vec::with_capacity(8 * (bytes.len() + num_padding_bytes));
+        for &byte in bytes {
+            bits.extend(self.split_le(byte, 8));
+        }
+        for byte in padding {
+            bits.extend((0..8).map(|i| self.constant_bool(byte >> i & 1 == 1)));
+        }
+
+        let _false = self._false();
+        let mut state = [[_false; LANE_BITS]; NUM_LANES];
+        for (i, block) in bits.chunks(8 * KECCAK256_RATE_BYTES).enumerate() {
+            for (j, &bit) in block.iter().enumerate() {
+                let lane_bit = &mut state[j / LANE_BITS][j % LANE_BITS];
+                // The state is zero before the first block is absorbed.
+                *lane_bit = if i == 0 {
+                    bit
+                } else {
+                    self.xor(*lane_bit, bit)
+                };
+            }
+            state = self.keccak_f(state);
+        }
+
+        core::array::from_fn(|i| self.le_sum(state[i / 8][8 * (i % 8)..8 * (i % 8 + 1)].iter()))
+    }
+
+    /// Applies the Keccak-f\[1600\] permutation to the given state, whose bits are assumed to be
+    /// boolean.
+    pub fn keccak_f(&mut self, state: KeccakStateTarget) -> KeccakStateTarget {
+        assert!(
+            self.config.num_routed_wires >= KeccakSliceGate::NUM_ROUTED_WIRES,
+            "Not enough routed wires."
+        );
+
+        let _false = self._false();
+        let mut inputs = state;
+        let mut outputs = state;
+        // The first set of gates only computes the θ step of the first round, and the last set
+        // only the χ and ι steps of the last round.
+        for step in 0..=NUM_ROUNDS {
+            let chi = step != 0;
+            let theta = step != NUM_ROUNDS;
+            let constants = vec![F::from_bool(chi), F::from_bool(theta)];
+            let rows = (0..LANE_BITS)
+                .map(|_| self.add_gate(KeccakSliceGate::new(), constants.clone()))
+                .collect::<Vec<_>>();
+
+            for (z, &row) in rows.iter().enumerate() {
+                for (i, lane) in inputs.iter().enumerate() {
+                    self.connect(
+                        lane[z].target,
+                        Target::wire(row, KeccakSliceGate::wire_input(i)),
+                    );
+                }
+                let round_constant = if chi {
+                    self.constant_bool(ROUND_CONSTANTS[step - 1] >> z & 1 == 1)
+                } else {
+                    _false
+                };
+                self.connect(
+                    round_constant.target,
+                    Target::wire(row, KeccakSliceGate::WIRE_ROUND_CONSTANT),
+                );
+                let prev_row = rows[(z + LANE_BITS - 1) % LANE_BITS];
+                for x in 0..5 {
+                    self.connect(
+                        Target::wire(prev_row, KeccakSliceGate::wire_parity(x)),
+                        Target::wire(row, KeccakSliceGate::wire_prev_parity(x)),
+                    );
+                }
+            }
+
+            // `new_unsafe` is safe here since the outputs of `KeccakSliceGate` are bits whenever
+            // its inputs are.
+            outputs = core::array::from_fn(|i| {
+                core::array::from_fn(|z| {
+                    BoolTarget::new_unsafe(Target::wire(rows[z], KeccakSliceGate::wire_output(i)))
+                })
+            });
+
+            // The ρ and π steps of the next round.
+            for (i, lane) in outputs.iter().enumerate() {
+                let (x, y) = (i % 5, i / 5);
+                let target_lane = y + 5 * ((2 * x + 3 * y) % 5);
+                for (z, &bit) in lane.iter().enumerate() {
+                    inputs[target_lane][(z + ROTATION_OFFSETS[i]) % LANE_BITS] = bit;
+                }
+            }
+        }
+
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use keccak_hash::keccak;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn test_keccak256(len: usize) -> Result<()> {
+        let mut rng = OsRng;
+        let input = (0..len).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+        let expected = keccak(&input).0;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let bytes = builder.add_virtual_targets(len);
+        let digest = builder.keccak256(&bytes);
+        for (&d, e) in digest.iter().zip(expected) {
+            let e = builder.constant(F::from_canonical_u8(e));
+            builder.connect(d, e);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&b, &x) in bytes.iter().zip(&input) {
+            pw.set_target(b, F::from_canonical_u8(x))?;
+        }
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_keccak256_empty() -> Result<()> {
+        test_keccak256(0)
+    }
+
+    #[test]
+    fn test_keccak256_two_blocks() -> Result<()> {
+        // Padding this message requires a second block.
+        test_keccak256(136)
+    }
+
+    #[test]
+    fn test_keccak256_rejects_non_bytes() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let byte = builder.add_virtual_target();
+        builder.keccak256(&[byte]);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(byte, F::from_canonical_u16(256)).unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+}
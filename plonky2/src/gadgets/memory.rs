@@ -0,0 +1,388 @@
+//! A read-write memory, checked offline.
+//!
+//! Reads and writes are recorded in program order as they are issued, and the consistency of the
+//! whole trace is only enforced when the circuit is built. The prover supplies a copy of the trace
+//! sorted by address and then by time. The circuit checks that it is a permutation of the original
+//! trace, that it is indeed sorted, and that each read returns the value of the last write to the
+//! same address, or zero if there was none.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use anyhow::Result;
+use hashbrown::HashMap;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::log2_ceil;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The number of bits of memory addresses. Addresses are range-checked when the circuit is built.
+pub const MEMORY_ADDRESS_BITS: usize = 32;
+
+/// The number of targets in each row of a memory trace: address, timestamp, value and whether
+/// the operation is a write.
+const MEMORY_TRACE_WIDTH: usize = 4;
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct MemoryOp {
+    addr: Target,
+    value: Target,
+    is_write: bool,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Reads the value at `addr`. Memory is initially zero.
+    pub fn mem_read(&mut self, addr: Target) -> Target {
+        let value = self.add_virtual_target();
+        self.memory_ops.push(MemoryOp {
+            addr,
+            value,
+            is_write: false,
+        });
+        value
+    }
+
+    /// Writes `value` at `addr`.
+    pub fn mem_write(&mut self, addr: Target, value: Target) {
+        self.memory_ops.push(MemoryOp {
+            addr,
+            value,
+            is_write: true,
+        });
+    }
+
+    /// We call this function at the end of circuit building to check the consistency of all the
+    /// memory operations.
+    pub(crate) fn add_all_memory_checks(&mut self) {
+        let ops = core::mem::take(&mut self.memory_ops);
+        if ops.is_empty() {
+            return;
+        }
+
+        let timestamp_bits = log2_ceil(ops.len());
+        let key_bits = MEMORY_ADDRESS_BITS + timestamp_bits;
+        assert!(key_bits < F::BITS - 1, "Too many memory operations");
+
+        self.add_simple_generator(MemoryReadGenerator {
+            ops: ops.iter().flat_map(|op| [op.addr, op.value]).collect(),
+            is_write: ops.iter().map(|op| op.is_write).collect(),
+        });
+
+        let trace = ops
+            .iter()
+            .enumerate()
+            .map(|(i, op)| {
+                vec![
+                    op.addr,
+                    self.constant(F::from_canonical_usize(i)),
+                    op.value,
+                    self.constant_bool(op.is_write).target,
+                ]
+            })
+            .collect::<Vec<_>>();
+        let sorted_trace = (0..ops.len())
+            .map(|_| self.add_virtual_targets(MEMORY_TRACE_WIDTH))
+            .collect::<Vec<_>>();
+        self.add_simple_generator(MemorySortGenerator {
+            trace: trace.concat(),
+            sorted_trace: sorted_trace.concat(),
+        });
+
+        // Since the sorted trace is a permutation of the original one, its timestamps are distinct
+        // and less than `2^timestamp_bits`, and its write flags are boolean.
+        self.assert_permutation_tuples(&trace, &sorted_trace);
+
+        // Check that the sorted trace is sorted by address, then by timestamp.
+        let timestamp_base = self.constant(F::from_canonical_u64(1 << timestamp_bits));
+        let keys = sorted_trace
+            .iter()
+            .map(|row| {
+                self.range_check(row[0], MEMORY_ADDRESS_BITS);
+                self.mul_add(row[0], timestamp_base, row[1])
+            })
+            .collect::<Vec<_>>();
+        self.assert_strictly_sorted(&keys, key_bits);
+
+        // Check that each read returns the value of the previous operation at the same address, if
+        // any, and zero otherwise.
+        let zero = self.zero();
+        self.assert_read_value(&sorted_trace[0], zero);
+        for rows in sorted_trace.windows(2) {
+            let same_addr = self.is_equal(rows[0][0], rows[1][0]);
+            let expected = self.mul(same_addr.target, rows[0][2]);
+            self.assert_read_value(&rows[1], expected);
+        }
+    }
+
+    /// Asserts that the value of `row` is `expected`, unless it is a write.
+    fn assert_read_value(&mut self, row: &[Target], expected: Target) {
+        let one = self.one();
+        let is_read = self.sub(one, row[3]);
+        let diff = self.sub(row[2], expected);
+        let read_diff = self.mul(is_read, diff);
+        self.assert_zero(read_diff);
+    }
+}
+
+/// Generates the values returned by all reads, by replaying the memory operations in program order.
+///
+/// Since a write may depend on the value of an earlier read, this generator does not wait for all
+/// of its dependencies: each run replays the operations up to the first one whose address or
+/// written value is still unknown, and the generator is finished once all of them are replayed.
+#[derive(Debug, Default)]
+pub struct MemoryReadGenerator {
+    /// The `(address, value)` pairs of all memory operations in program order, flattened.
+    ops: Vec<Target>,
+    is_write: Vec<bool>,
+}
+
+impl MemoryReadGenerator {
+    /// Replays the memory operations, returning whether all of them could be replayed.
+    fn replay<F: RichField>(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<bool> {
+        let mut memory = HashMap::new();
+        for (op, &is_write) in self.ops.chunks_exact(2).zip(&self.is_write) {
+            let Some(addr) = witness.try_get_target(op[0]) else {
+                return Ok(false);
+            };
+            if is_write {
+                let Some(value) = witness.try_get_target(op[1]) else {
+                    return Ok(false);
+                };
+                memory.insert(addr.to_canonical_u64(), value);
+            } else if witness.try_get_target(op[1]).is_none() {
+                let value = memory
+                    .get(&addr.to_canonical_u64())
+                    .copied()
+                    .unwrap_or(F::ZERO);
+                out_buffer.set_target(op[1], value)?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for MemoryReadGenerator {
+    fn id(&self) -> String {
+        "MemoryReadGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        // The addresses of all operations, and the values of all writes.
+        self.ops
+            .chunks_exact(2)
+            .zip(&self.is_write)
+            .flat_map(|(op, &is_write)| if is_write { op } else { &op[..1] })
+            .copied()
+            .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        self.replay(witness, out_buffer).map(drop)
+    }
+
+    fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool {
+        self.replay(witness, out_buffer).unwrap_or(false)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.ops)?;
+        dst.write_usize(self.is_write.len())?;
+        for &is_write in &self.is_write {
+            dst.write_bool(is_write)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let ops = src.read_target_vec()?;
+        let len = src.read_usize()?;
+        let is_write = (0..len)
+            .map(|_| src.read_bool())
+            .collect::<IoResult<Vec<_>>>()?;
+        Ok(Self { ops, is_write })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemorySortGenerator {
+    /// The rows of the memory trace in program order, flattened.
+    trace: Vec<Target>,
+    /// The rows of the memory trace sorted by address and timestamp, flattened.
+    sorted_trace: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for MemorySortGenerator {
+    fn id(&self) -> String {
+        "MemorySortGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.trace.clone()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let mut rows = self
+            .trace
+            .chunks_exact(MEMORY_TRACE_WIDTH)
+            .map(|row| witness.get_targets(row))
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|row| (row[0].to_canonical_u64(), row[1].to_canonical_u64()));
+
+        out_buffer.set_target_arr(&self.sorted_trace, &rows.concat())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.trace)?;
+        dst.write_target_vec(&self.sorted_trace)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let trace = src.read_target_vec()?;
+        let sorted_trace = src.read_target_vec()?;
+        Ok(Self {
+            trace,
+            sorted_trace,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+    use crate::util::serialization::{DefaultGateSerializer, DefaultGeneratorSerializer};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_memory() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let addr_a = builder.add_virtual_target();
+        let addr_b = builder.constant(F::from_canonical_u64(1 << 20));
+        let addr_c = builder.constant(F::from_canonical_u64(7));
+        let ten = builder.constant(F::from_canonical_u64(10));
+        let twenty = builder.constant(F::from_canonical_u64(20));
+        let eleven = builder.constant(F::from_canonical_u64(11));
+
+        builder.mem_write(addr_a, ten);
+        builder.mem_write(addr_b, twenty);
+        let a_0 = builder.mem_read(addr_a);
+        builder.connect(a_0, ten);
+        builder.mem_write(addr_a, eleven);
+        let a_1 = builder.mem_read(addr_a);
+        builder.connect(a_1, eleven);
+        let b_0 = builder.mem_read(addr_b);
+        builder.connect(b_0, twenty);
+        let c_0 = builder.mem_read(addr_c);
+        builder.assert_zero(c_0);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(addr_a, F::from_canonical_u64(3))?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_memory_counter() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // Increment a counter stored at an address given by the witness.
+        let addr = builder.add_virtual_target();
+        let one = builder.one();
+        for _ in 0..20 {
+            let x = builder.mem_read(addr);
+            let x_plus_one = builder.add(x, one);
+            builder.mem_write(addr, x_plus_one);
+        }
+        let result = builder.mem_read(addr);
+        let expected = builder.constant(F::from_canonical_u64(20));
+        builder.connect(result, expected);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(addr, F::from_canonical_u64(12345))?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_memory_serialization() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let addr = builder.add_virtual_target();
+        let one = builder.one();
+        for _ in 0..3 {
+            let x = builder.mem_read(addr);
+            let x_plus_one = builder.add(x, one);
+            builder.mem_write(addr, x_plus_one);
+        }
+        let data = builder.build::<C>();
+
+        let gate_serializer = DefaultGateSerializer;
+        let generator_serializer = DefaultGeneratorSerializer::<C, D>::default();
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(|_| anyhow::Error::msg("CircuitData serialization failed."))?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(|_| anyhow::Error::msg("CircuitData deserialization failed."))?;
+        assert_eq!(data, data_from_bytes);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(addr, F::from_canonical_u64(5))?;
+        let proof = data_from_bytes.prove(pw)?;
+        data_from_bytes.verify(proof)
+    }
+
+    #[test]
+    fn test_memory_address_out_of_range() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let addr = builder.add_virtual_target();
+        let x = builder.mem_read(addr);
+        builder.assert_zero(x);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_target(addr, F::from_canonical_u64(1 << 40)).unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+}
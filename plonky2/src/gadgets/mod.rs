@@ -4,12 +4,23 @@
 
 pub mod arithmetic;
 pub mod arithmetic_extension;
+pub mod biguint;
+pub mod bitwise;
+pub mod curve;
+pub mod curve_windowed_mul;
+pub mod ecdsa;
 pub mod hash;
 pub mod interpolation;
+pub mod keccak;
 pub mod lookup;
+pub mod memory;
+pub mod nonnative;
 pub mod polynomial;
 pub mod random_access;
 pub mod range_check;
 pub mod select;
+pub mod sha256;
 pub mod split_base;
 pub mod split_join;
+pub mod uint;
+pub mod vec;
@@ -0,0 +1,592 @@
+//! Gadgets for arithmetic in a non-native prime field, whose elements are represented as integers
+//! split into 32-bit limbs.
+//!
+//! All the elements returned by these gadgets are canonical, i.e. less than the order of the field.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+use core::any::type_name;
+use core::marker::PhantomData;
+
+use anyhow::{ensure, Result};
+use num::Integer;
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField, PrimeField64};
+use crate::gadgets::biguint::{BigUintTarget, WitnessBigUint, WitnessWriteBigUint};
+use crate::gadgets::uint::U32Target;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// An element of the field `FF`, given by its canonical value.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NonNativeTarget<FF: Field> {
+    pub value: BigUintTarget,
+    _phantom: PhantomData<FF>,
+}
+
+impl<FF: Field> NonNativeTarget<FF> {
+    /// Wraps the given integer as an element of `FF`, without checking that it is canonical.
+    pub fn new_unsafe(value: BigUintTarget) -> Self {
+        Self {
+            value,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// The number of 32-bit limbs needed to represent elements of `FF`.
+pub fn num_nonnative_limbs<FF: Field>() -> usize {
+    (FF::order().bits() as usize).div_ceil(32)
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a new virtual element of `FF`, whose limbs are range-checked and whose value is checked
+    /// to be canonical.
+    pub fn add_virtual_nonnative_target<FF: Field>(&mut self) -> NonNativeTarget<FF> {
+        let x = self.add_virtual_nonnative_target_unchecked();
+        self.assert_canonical_nonnative(&x);
+        x
+    }
+
+    /// Reduces an integer modulo the order of `FF`.
+    pub fn biguint_to_nonnative<FF: Field>(&mut self, x: &BigUintTarget) -> NonNativeTarget<FF> {
+        let modulus = self.constant_biguint(&FF::order());
+        let value = self.rem_biguint(x, &modulus);
+        NonNativeTarget::new_unsafe(value)
+    }
+
+    pub fn nonnative_to_canonical_biguint<FF: Field>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> BigUintTarget {
+        x.value.clone()
+    }
+
+    pub fn constant_nonnative<FF: PrimeField>(&mut self, x: FF) -> NonNativeTarget<FF> {
+        let value = self.constant_biguint(&x.to_canonical_biguint());
+        NonNativeTarget::new_unsafe(value)
+    }
+
+    pub fn zero_nonnative<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        self.constant_nonnative(FF::ZERO)
+    }
+
+    pub fn connect_nonnative<FF: Field>(
+        &mut self,
+        lhs: &NonNativeTarget<FF>,
+        rhs: &NonNativeTarget<FF>,
+    ) {
+        self.connect_biguint(&lhs.value, &rhs.value);
+    }
+
+    /// Selects `x` or `y` based on `b`, i.e., this returns `if b { x } else { y }`.
+    pub fn select_nonnative<FF: Field>(
+        &mut self,
+        b: BoolTarget,
+        x: &NonNativeTarget<FF>,
+        y: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let limbs = x
+            .value
+            .limbs
+            .iter()
+            .zip(&y.value.limbs)
+            .map(|(x, y)| U32Target::new_unsafe(self.select(b, x.target, y.target)))
+            .collect();
+        NonNativeTarget::new_unsafe(BigUintTarget { limbs })
+    }
+
+    pub fn add_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let sum = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_bool_target_safe();
+        self.add_simple_generator(NonNativeAdditionGenerator::<FF> {
+            a: a.clone(),
+            b: b.clone(),
+            sum: sum.clone(),
+            overflow,
+        });
+
+        // `a + b = sum + overflow * p`.
+        let modulus = self.constant_biguint(&FF::order());
+        let expected = self.add_biguint(&a.value, &b.value);
+        let overflow_modulus = self.mul_biguint_by_bool(&modulus, overflow);
+        let actual = self.add_biguint(&sum.value, &overflow_modulus);
+        self.connect_biguint(&expected, &actual);
+
+        sum
+    }
+
+    pub fn sub_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let diff = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_bool_target_safe();
+        self.add_simple_generator(NonNativeSubtractionGenerator::<FF> {
+            a: a.clone(),
+            b: b.clone(),
+            diff: diff.clone(),
+            overflow,
+        });
+
+        // `a + overflow * p = b + diff`.
+        let modulus = self.constant_biguint(&FF::order());
+        let overflow_modulus = self.mul_biguint_by_bool(&modulus, overflow);
+        let lhs = self.add_biguint(&a.value, &overflow_modulus);
+        let rhs = self.add_biguint(&b.value, &diff.value);
+        self.connect_biguint(&lhs, &rhs);
+
+        diff
+    }
+
+    pub fn neg_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let zero = self.zero_nonnative();
+        self.sub_nonnative(&zero, x)
+    }
+
+    pub fn mul_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let product = self.add_virtual_nonnative_target::<FF>();
+        let quotient = self.add_virtual_biguint_target(num_nonnative_limbs::<FF>());
+        self.add_simple_generator(NonNativeMultiplicationGenerator::<FF> {
+            a: a.clone(),
+            b: b.clone(),
+            product: product.clone(),
+            quotient: quotient.clone(),
+        });
+
+        // `a * b = product + quotient * p`.
+        let modulus = self.constant_biguint(&FF::order());
+        let expected = self.mul_biguint(&a.value, &b.value);
+        let actual = self.mul_add_biguint(&quotient, &modulus, &product.value);
+        self.connect_biguint(&expected, &actual);
+
+        product
+    }
+
+    /// Computes the inverse of `x`. The circuit is unsatisfiable if `x` is zero.
+    pub fn inv_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let inv = self.add_virtual_nonnative_target::<FF>();
+        self.add_simple_generator(NonNativeInverseGenerator::<FF> {
+            x: x.clone(),
+            inv: inv.clone(),
+        });
+
+        let product = self.mul_nonnative(x, &inv);
+        let one = self.constant_nonnative(FF::ONE);
+        self.connect_nonnative(&product, &one);
+
+        inv
+    }
+
+    fn add_virtual_nonnative_target_unchecked<FF: Field>(&mut self) -> NonNativeTarget<FF> {
+        let value = self.add_virtual_biguint_target(num_nonnative_limbs::<FF>());
+        NonNativeTarget::new_unsafe(value)
+    }
+
+    fn assert_canonical_nonnative<FF: Field>(&mut self, x: &NonNativeTarget<FF>) {
+        let modulus = self.constant_biguint(&FF::order());
+        let is_canonical = self.lt_biguint(&x.value, &modulus);
+        self.assert_one(is_canonical.target);
+    }
+}
+
+fn write_nonnative<FF: Field>(dst: &mut Vec<u8>, x: &NonNativeTarget<FF>) -> IoResult<()> {
+    dst.write_target_vec(&x.value.targets())
+}
+
+fn read_nonnative<FF: Field>(src: &mut Buffer) -> IoResult<NonNativeTarget<FF>> {
+    let value = BigUintTarget::from_targets(src.read_target_vec()?);
+    Ok(NonNativeTarget::new_unsafe(value))
+}
+
+fn dependencies<FF: Field>(xs: &[&NonNativeTarget<FF>]) -> Vec<Target> {
+    xs.iter().flat_map(|x| x.value.targets()).collect()
+}
+
+/// Setters for [`NonNativeTarget`]s, for any [`WitnessWrite`].
+pub trait WitnessWriteNonNative<F: Field>: WitnessWrite<F> {
+    /// Sets the limbs of `target` to the canonical representation of `value`.
+    fn set_nonnative_target<FF: PrimeField>(
+        &mut self,
+        target: &NonNativeTarget<FF>,
+        value: FF,
+    ) -> Result<()>;
+}
+
+impl<F: Field, W: WitnessWrite<F>> WitnessWriteNonNative<F> for W {
+    fn set_nonnative_target<FF: PrimeField>(
+        &mut self,
+        target: &NonNativeTarget<FF>,
+        value: FF,
+    ) -> Result<()> {
+        self.set_biguint_target(&target.value, &value.to_canonical_biguint())
+    }
+}
+
+/// Getters for [`NonNativeTarget`]s, for any [`Witness`].
+pub trait WitnessNonNative<F: PrimeField64>: Witness<F> {
+    /// Returns the value of `target`, reduced modulo the order of `FF`.
+    fn get_nonnative_target<FF: PrimeField>(&self, target: &NonNativeTarget<FF>) -> FF;
+}
+
+impl<F: PrimeField64, W: Witness<F>> WitnessNonNative<F> for W {
+    fn get_nonnative_target<FF: PrimeField>(&self, target: &NonNativeTarget<FF>) -> FF {
+        FF::from_noncanonical_biguint(self.get_biguint_target(&target.value))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeAdditionGenerator<FF: Field> {
+    a: NonNativeTarget<FF>,
+    b: NonNativeTarget<FF>,
+    sum: NonNativeTarget<FF>,
+    overflow: BoolTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeAdditionGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeAdditionGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        dependencies(&[&self.a, &self.b])
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let a = witness.get_nonnative_target(&self.a);
+        let b = witness.get_nonnative_target(&self.b);
+        let sum = a + b;
+        let overflow = a.to_canonical_biguint() + b.to_canonical_biguint() >= FF::order();
+
+        out_buffer.set_nonnative_target(&self.sum, sum)?;
+        out_buffer.set_bool_target(self.overflow, overflow)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        write_nonnative(dst, &self.a)?;
+        write_nonnative(dst, &self.b)?;
+        write_nonnative(dst, &self.sum)?;
+        dst.write_target_bool(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = read_nonnative(src)?;
+        let b = read_nonnative(src)?;
+        let sum = read_nonnative(src)?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            sum,
+            overflow,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeSubtractionGenerator<FF: Field> {
+    a: NonNativeTarget<FF>,
+    b: NonNativeTarget<FF>,
+    diff: NonNativeTarget<FF>,
+    overflow: BoolTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeSubtractionGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeSubtractionGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        dependencies(&[&self.a, &self.b])
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let a = witness.get_nonnative_target(&self.a);
+        let b = witness.get_nonnative_target(&self.b);
+        let diff = a - b;
+        let overflow = a.to_canonical_biguint() < b.to_canonical_biguint();
+
+        out_buffer.set_nonnative_target(&self.diff, diff)?;
+        out_buffer.set_bool_target(self.overflow, overflow)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        write_nonnative(dst, &self.a)?;
+        write_nonnative(dst, &self.b)?;
+        write_nonnative(dst, &self.diff)?;
+        dst.write_target_bool(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = read_nonnative(src)?;
+        let b = read_nonnative(src)?;
+        let diff = read_nonnative(src)?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            diff,
+            overflow,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeMultiplicationGenerator<FF: Field> {
+    a: NonNativeTarget<FF>,
+    b: NonNativeTarget<FF>,
+    product: NonNativeTarget<FF>,
+    quotient: BigUintTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeMultiplicationGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeMultiplicationGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        dependencies(&[&self.a, &self.b])
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let a = witness.get_nonnative_target(&self.a);
+        let b = witness.get_nonnative_target(&self.b);
+        let (quotient, product) =
+            (a.to_canonical_biguint() * b.to_canonical_biguint()).div_rem(&FF::order());
+
+        out_buffer.set_biguint_target(&self.product.value, &product)?;
+        out_buffer.set_biguint_target(&self.quotient, &quotient)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        write_nonnative(dst, &self.a)?;
+        write_nonnative(dst, &self.b)?;
+        write_nonnative(dst, &self.product)?;
+        dst.write_target_vec(&self.quotient.targets())
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = read_nonnative(src)?;
+        let b = read_nonnative(src)?;
+        let product = read_nonnative(src)?;
+        let quotient = BigUintTarget::from_targets(src.read_target_vec()?);
+        Ok(Self {
+            a,
+            b,
+            product,
+            quotient,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeInverseGenerator<FF: Field> {
+    x: NonNativeTarget<FF>,
+    inv: NonNativeTarget<FF>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize, FF: PrimeField> SimpleGenerator<F, D>
+    for NonNativeInverseGenerator<FF>
+{
+    fn id(&self) -> String {
+        format!("NonNativeInverseGenerator<{}>", type_name::<FF>())
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        dependencies(&[&self.x])
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let x = witness.get_nonnative_target(&self.x);
+        ensure!(x.is_nonzero(), "Inverse of zero.");
+
+        out_buffer.set_nonnative_target(&self.inv, x.inverse())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        write_nonnative(dst, &self.x)?;
+        write_nonnative(dst, &self.inv)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = read_nonnative(src)?;
+        let inv = read_nonnative(src)?;
+        Ok(Self { x, inv })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::WitnessWriteNonNative;
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, PrimeField, Sample};
+    use crate::gadgets::biguint::WitnessWriteBigUint;
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+    use crate::util::serialization::{DefaultGateSerializer, EcdsaGeneratorSerializer};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn test_nonnative_arithmetic<FF: PrimeField>() -> Result<()> {
+        let x = FF::rand();
+        let y = FF::rand();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_nonnative_target::<FF>();
+        let yt = builder.add_virtual_nonnative_target::<FF>();
+
+        let sum = builder.add_nonnative(&xt, &yt);
+        let expected_sum = builder.constant_nonnative(x + y);
+        builder.connect_nonnative(&sum, &expected_sum);
+
+        let diff = builder.sub_nonnative(&xt, &yt);
+        let expected_diff = builder.constant_nonnative(x - y);
+        builder.connect_nonnative(&diff, &expected_diff);
+
+        let neg = builder.neg_nonnative(&xt);
+        let expected_neg = builder.constant_nonnative(-x);
+        builder.connect_nonnative(&neg, &expected_neg);
+
+        let product = builder.mul_nonnative(&xt, &yt);
+        let expected_product = builder.constant_nonnative(x * y);
+        builder.connect_nonnative(&product, &expected_product);
+
+        let inv = builder.inv_nonnative(&xt);
+        let expected_inv = builder.constant_nonnative(x.inverse());
+        builder.connect_nonnative(&inv, &expected_inv);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&xt, x)?;
+        pw.set_nonnative_target(&yt, y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_secp256k1_base_arithmetic() -> Result<()> {
+        test_nonnative_arithmetic::<Secp256K1Base>()
+    }
+
+    #[test]
+    fn test_secp256k1_scalar_arithmetic() -> Result<()> {
+        test_nonnative_arithmetic::<Secp256K1Scalar>()
+    }
+
+    #[test]
+    fn test_nonnative_biguint_reduction() -> Result<()> {
+        type FF = Secp256K1Base;
+        let x = FF::rand().to_canonical_biguint() * FF::rand().to_canonical_biguint();
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_biguint_target(16);
+        let reduced = builder.biguint_to_nonnative::<FF>(&xt);
+        let expected = builder.constant_nonnative(FF::from_noncanonical_biguint(&x % FF::order()));
+        builder.connect_nonnative(&reduced, &expected);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_biguint_target(&xt, &x)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_nonnative_serialization() -> Result<()> {
+        fn add_arithmetic<FF: PrimeField>(builder: &mut CircuitBuilder<F, D>) {
+            let x = builder.add_virtual_nonnative_target::<FF>();
+            let y = builder.add_virtual_nonnative_target::<FF>();
+            let sum = builder.add_nonnative(&x, &y);
+            let diff = builder.sub_nonnative(&sum, &y);
+            let product = builder.mul_nonnative(&diff, &y);
+            builder.inv_nonnative(&product);
+        }
+
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        add_arithmetic::<Secp256K1Base>(&mut builder);
+        add_arithmetic::<Secp256K1Scalar>(&mut builder);
+        let data = builder.build::<C>();
+
+        let gate_serializer = DefaultGateSerializer;
+        let generator_serializer = EcdsaGeneratorSerializer::<C, D>::default();
+        let bytes = data
+            .to_bytes(&gate_serializer, &generator_serializer)
+            .map_err(|_| anyhow::Error::msg("CircuitData serialization failed."))?;
+        let data_from_bytes =
+            CircuitData::<F, C, D>::from_bytes(&bytes, &gate_serializer, &generator_serializer)
+                .map_err(|_| anyhow::Error::msg("CircuitData deserialization failed."))?;
+        assert_eq!(data, data_from_bytes);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_inv_nonnative_zero() {
+        type FF = Secp256K1Base;
+        let config = CircuitConfig::standard_ecc_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_nonnative_target::<FF>();
+        builder.inv_nonnative(&xt);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_nonnative_target(&xt, FF::ZERO).unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+}
@@ -57,6 +57,94 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         let zero = self.zero();
         self.connect(z, zero);
     }
+
+    /// Returns whether `a < b`. Both `a` and `b` are assumed to be range-checked for having
+    /// `num_bits` bits.
+    pub fn is_less_than(&mut self, a: Target, b: Target, num_bits: usize) -> BoolTarget {
+        Self::assert_comparison_bits(num_bits);
+
+        // `a - b + 2^num_bits` lies in `[1, 2^(num_bits + 1))`, and its top bit is set iff `a >= b`.
+        let pow2 = self.constant(F::from_canonical_u64(1 << num_bits));
+        let diff = self.sub(a, b);
+        let shifted_diff = self.add(diff, pow2);
+        let bits = self.split_le(shifted_diff, num_bits + 1);
+        self.not(bits[num_bits])
+    }
+
+    /// Checks that `a < b`. Both `a` and `b` are assumed to be range-checked for having `num_bits`
+    /// bits.
+    pub fn assert_less_than(&mut self, a: Target, b: Target, num_bits: usize) {
+        Self::assert_comparison_bits(num_bits);
+
+        // If `a >= b`, then `b - a - 1` wraps around to a value of at least `p - 2^num_bits`.
+        let one = self.one();
+        let diff = self.sub(b, a);
+        let diff_minus_one = self.sub(diff, one);
+        self.range_check(diff_minus_one, num_bits);
+    }
+
+    /// Checks that `lo <= x < hi`, for arbitrary bounds with `hi - lo <= 2^62`.
+    pub fn assert_in_range(&mut self, x: Target, lo: u64, hi: u64) {
+        assert!(lo < hi, "Empty range {lo}..{hi}");
+        assert!(hi <= F::ORDER, "Range {lo}..{hi} exceeds the field order");
+        let num_bits = (64 - (hi - lo - 1).leading_zeros()) as usize;
+        Self::assert_comparison_bits(num_bits);
+
+        let lo = self.constant(F::from_canonical_u64(lo));
+        if num_bits == 0 {
+            self.connect(x, lo);
+            return;
+        }
+
+        // Both `x - lo` and `hi - 1 - x` must fit in `num_bits` bits. Since `2^(num_bits + 1) < p`,
+        // neither of them can wrap around.
+        let hi_minus_one = self.constant(F::from_canonical_u64(hi - 1));
+        let above_lo = self.sub(x, lo);
+        let below_hi = self.sub(hi_minus_one, x);
+        self.range_check(above_lo, num_bits);
+        self.range_check(below_hi, num_bits);
+    }
+
+    /// Checks that the little-endian bits `bits`, e.g. as returned by `split_le(x, 64)`, encode an
+    /// integer less than the order of the field. This makes the decomposition unique.
+    pub fn assert_canonical_le_bits(&mut self, bits: &[BoolTarget]) {
+        assert!(bits.len() <= 64, "Too many bits");
+
+        // Compare against the order from the least significant bit up. After processing bit `i`,
+        // `lt` holds whether the low `i + 1` bits encode an integer less than those of the order.
+        let mut lt = self._false();
+        for i in 0..64 {
+            let Some(&b) = bits.get(i) else {
+                // The missing high bits are zero, so they are less than or equal to the order's.
+                if F::ORDER >> i != 0 {
+                    lt = self._true();
+                }
+                break;
+            };
+            let not_b = self.not(b);
+            lt = if (F::ORDER >> i) & 1 == 1 {
+                self.or(not_b, lt)
+            } else {
+                self.and(not_b, lt)
+            };
+        }
+        self.assert_one(lt.target);
+    }
+
+    /// Splits `x` into 64 little-endian bits, encoding its canonical value.
+    pub fn split_le_canonical(&mut self, x: Target) -> Vec<BoolTarget> {
+        let bits = self.split_le(x, 64);
+        self.assert_canonical_le_bits(&bits);
+        bits
+    }
+
+    pub(crate) fn assert_comparison_bits(num_bits: usize) {
+        assert!(
+            num_bits < F::BITS - 1,
+            "Comparisons are only supported for up to {} bits",
+            F::BITS - 2
+        );
+    }
 }
 
 #[derive(Debug, Default)]
@@ -109,3 +197,128 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Low
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::{Field, Field64, PrimeField64, Sample};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn prove_in_range(x: u64, lo: u64, hi: u64) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_target();
+        builder.assert_in_range(xt, lo, hi);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(xt, F::from_canonical_u64(x))?;
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn prove_canonical_le_bits(x: u64) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let bits = (0..64)
+            .map(|_| builder.add_virtual_bool_target_safe())
+            .collect::<Vec<_>>();
+        builder.assert_canonical_le_bits(&bits);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (i, &b) in bits.iter().enumerate() {
+            pw.set_bool_target(b, (x >> i) & 1 == 1)?;
+        }
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_is_less_than() -> Result<()> {
+        let mut rng = OsRng;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let mut pairs = (0..10)
+            .map(|_| (rng.gen::<u32>() as u64, rng.gen::<u32>() as u64))
+            .collect::<Vec<_>>();
+        pairs.extend([(5, 5), (0, u32::MAX as u64), (u32::MAX as u64, 0)]);
+        for (a, b) in pairs {
+            let at = builder.add_virtual_target();
+            let bt = builder.add_virtual_target();
+            pw.set_target(at, F::from_canonical_u64(a))?;
+            pw.set_target(bt, F::from_canonical_u64(b))?;
+
+            let lt = builder.is_less_than(at, bt, 32);
+            let expected = builder.constant_bool(a < b);
+            builder.connect(lt.target, expected.target);
+            if a < b {
+                builder.assert_less_than(at, bt, 32);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_assert_less_than_equal_inputs() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let at = builder.add_virtual_target();
+        let bt = builder.add_virtual_target();
+        builder.assert_less_than(at, bt, 32);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(at, F::from_canonical_u64(7)).unwrap();
+        pw.set_target(bt, F::from_canonical_u64(7)).unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+
+    #[test]
+    fn test_assert_in_range() -> Result<()> {
+        prove_in_range(10, 10, 1000)?;
+        prove_in_range(999, 10, 1000)?;
+        prove_in_range(42, 42, 43)?;
+        assert!(prove_in_range(9, 10, 1000).is_err());
+        assert!(prove_in_range(1000, 10, 1000).is_err());
+        assert!(prove_in_range(0, 1, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_le_bits() -> Result<()> {
+        prove_canonical_le_bits(F::ORDER - 1)?;
+        prove_canonical_le_bits(0)?;
+        assert!(prove_canonical_le_bits(F::ORDER).is_err());
+        assert!(prove_canonical_le_bits(u64::MAX).is_err());
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = F::rand();
+        let xt = builder.constant(x);
+        let bits = builder.split_le_canonical(xt);
+        for (i, b) in bits.into_iter().enumerate() {
+            let expected = builder.constant_bool((x.to_canonical_u64() >> i) & 1 == 1);
+            builder.connect(b.target, expected.target);
+        }
+        let data = builder.build::<C>();
+        let proof = data.prove(PartialWitness::new())?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -0,0 +1,269 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::hash::sha256::{
+    sha256_padding, SHA256_BLOCK_BYTES, SHA256_INITIAL_STATE, SHA256_ROUND_CONSTANTS,
+};
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A 32-bit word, given by its value along with its bits in little-endian order.
+#[derive(Copy, Clone, Debug)]
+struct WordTarget {
+    value: Target,
+    bits: [BoolTarget; 32],
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the SHA-256 digest of the given bytes. The inputs are range-checked to be bytes.
+    pub fn sha256(&mut self, bytes: &[Target]) -> [Target; 32] {
+        let mut bits = Vec::with_capacity(8 * (bytes.len() + SHA256_BLOCK_BYTES));
+        for &byte in bytes {
+            bits.extend(self.split_le(byte, 8));
+        }
+        for byte in sha256_padding(bytes.len()) {
+            bits.extend((0..8).map(|i| self.constant_bool(byte >> i & 1 == 1)));
+        }
+
+        let mut state = SHA256_INITIAL_STATE.map(|w| self.constant_word(w));
+        for block in bits.chunks_exact(8 * SHA256_BLOCK_BYTES) {
+            // Words are big-endian, so the first byte holds their most significant bits.
+            let block = core::array::from_fn(|i| {
+                let bits = core::array::from_fn(|j| block[32 * i + 8 * (3 - j / 8) + j % 8]);
+                self.word_from_bits(bits)
+            });
+            state = self.sha256_compress_words(state, block);
+        }
+
+        core::array::from_fn(|i| {
+            let word = state[i / 4].bits;
+            let byte = 3 - i % 4;
+            self.le_sum(word[8 * byte..8 * byte + 8].iter())
+        })
+    }
+
+    /// Applies the SHA-256 compression function to the given state and block of 16 words. The
+    /// inputs are range-checked to be 32-bit words.
+    pub fn sha256_compress(&mut self, state: [Target; 8], block: [Target; 16]) -> [Target; 8] {
+        let state = state.map(|w| self.word_from_value(w));
+        let block = block.map(|w| self.word_from_value(w));
+        self.sha256_compress_words(state, block)
+            .map(|word| word.value)
+    }
+
+    fn sha256_compress_words(
+        &mut self,
+        state: [WordTarget; 8],
+        block: [WordTarget; 16],
+    ) -> [WordTarget; 8] {
+        // Message schedule.
+        let mut w = block.to_vec();
+        for i in 16..64 {
+            let s0 = self.sigma_word(&w[i - 15].bits, [7, 18], 3);
+            let s1 = self.sigma_word(&w[i - 2].bits, [17, 19], 10);
+            w.push(self.add_words(&[w[i - 16].value, s0, w[i - 7].value, s1], 0));
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = self.big_sigma_word(&e.bits, [6, 11, 25]);
+            let ch = self.ch_word(&e.bits, &f.bits, &g.bits);
+            let t1 = self.add_many([h.value, s1, ch, w[i].value]);
+            let s0 = self.big_sigma_word(&a.bits, [2, 13, 22]);
+            let maj = self.maj_word(&a.bits, &b.bits, &c.bits);
+
+            h = g;
+            g = f;
+            f = e;
+            e = self.add_words(&[d.value, t1], SHA256_ROUND_CONSTANTS[i]);
+            d = c;
+            c = b;
+            b = a;
+            a = self.add_words(&[t1, s0, maj], SHA256_ROUND_CONSTANTS[i]);
+        }
+
+        let working = [a, b, c, d, e, f, g, h];
+        core::array::from_fn(|i| self.add_words(&[state[i].value, working[i].value], 0))
+    }
+
+    fn constant_word(&mut self, word: u32) -> WordTarget {
+        WordTarget {
+            value: self.constant(F::from_canonical_u32(word)),
+            bits: core::array::from_fn(|i| self.constant_bool(word >> i & 1 == 1)),
+        }
+    }
+
+    fn word_from_bits(&mut self, bits: [BoolTarget; 32]) -> WordTarget {
+        WordTarget {
+            value: self.le_sum(bits.iter()),
+            bits,
+        }
+    }
+
+    fn word_from_value(&mut self, value: Target) -> WordTarget {
+        let bits = self.split_le(value, 32);
+        WordTarget {
+            value,
+            bits: bits.try_into().unwrap(),
+        }
+    }
+
+    /// Adds the given values along with a constant, modulo `2^32`. The sum must be less than
+    /// `2^35`, which holds for the sums of at most 7 words computed here.
+    fn add_words(&mut self, values: &[Target], constant: u32) -> WordTarget {
+        let constant = self.constant(F::from_canonical_u32(constant));
+        let sum = self.add_many(values.iter().copied().chain([constant]));
+        let bits = self.split_le(sum, 35);
+        let carry = self.le_sum(bits[32..].iter());
+        let value = self.mul_const_add(-F::from_canonical_u64(1 << 32), carry, sum);
+        WordTarget {
+            value,
+            bits: bits[..32].try_into().unwrap(),
+        }
+    }
+
+    /// Computes the value of `ROTR^r0(x) ^ ROTR^r1(x) ^ SHR^s(x)`.
+    fn sigma_word(&mut self, x: &[BoolTarget; 32], [r0, r1]: [usize; 2], s: usize) -> Target {
+        let bits = (0..32)
+            .map(|i| {
+                let b = self.xor(x[(i + r0) % 32], x[(i + r1) % 32]);
+                if i + s < 32 {
+                    self.xor(b, x[i + s])
+                } else {
+                    b
+                }
+            })
+            .collect::<Vec<_>>();
+        self.le_sum(bits.into_iter())
+    }
+
+    /// Computes the value of `ROTR^r0(x) ^ ROTR^r1(x) ^ ROTR^r2(x)`.
+    fn big_sigma_word(&mut self, x: &[BoolTarget; 32], [r0, r1, r2]: [usize; 3]) -> Target {
+        let bits = (0..32)
+            .map(|i| {
+                let b = self.xor(x[(i + r0) % 32], x[(i + r1) % 32]);
+                self.xor(b, x[(i + r2) % 32])
+            })
+            .collect::<Vec<_>>();
+        self.le_sum(bits.into_iter())
+    }
+
+    /// Computes the value of `(x & y) ^ (!x & z)`, i.e. `z + x * (y - z)` bitwise.
+    fn ch_word(
+        &mut self,
+        x: &[BoolTarget; 32],
+        y: &[BoolTarget; 32],
+        z: &[BoolTarget; 32],
+    ) -> Target {
+        let bits = (0..32)
+            .map(|i| {
+                let diff = self.sub(y[i].target, z[i].target);
+                BoolTarget::new_unsafe(self.mul_add(x[i].target, diff, z[i].target))
+            })
+            .collect::<Vec<_>>();
+        self.le_sum(bits.into_iter())
+    }
+
+    /// Computes the value of `(x & y) ^ (x & z) ^ (y & z)`, i.e. `x * y + z * (x ^ y)` bitwise.
+    fn maj_word(
+        &mut self,
+        x: &[BoolTarget; 32],
+        y: &[BoolTarget; 32],
+        z: &[BoolTarget; 32],
+    ) -> Target {
+        let bits = (0..32)
+            .map(|i| {
+                let x_and_y = self.mul(x[i].target, y[i].target);
+                let x_xor_y = self.xor(x[i], y[i]);
+                BoolTarget::new_unsafe(self.mul_add(z[i].target, x_xor_y.target, x_and_y))
+            })
+            .collect::<Vec<_>>();
+        self.le_sum(bits.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::types::Field;
+    use crate::hash::sha256::{sha256, sha256_compress};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn test_sha256(input: &[u8]) -> Result<()> {
+        let expected = sha256(input);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let bytes = builder.add_virtual_targets(input.len());
+        let digest = builder.sha256(&bytes);
+        for (&d, e) in digest.iter().zip(expected) {
+            let e = builder.constant(F::from_canonical_u8(e));
+            builder.connect(d, e);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&b, &x) in bytes.iter().zip(input) {
+            pw.set_target(b, F::from_canonical_u8(x))?;
+        }
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_sha256_abc() -> Result<()> {
+        test_sha256(b"abc")
+    }
+
+    #[test]
+    fn test_sha256_two_blocks() -> Result<()> {
+        let mut rng = OsRng;
+        let input = (0..100).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+        test_sha256(&input)
+    }
+
+    #[test]
+    fn test_sha256_compress() -> Result<()> {
+        let mut rng = OsRng;
+        let state: [u32; 8] = rng.gen();
+        let block: [u32; 16] = rng.gen();
+        let mut expected = state;
+        sha256_compress(&mut expected, &block);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let state_targets = builder.add_virtual_target_arr::<8>();
+        let block_targets = builder.add_virtual_target_arr::<16>();
+        let output = builder.sha256_compress(state_targets, block_targets);
+        for (&o, e) in output.iter().zip(expected) {
+            let e = builder.constant(F::from_canonical_u32(e));
+            builder.connect(o, e);
+        }
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&t, x) in state_targets.iter().zip(state) {
+            pw.set_target(t, F::from_canonical_u32(x))?;
+        }
+        for (&t, x) in block_targets.iter().zip(block) {
+            pw.set_target(t, F::from_canonical_u32(x))?;
+        }
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
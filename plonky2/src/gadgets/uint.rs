@@ -0,0 +1,598 @@
+//! Gadgets for 32-bit and 64-bit unsigned integer arithmetic.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField64};
+use crate::gates::u32_arithmetic::U32ArithmeticGate;
+use crate::gates::u32_subtraction::U32SubtractionGate;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A `Target` which has already been constrained such that it can only be a 32-bit unsigned
+/// integer.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[allow(clippy::manual_non_exhaustive)]
+pub struct U32Target {
+    pub target: Target,
+    /// This private field is here to force all instantiations to go through `new_unsafe`.
+    _private: (),
+}
+
+impl U32Target {
+    pub const fn new_unsafe(target: Target) -> U32Target {
+        U32Target {
+            target,
+            _private: (),
+        }
+    }
+}
+
+/// A 64-bit unsigned integer, given by its 32-bit limbs in little-endian order.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct U64Target {
+    pub limbs: [U32Target; 2],
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a new virtual target, range-checked to be a 32-bit unsigned integer.
+    pub fn add_virtual_u32_target(&mut self) -> U32Target {
+        let target = self.add_virtual_target();
+        self.range_check_u32(target)
+    }
+
+    pub fn add_virtual_u32_targets(&mut self, n: usize) -> Vec<U32Target> {
+        (0..n).map(|_| self.add_virtual_u32_target()).collect()
+    }
+
+    /// Range-checks `x` to be a 32-bit unsigned integer.
+    pub fn range_check_u32(&mut self, x: Target) -> U32Target {
+        self.range_check(x, 32);
+        U32Target::new_unsafe(x)
+    }
+
+    pub fn constant_u32(&mut self, c: u32) -> U32Target {
+        U32Target::new_unsafe(self.constant(F::from_canonical_u32(c)))
+    }
+
+    pub fn connect_u32(&mut self, x: U32Target, y: U32Target) {
+        self.connect(x.target, y.target);
+    }
+
+    /// Computes `x * y + z`, returned as its low and high 32-bit halves.
+    pub fn mul_add_u32(
+        &mut self,
+        x: U32Target,
+        y: U32Target,
+        z: U32Target,
+    ) -> (U32Target, U32Target) {
+        self.split_u64_mul_add(x.target, y.target, z.target)
+    }
+
+    /// Computes `x * y`, returned as its low and high 32-bit halves.
+    pub fn mul_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target) {
+        let zero = self.constant_u32(0);
+        self.mul_add_u32(x, y, zero)
+    }
+
+    /// Computes `x + y`, returned as its low 32 bits along with the carry.
+    pub fn add_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, BoolTarget) {
+        let one = self.constant_u32(1);
+        let (sum, carry) = self.mul_add_u32(x, one, y);
+        // The sum is less than `2^33`, so the carry is a bit.
+        (sum, BoolTarget::new_unsafe(carry.target))
+    }
+
+    /// Computes `x + y + carry`, returned as its low 32 bits along with the output carry.
+    pub fn add_u32_with_carry(
+        &mut self,
+        x: U32Target,
+        y: U32Target,
+        carry: BoolTarget,
+    ) -> (U32Target, BoolTarget) {
+        let one = self.one();
+        let y_plus_carry = self.add(y.target, carry.target);
+        let (sum, carry) = self.split_u64_mul_add(x.target, one, y_plus_carry);
+        // The sum is less than `2^33`, so the carry is a bit.
+        (sum, BoolTarget::new_unsafe(carry.target))
+    }
+
+    /// Computes the sum of the given values, returned as its low 32 bits along with the carry. At
+    /// most `2^32` values can be added.
+    pub fn add_many_u32(&mut self, values: &[U32Target]) -> (U32Target, U32Target) {
+        assert!(values.len() as u64 <= 1 << 32, "Too many values to add.");
+        let sum = self.add_many(values.iter().map(|x| x.target));
+        let one = self.one();
+        let zero = self.zero();
+        self.split_u64_mul_add(sum, one, zero)
+    }
+
+    /// Computes `x - y - borrow`, returned as its value modulo `2^32` along with the output
+    /// borrow, which is set whenever the subtraction underflows.
+    pub fn sub_u32(
+        &mut self,
+        x: U32Target,
+        y: U32Target,
+        borrow: BoolTarget,
+    ) -> (U32Target, BoolTarget) {
+        let gate = U32SubtractionGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+
+        self.connect(
+            x.target,
+            Target::wire(row, U32SubtractionGate::wire_ith_input_x(i)),
+        );
+        self.connect(
+            y.target,
+            Target::wire(row, U32SubtractionGate::wire_ith_input_y(i)),
+        );
+        self.connect(
+            borrow.target,
+            Target::wire(row, U32SubtractionGate::wire_ith_input_borrow(i)),
+        );
+
+        let result = Target::wire(row, U32SubtractionGate::wire_ith_output_result(i));
+        let output_borrow = Target::wire(row, U32SubtractionGate::wire_ith_output_borrow(i));
+        (
+            U32Target::new_unsafe(result),
+            BoolTarget::new_unsafe(output_borrow),
+        )
+    }
+
+    /// Returns whether `x < y`.
+    pub fn lt_u32(&mut self, x: U32Target, y: U32Target) -> BoolTarget {
+        let _false = self._false();
+        let (_, borrow) = self.sub_u32(x, y, _false);
+        borrow
+    }
+
+    /// Returns whether `x <= y`.
+    pub fn le_u32(&mut self, x: U32Target, y: U32Target) -> BoolTarget {
+        let gt = self.lt_u32(y, x);
+        self.not(gt)
+    }
+
+    /// Computes the quotient and remainder of the division of `x` by `y`. The circuit is
+    /// unsatisfiable if `y` is zero.
+    pub fn div_rem_u32(&mut self, x: U32Target, y: U32Target) -> (U32Target, U32Target) {
+        let quotient = self.add_virtual_u32_target();
+        let remainder = self.add_virtual_u32_target();
+        self.add_simple_generator(UintDivRemGenerator {
+            dividend: vec![x.target],
+            divisor: vec![y.target],
+            quotient: vec![quotient.target],
+            remainder: vec![remainder.target],
+        });
+
+        let (low, high) = self.mul_add_u32(quotient, y, remainder);
+        self.connect_u32(low, x);
+        let zero = self.zero();
+        self.connect(high.target, zero);
+
+        let remainder_lt_divisor = self.lt_u32(remainder, y);
+        self.assert_one(remainder_lt_divisor.target);
+
+        (quotient, remainder)
+    }
+
+    /// Adds a new virtual 64-bit unsigned integer, whose limbs are range-checked.
+    pub fn add_virtual_u64_target(&mut self) -> U64Target {
+        U64Target {
+            limbs: [(); 2].map(|_| self.add_virtual_u32_target()),
+        }
+    }
+
+    pub fn constant_u64(&mut self, c: u64) -> U64Target {
+        U64Target {
+            limbs: [c as u32, (c >> 32) as u32].map(|limb| self.constant_u32(limb)),
+        }
+    }
+
+    pub fn connect_u64(&mut self, x: U64Target, y: U64Target) {
+        for (x_limb, y_limb) in x.limbs.into_iter().zip(y.limbs) {
+            self.connect_u32(x_limb, y_limb);
+        }
+    }
+
+    /// Computes `x + y`, returned as its low 64 bits along with the carry.
+    pub fn add_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, BoolTarget) {
+        let (low, carry) = self.add_u32(x.limbs[0], y.limbs[0]);
+        let (high, carry) = self.add_u32_with_carry(x.limbs[1], y.limbs[1], carry);
+        (U64Target { limbs: [low, high] }, carry)
+    }
+
+    /// Computes `x - y`, returned as its value modulo `2^64` along with the borrow, which is set
+    /// whenever the subtraction underflows.
+    pub fn sub_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, BoolTarget) {
+        let _false = self._false();
+        let (low, borrow) = self.sub_u32(x.limbs[0], y.limbs[0], _false);
+        let (high, borrow) = self.sub_u32(x.limbs[1], y.limbs[1], borrow);
+        (U64Target { limbs: [low, high] }, borrow)
+    }
+
+    /// Computes `x * y`, returned as its low and high 64-bit halves.
+    pub fn mul_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, U64Target) {
+        let [x0, x1] = x.limbs;
+        let [y0, y1] = y.limbs;
+        let (p00_low, p00_high) = self.mul_u32(x0, y0);
+        let (p01_low, p01_high) = self.mul_u32(x0, y1);
+        let (p10_low, p10_high) = self.mul_u32(x1, y0);
+        let (p11_low, p11_high) = self.mul_u32(x1, y1);
+
+        let (limb1, carry1) = self.add_many_u32(&[p00_high, p01_low, p10_low]);
+        let (limb2, carry2) = self.add_many_u32(&[carry1, p01_high, p10_high, p11_low]);
+        // The product is less than `2^128`, so this last column has no carry.
+        let (limb3, _) = self.add_many_u32(&[carry2, p11_high]);
+
+        (
+            U64Target {
+                limbs: [p00_low, limb1],
+            },
+            U64Target {
+                limbs: [limb2, limb3],
+            },
+        )
+    }
+
+    /// Returns whether `x < y`.
+    pub fn lt_u64(&mut self, x: U64Target, y: U64Target) -> BoolTarget {
+        let (_, borrow) = self.sub_u64(x, y);
+        borrow
+    }
+
+    /// Returns whether `x <= y`.
+    pub fn le_u64(&mut self, x: U64Target, y: U64Target) -> BoolTarget {
+        let gt = self.lt_u64(y, x);
+        self.not(gt)
+    }
+
+    /// Computes the quotient and remainder of the division of `x` by `y`. The circuit is
+    /// unsatisfiable if `y` is zero.
+    pub fn div_rem_u64(&mut self, x: U64Target, y: U64Target) -> (U64Target, U64Target) {
+        let quotient = self.add_virtual_u64_target();
+        let remainder = self.add_virtual_u64_target();
+        self.add_simple_generator(UintDivRemGenerator {
+            dividend: x.limbs.iter().map(|l| l.target).collect(),
+            divisor: y.limbs.iter().map(|l| l.target).collect(),
+            quotient: quotient.limbs.iter().map(|l| l.target).collect(),
+            remainder: remainder.limbs.iter().map(|l| l.target).collect(),
+        });
+
+        let zero = self.zero();
+        let (product, product_high) = self.mul_u64(quotient, y);
+        for limb in product_high.limbs {
+            self.connect(limb.target, zero);
+        }
+        let (sum, carry) = self.add_u64(product, remainder);
+        self.connect_u64(sum, x);
+        self.connect(carry.target, zero);
+
+        let remainder_lt_divisor = self.lt_u64(remainder, y);
+        self.assert_one(remainder_lt_divisor.target);
+
+        (quotient, remainder)
+    }
+
+    /// Computes `x * y + z` with a `U32ArithmeticGate`, returned as its low and high 32-bit
+    /// halves. The inputs need not be 32-bit, as long as `x * y + z` is less than the field order.
+    fn split_u64_mul_add(&mut self, x: Target, y: Target, z: Target) -> (U32Target, U32Target) {
+        let gate = U32ArithmeticGate::new_from_config(&self.config);
+        let (row, i) = self.find_slot(gate, &[], &[]);
+
+        self.connect(
+            x,
+            Target::wire(row, U32ArithmeticGate::wire_ith_multiplicand_0(i)),
+        );
+        self.connect(
+            y,
+            Target::wire(row, U32ArithmeticGate::wire_ith_multiplicand_1(i)),
+        );
+        self.connect(z, Target::wire(row, U32ArithmeticGate::wire_ith_addend(i)));
+
+        let low = Target::wire(row, U32ArithmeticGate::wire_ith_output_low_half(i));
+        let high = Target::wire(row, U32ArithmeticGate::wire_ith_output_high_half(i));
+        (U32Target::new_unsafe(low), U32Target::new_unsafe(high))
+    }
+}
+
+/// Generates the quotient and remainder of the division of two unsigned integers of at most 64
+/// bits, given by their 32-bit limbs in little-endian order.
+/// Setters for [`U32Target`]s and [`U64Target`]s, for any [`WitnessWrite`].
+pub trait WitnessWriteU32<F: Field>: WitnessWrite<F> {
+    /// Sets `target` to `value`.
+    fn set_u32_target(&mut self, target: U32Target, value: u32) -> Result<()>;
+
+    /// Sets the two limbs of `target` to the low and high halves of `value`.
+    fn set_u64_target(&mut self, target: U64Target, value: u64) -> Result<()>;
+}
+
+impl<F: Field, W: WitnessWrite<F>> WitnessWriteU32<F> for W {
+    fn set_u32_target(&mut self, target: U32Target, value: u32) -> Result<()> {
+        self.set_target(target.target, F::from_canonical_u32(value))
+    }
+
+    fn set_u64_target(&mut self, target: U64Target, value: u64) -> Result<()> {
+        self.set_u32_target(target.limbs[0], value as u32)?;
+        self.set_u32_target(target.limbs[1], (value >> 32) as u32)
+    }
+}
+
+/// Getters for [`U32Target`]s and [`U64Target`]s, for any [`Witness`].
+pub trait WitnessU32<F: PrimeField64>: Witness<F> {
+    /// Returns the value of `target`, panicking if it is not a `u32`.
+    fn get_u32_target(&self, target: U32Target) -> u32;
+
+    /// Returns the value of `target`, recombined from its two limbs.
+    fn get_u64_target(&self, target: U64Target) -> u64;
+}
+
+impl<F: PrimeField64, W: Witness<F>> WitnessU32<F> for W {
+    fn get_u32_target(&self, target: U32Target) -> u32 {
+        let value = self.get_target(target.target).to_canonical_u64();
+        u32::try_from(value).expect("not a u32")
+    }
+
+    fn get_u64_target(&self, target: U64Target) -> u64 {
+        let low = self.get_u32_target(target.limbs[0]) as u64;
+        let high = self.get_u32_target(target.limbs[1]) as u64;
+        low | (high << 32)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UintDivRemGenerator {
+    dividend: Vec<Target>,
+    divisor: Vec<Target>,
+    quotient: Vec<Target>,
+    remainder: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for UintDivRemGenerator {
+    fn id(&self) -> String {
+        "UintDivRemGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.dividend.iter().chain(&self.divisor).copied().collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let get_value = |limbs: &[Target]| {
+            limbs.iter().rev().fold(0u64, |acc, &limb| {
+                (acc << 32) | witness.get_target(limb).to_canonical_u64()
+            })
+        };
+        let dividend = get_value(&self.dividend);
+        let divisor = get_value(&self.divisor);
+        ensure!(divisor != 0, "Division by zero.");
+
+        let set_value = |out_buffer: &mut GeneratedValues<F>, limbs: &[Target], value: u64| {
+            for (i, &limb) in limbs.iter().enumerate() {
+                out_buffer.set_target(limb, F::from_canonical_u32((value >> (32 * i)) as u32))?;
+            }
+            Ok::<_, anyhow::Error>(())
+        };
+        set_value(out_buffer, &self.quotient, dividend / divisor)?;
+        set_value(out_buffer, &self.remainder, dividend % divisor)
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.dividend)?;
+        dst.write_target_vec(&self.divisor)?;
+        dst.write_target_vec(&self.quotient)?;
+        dst.write_target_vec(&self.remainder)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let dividend = src.read_target_vec()?;
+        let divisor = src.read_target_vec()?;
+        let quotient = src.read_target_vec()?;
+        let remainder = src.read_target_vec()?;
+        Ok(Self {
+            dividend,
+            divisor,
+            quotient,
+            remainder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::WitnessWriteU32;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_u32_arithmetic() -> Result<()> {
+        let mut rng = OsRng;
+        let (x, y, z) = (rng.gen::<u32>(), rng.gen::<u32>(), rng.gen::<u32>());
+        let borrow = rng.gen::<bool>();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let [xt, yt, zt] = [(); 3].map(|_| builder.add_virtual_u32_target());
+        let borrow_t = builder.add_virtual_bool_target_safe();
+
+        let product = x as u64 * y as u64 + z as u64;
+        let (low, high) = builder.mul_add_u32(xt, yt, zt);
+        let expected_low = builder.constant_u32(product as u32);
+        let expected_high = builder.constant_u32((product >> 32) as u32);
+        builder.connect_u32(low, expected_low);
+        builder.connect_u32(high, expected_high);
+
+        let (sum, carry) = builder.add_u32(xt, yt);
+        let (expected_sum, expected_carry) = x.overflowing_add(y);
+        let expected_sum = builder.constant_u32(expected_sum);
+        let expected_carry = builder.constant_bool(expected_carry);
+        builder.connect_u32(sum, expected_sum);
+        builder.connect(carry.target, expected_carry.target);
+
+        let (sum, carry) = builder.add_many_u32(&[xt, yt, zt]);
+        let expected = x as u64 + y as u64 + z as u64;
+        let expected_sum = builder.constant_u32(expected as u32);
+        let expected_carry = builder.constant_u32((expected >> 32) as u32);
+        builder.connect_u32(sum, expected_sum);
+        builder.connect_u32(carry, expected_carry);
+
+        let (diff, borrow_out) = builder.sub_u32(xt, yt, borrow_t);
+        let (expected_diff, b0) = x.overflowing_sub(y);
+        let (expected_diff, b1) = expected_diff.overflowing_sub(borrow as u32);
+        let expected_diff = builder.constant_u32(expected_diff);
+        let expected_borrow = builder.constant_bool(b0 || b1);
+        builder.connect_u32(diff, expected_diff);
+        builder.connect(borrow_out.target, expected_borrow.target);
+
+        let lt = builder.lt_u32(xt, yt);
+        let expected_lt = builder.constant_bool(x < y);
+        builder.connect(lt.target, expected_lt.target);
+        let le = builder.le_u32(xt, xt);
+        builder.assert_one(le.target);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_u32_target(xt, x)?;
+        pw.set_u32_target(yt, y)?;
+        pw.set_u32_target(zt, z)?;
+        pw.set_bool_target(borrow_t, borrow)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_div_rem_u32() -> Result<()> {
+        let mut rng = OsRng;
+        let x = rng.gen::<u32>();
+        let y = rng.gen::<u16>() as u32 + 1;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_u32_target();
+        let yt = builder.add_virtual_u32_target();
+        let (q, r) = builder.div_rem_u32(xt, yt);
+        let expected_q = builder.constant_u32(x / y);
+        let expected_r = builder.constant_u32(x % y);
+        builder.connect_u32(q, expected_q);
+        builder.connect_u32(r, expected_r);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_u32_target(xt, x)?;
+        pw.set_u32_target(yt, y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_div_rem_u32_by_zero() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_u32_target();
+        let yt = builder.add_virtual_u32_target();
+        builder.div_rem_u32(xt, yt);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_u32_target(xt, 1).unwrap();
+        pw.set_u32_target(yt, 0).unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+
+    #[test]
+    fn test_u64_arithmetic() -> Result<()> {
+        let mut rng = OsRng;
+        let (x, y) = (rng.gen::<u64>(), rng.gen::<u64>());
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_u64_target();
+        let yt = builder.add_virtual_u64_target();
+
+        let (sum, carry) = builder.add_u64(xt, yt);
+        let (expected_sum, expected_carry) = x.overflowing_add(y);
+        let expected_sum = builder.constant_u64(expected_sum);
+        let expected_carry = builder.constant_bool(expected_carry);
+        builder.connect_u64(sum, expected_sum);
+        builder.connect(carry.target, expected_carry.target);
+
+        let (diff, borrow) = builder.sub_u64(xt, yt);
+        let (expected_diff, expected_borrow) = x.overflowing_sub(y);
+        let expected_diff = builder.constant_u64(expected_diff);
+        let expected_borrow = builder.constant_bool(expected_borrow);
+        builder.connect_u64(diff, expected_diff);
+        builder.connect(borrow.target, expected_borrow.target);
+
+        let (low, high) = builder.mul_u64(xt, yt);
+        let product = x as u128 * y as u128;
+        let expected_low = builder.constant_u64(product as u64);
+        let expected_high = builder.constant_u64((product >> 64) as u64);
+        builder.connect_u64(low, expected_low);
+        builder.connect_u64(high, expected_high);
+
+        let le = builder.le_u64(xt, yt);
+        let expected_le = builder.constant_bool(x <= y);
+        builder.connect(le.target, expected_le.target);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_u64_target(xt, x)?;
+        pw.set_u64_target(yt, y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_div_rem_u64() -> Result<()> {
+        let mut rng = OsRng;
+        let x = rng.gen::<u64>();
+        let y = (rng.gen::<u64>() >> 20).max(1);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let xt = builder.add_virtual_u64_target();
+        let yt = builder.add_virtual_u64_target();
+        let (q, r) = builder.div_rem_u64(xt, yt);
+        let expected_q = builder.constant_u64(x / y);
+        let expected_r = builder.constant_u64(x % y);
+        builder.connect_u64(q, expected_q);
+        builder.connect_u64(r, expected_r);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_u64_target(xt, x)?;
+        pw.set_u64_target(yt, y)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
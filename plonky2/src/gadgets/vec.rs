@@ -0,0 +1,441 @@
+//! Gadgets for vectors of targets: variable-length arrays, dynamic slicing and concatenation,
+//! permutation checks and sorted-order checks.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::challenger::RecursiveChallenger;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::WitnessWrite;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::util::log2_ceil;
+
+/// An array whose length is only known at proving time, up to a maximum length fixed when
+/// building the circuit. The entries at positions `len..max_len()` are always zero.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VariableLengthArray {
+    pub len: Target,
+    pub data: Vec<Target>,
+}
+
+impl VariableLengthArray {
+    pub fn max_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a new virtual array of length at most `max_len`. Its length is range-checked, and its
+    /// entries past the length are checked to be zero.
+    pub fn add_virtual_variable_length_array(&mut self, max_len: usize) -> VariableLengthArray {
+        let arr = VariableLengthArray {
+            len: self.add_virtual_target(),
+            data: self.add_virtual_targets(max_len),
+        };
+
+        let mask = self.variable_length_mask(arr.len, max_len);
+        for (&x, &active) in arr.data.iter().zip(&mask) {
+            let inactive = self.not(active);
+            let padding = self.mul(inactive.target, x);
+            self.assert_zero(padding);
+        }
+
+        arr
+    }
+
+    pub fn constant_variable_length_array(
+        &mut self,
+        values: &[F],
+        max_len: usize,
+    ) -> VariableLengthArray {
+        assert!(values.len() <= max_len, "Too many values");
+        let mut data = values.iter().map(|&x| self.constant(x)).collect::<Vec<_>>();
+        data.resize(max_len, self.zero());
+
+        VariableLengthArray {
+            len: self.constant(F::from_canonical_usize(values.len())),
+            data,
+        }
+    }
+
+    /// Returns `arr[index]`, checking that `index < arr.len`.
+    pub fn random_access_array(&mut self, arr: &VariableLengthArray, index: Target) -> Target {
+        let num_bits = log2_ceil(arr.max_len() + 1);
+        self.assert_less_than(index, arr.len, num_bits);
+        // `random_access` range-checks `index` through the bits of the gate, but there is no gate
+        // for a single element.
+        if arr.max_len() == 1 {
+            self.assert_zero(index);
+        }
+        self.random_access_any_length(index, arr.data.clone())
+    }
+
+    /// Returns `arr[start..start + len]`, as an array of maximum length `max_len`. The circuit is
+    /// unsatisfiable unless `start + len <= arr.len` and `len <= max_len`.
+    pub fn slice_array(
+        &mut self,
+        arr: &VariableLengthArray,
+        start: Target,
+        len: Target,
+        max_len: usize,
+    ) -> VariableLengthArray {
+        let num_bits = log2_ceil(arr.max_len() + max_len + 1);
+        self.range_check(start, num_bits);
+        self.range_check(len, num_bits);
+        let end = self.add(start, len);
+        let remaining = self.sub(arr.len, end);
+        self.range_check(remaining, num_bits);
+
+        // Since `start <= arr.max_len()`, padding with `max_len` zeros keeps every index in bounds.
+        let mut padded = arr.data.clone();
+        padded.resize(arr.max_len() + max_len, self.zero());
+
+        let mask = self.variable_length_mask(len, max_len);
+        let data = (0..max_len)
+            .map(|i| {
+                let i_target = self.constant(F::from_canonical_usize(i));
+                let index = self.add(start, i_target);
+                let x = self.random_access_any_length(index, padded.clone());
+                self.mul(mask[i].target, x)
+            })
+            .collect();
+
+        VariableLengthArray { len, data }
+    }
+
+    /// Returns the concatenation of `a` and `b`, as an array of maximum length
+    /// `a.max_len() + b.max_len()`.
+    pub fn concat_arrays(
+        &mut self,
+        a: &VariableLengthArray,
+        b: &VariableLengthArray,
+    ) -> VariableLengthArray {
+        let max_len = a.max_len() + b.max_len();
+
+        // Entry `j` of `b` shifted right by `a.len` is entry `j - a.len + a.max_len()` of `b`
+        // prefixed with `a.max_len()` zeros. Since `a` and `b` are zero past their lengths, adding
+        // the shifted `b` to `a` yields the concatenation. The index can reach
+        // `2 * a.max_len() + b.max_len() - 1` when `a.len = 0`, so `b` is also suffixed with
+        // `a.max_len()` zeros to keep every index in bounds.
+        let zero = self.zero();
+        let mut shifted_b = vec![zero; a.max_len()];
+        shifted_b.extend_from_slice(&b.data);
+        shifted_b.resize(2 * a.max_len() + b.max_len(), zero);
+
+        let offset = self.constant(F::from_canonical_usize(a.max_len()));
+        let shift = self.sub(offset, a.len);
+        let data = (0..max_len)
+            .map(|j| {
+                let j_target = self.constant(F::from_canonical_usize(j));
+                let index = self.add(j_target, shift);
+                let b_entry = self.random_access_any_length(index, shifted_b.clone());
+                let a_entry = a.data.get(j).copied().unwrap_or(zero);
+                self.add(a_entry, b_entry)
+            })
+            .collect();
+
+        VariableLengthArray {
+            len: self.add(a.len, b.len),
+            data,
+        }
+    }
+
+    pub fn connect_variable_length_arrays(
+        &mut self,
+        a: &VariableLengthArray,
+        b: &VariableLengthArray,
+    ) {
+        assert_eq!(a.max_len(), b.max_len());
+        self.connect(a.len, b.len);
+        for (&x, &y) in a.data.iter().zip(&b.data) {
+            self.connect(x, y);
+        }
+    }
+
+    /// Checks that `b` is a permutation of `a`.
+    ///
+    /// This compares the grand products `prod_i (gamma - a_i)` and `prod_i (gamma - b_i)` at a
+    /// challenge `gamma` in the extension field, drawn from a challenger which observes `a` and `b`.
+    pub fn assert_permutation(&mut self, a: &[Target], b: &[Target]) {
+        let a = a.iter().map(|&x| vec![x]).collect::<Vec<_>>();
+        let b = b.iter().map(|&x| vec![x]).collect::<Vec<_>>();
+        self.assert_permutation_tuples(&a, &b);
+    }
+
+    /// Checks that the rows of `b` are a permutation of the rows of `a`, where all rows have the
+    /// same width.
+    ///
+    /// Each row is first compressed to `sum_j alpha^j row_j`, then the grand products
+    /// `prod_i (gamma - a_i)` and `prod_i (gamma - b_i)` are compared, where `alpha` and `gamma` are
+    /// challenges in the extension field drawn from a challenger which observes `a` and `b`.
+    pub fn assert_permutation_tuples(&mut self, a: &[Vec<Target>], b: &[Vec<Target>]) {
+        assert_eq!(a.len(), b.len(), "Permutation length mismatch");
+        if a.is_empty() {
+            return;
+        }
+        let width = a[0].len();
+        assert!(
+            a.iter().chain(b).all(|row| row.len() == width),
+            "Permutation width mismatch"
+        );
+
+        let mut challenger = RecursiveChallenger::<F, PoseidonHash, D>::new(self);
+        for row in a.iter().chain(b) {
+            challenger.observe_elements(row);
+        }
+        let alpha = challenger.get_extension_challenge(self);
+        let gamma = challenger.get_extension_challenge(self);
+
+        let a_product = self.permutation_grand_product(a, alpha, gamma);
+        let b_product = self.permutation_grand_product(b, alpha, gamma);
+        self.connect_extension(a_product, b_product);
+    }
+
+    /// Checks that `values` is sorted in non-decreasing order. All values are assumed to be
+    /// range-checked for having `num_bits` bits.
+    pub fn assert_sorted(&mut self, values: &[Target], num_bits: usize) {
+        Self::assert_comparison_bits(num_bits);
+
+        for pair in values.windows(2) {
+            let diff = self.sub(pair[1], pair[0]);
+            self.range_check(diff, num_bits);
+        }
+    }
+
+    /// Checks that `values` is sorted in strictly increasing order. All values are assumed to be
+    /// range-checked for having `num_bits` bits.
+    pub fn assert_strictly_sorted(&mut self, values: &[Target], num_bits: usize) {
+        for pair in values.windows(2) {
+            self.assert_less_than(pair[0], pair[1], num_bits);
+        }
+    }
+
+    /// Returns `[0 < len, 1 < len, ..., max_len - 1 < len]`, checking that `len <= max_len`.
+    fn variable_length_mask(&mut self, len: Target, max_len: usize) -> Vec<BoolTarget> {
+        self.assert_in_range(len, 0, max_len as u64 + 1);
+
+        let mut active = self._true();
+        (0..max_len)
+            .map(|i| {
+                let i = self.constant(F::from_canonical_usize(i));
+                let is_end = self.is_equal(len, i);
+                let not_end = self.not(is_end);
+                active = self.and(active, not_end);
+                active
+            })
+            .collect()
+    }
+
+    /// Like `random_access`, but supports vectors longer than a single `RandomAccessGate` allows,
+    /// by first selecting within chunks using the low bits of the index.
+    fn random_access_any_length(&mut self, access_index: Target, v: Vec<Target>) -> Target {
+        let chunk_bits = self.max_random_access_bits();
+        if v.len() <= 1 << chunk_bits {
+            return self.random_access(access_index, v);
+        }
+
+        let num_bits = log2_ceil(v.len());
+        let (low, high) = self.split_low_high(access_index, chunk_bits, num_bits);
+        let chunk_results = v
+            .chunks(1 << chunk_bits)
+            .map(|chunk| self.random_access(low, chunk.to_vec()))
+            .collect();
+        self.random_access_any_length(high, chunk_results)
+    }
+
+    /// The largest `bits` such that a `RandomAccessGate` over `2^bits` elements fits in a row.
+    fn max_random_access_bits(&self) -> usize {
+        let fits = |bits: usize| {
+            let vec_size = 1 << bits;
+            2 + vec_size <= self.config.num_routed_wires
+                && 2 + vec_size + bits <= self.config.num_wires
+        };
+        let mut bits = 0;
+        while fits(bits + 1) {
+            bits += 1;
+        }
+        bits
+    }
+
+    fn permutation_grand_product(
+        &mut self,
+        rows: &[Vec<Target>],
+        alpha: ExtensionTarget<D>,
+        gamma: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D> {
+        let mut product = self.one_extension();
+        for row in rows {
+            let mut compressed = self.zero_extension();
+            for &x in row.iter().rev() {
+                let x = self.convert_to_ext(x);
+                compressed = self.mul_add_extension(compressed, alpha, x);
+            }
+            let factor = self.sub_extension(gamma, compressed);
+            product = self.mul_extension(product, factor);
+        }
+        product
+    }
+}
+
+/// Setters for [`VariableLengthArray`]s, for any [`WitnessWrite`].
+pub trait WitnessWriteVariableLengthArray<F: Field>: WitnessWrite<F> {
+    /// Sets the length of `arr` to the number of `values`, and its contents to `values` padded
+    /// with zeros.
+    fn set_variable_length_array(&mut self, arr: &VariableLengthArray, values: &[F]) -> Result<()>;
+}
+
+impl<F: Field, W: WitnessWrite<F>> WitnessWriteVariableLengthArray<F> for W {
+    fn set_variable_length_array(&mut self, arr: &VariableLengthArray, values: &[F]) -> Result<()> {
+        ensure!(
+            values.len() <= arr.max_len(),
+            "Too many values for an array of maximum length {}.",
+            arr.max_len()
+        );
+        self.set_target(arr.len, F::from_canonical_usize(values.len()))?;
+        for (i, &target) in arr.data.iter().enumerate() {
+            self.set_target(target, values.get(i).copied().unwrap_or(F::ZERO))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::WitnessWriteVariableLengthArray;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn to_field(values: &[u64]) -> Vec<F> {
+        values.iter().map(|&x| F::from_canonical_u64(x)).collect()
+    }
+
+    #[test]
+    fn test_variable_length_arrays() -> Result<()> {
+        let values = F::rand_vec(70);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let arr = builder.add_virtual_variable_length_array(100);
+
+        let index = builder.constant(F::from_canonical_usize(66));
+        let entry = builder.random_access_array(&arr, index);
+        let expected_entry = builder.constant(values[66]);
+        builder.connect(entry, expected_entry);
+
+        let start = builder.constant(F::from_canonical_usize(5));
+        let len = builder.constant(F::from_canonical_usize(10));
+        let slice = builder.slice_array(&arr, start, len, 12);
+        let expected_slice = builder.constant_variable_length_array(&values[5..15], 12);
+        builder.connect_variable_length_arrays(&slice, &expected_slice);
+
+        let prefix = builder.constant_variable_length_array(&values[..3], 4);
+        let suffix = builder.constant_variable_length_array(&values[3..5], 3);
+        let concat = builder.concat_arrays(&prefix, &suffix);
+        let expected_concat = builder.constant_variable_length_array(&values[..5], 7);
+        builder.connect_variable_length_arrays(&concat, &expected_concat);
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_variable_length_array(&arr, &values)?;
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_concat_partial_and_full_arrays() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let a = builder.add_virtual_variable_length_array(4);
+        let b = builder.add_virtual_variable_length_array(4);
+        let concat = builder.concat_arrays(&a, &b);
+        let expected = builder.constant_variable_length_array(&to_field(&[1, 2, 3, 4, 5, 6, 7]), 8);
+        builder.connect_variable_length_arrays(&concat, &expected);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_variable_length_array(&a, &to_field(&[1, 2, 3]))?;
+        pw.set_variable_length_array(&b, &to_field(&[4, 5, 6, 7]))?;
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_out_of_bounds_access() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let arr = builder.add_virtual_variable_length_array(8);
+        let index = builder.constant(F::from_canonical_usize(3));
+        builder.random_access_array(&arr, index);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_variable_length_array(&arr, &to_field(&[1, 2, 3]))
+            .unwrap();
+        assert!(data.prove(pw).is_err());
+    }
+
+    #[test]
+    fn test_single_element_access() -> Result<()> {
+        let prove_access = |index: F| {
+            let config = CircuitConfig::standard_recursion_config();
+            let mut builder = CircuitBuilder::<F, D>::new(config);
+            let arr = builder.add_virtual_variable_length_array(1);
+            let index_target = builder.add_virtual_target();
+            builder.random_access_array(&arr, index_target);
+            let data = builder.build::<C>();
+
+            let mut pw = PartialWitness::new();
+            pw.set_variable_length_array(&arr, &to_field(&[7]))?;
+            pw.set_target(index_target, index)?;
+            let proof = data.prove(pw)?;
+            verify(proof, &data.verifier_only, &data.common)
+        };
+
+        prove_access(F::ZERO)?;
+        assert!(prove_access(F::NEG_ONE).is_err());
+        Ok(())
+    }
+
+    fn prove_permutation(a: &[u64], b: &[u64]) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let a_targets = builder.add_virtual_targets(a.len());
+        let b_targets = builder.add_virtual_targets(b.len());
+        builder.assert_permutation(&a_targets, &b_targets);
+        builder.assert_sorted(&b_targets, 32);
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target_arr(&a_targets, &to_field(a))?;
+        pw.set_target_arr(&b_targets, &to_field(b))?;
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_permutation() -> Result<()> {
+        prove_permutation(&[5, 3, 9, 3, 1], &[1, 3, 3, 5, 9])?;
+        assert!(prove_permutation(&[5, 3, 9, 3, 1], &[1, 3, 5, 5, 9]).is_err());
+        assert!(prove_permutation(&[5, 3, 9, 3, 1], &[1, 3, 3, 9, 5]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,436 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use anyhow::Result;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The number of lanes of the Keccak-f\[1600\] state, which is also the number of bits in a slice.
+pub(crate) const NUM_LANES: usize = 25;
+
+/// A gate which operates on a single slice of the Keccak-f\[1600\] state, i.e. the 25 bits sharing
+/// the same position `z` in their lanes. It computes the χ and ι steps of a round, followed by the
+/// θ step of the next round.
+///
+/// The ρ and π steps only move bits between slices, so they are handled with copy constraints
+/// between the outputs of the gates of a round and the inputs of the gates of the next one.
+///
+/// The θ step of a slice depends on the column parities of the previous slice, which are copied
+/// from the gate of that slice. Bits are indexed by lane, with lane `(x, y)` at index `x + 5y`.
+///
+/// The gate has two constants, which are flags enabling respectively the χ and θ steps, so that
+/// the first θ step and the last χ and ι steps of a permutation can be computed with the same gate.
+/// The inputs are assumed to be bits; the outputs are then bits as well.
+#[derive(Debug, Default)]
+pub struct KeccakSliceGate;
+
+impl KeccakSliceGate {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// The wire index of the `i`-th bit of the slice, before the χ step.
+    pub(crate) const fn wire_input(i: usize) -> usize {
+        debug_assert!(i < NUM_LANES);
+        i
+    }
+
+    /// The bit of the round constant added to the first lane by the ι step.
+    pub(crate) const WIRE_ROUND_CONSTANT: usize = NUM_LANES;
+
+    /// The wire index of the parity of the `x`-th column of the previous slice, after the ι step.
+    pub(crate) const fn wire_prev_parity(x: usize) -> usize {
+        debug_assert!(x < 5);
+        NUM_LANES + 1 + x
+    }
+
+    /// The wire index of the parity of the `x`-th column of this slice, after the ι step.
+    pub(crate) const fn wire_parity(x: usize) -> usize {
+        debug_assert!(x < 5);
+        NUM_LANES + 6 + x
+    }
+
+    /// The wire index of the `i`-th bit of the slice, after the θ step.
+    pub(crate) const fn wire_output(i: usize) -> usize {
+        debug_assert!(i < NUM_LANES);
+        NUM_LANES + 11 + i
+    }
+
+    /// The number of routed wires required by this gate.
+    pub(crate) const NUM_ROUTED_WIRES: usize = 2 * NUM_LANES + 11;
+
+    /// The wire index of the `i`-th bit of the slice, after the ι step.
+    const fn wire_chi(i: usize) -> usize {
+        debug_assert!(i < NUM_LANES);
+        Self::NUM_ROUTED_WIRES + i
+    }
+
+    /// Evaluates the constraints of the gate, given its wire values and constants.
+    fn eval_constraints<P: PackedField>(
+        wire: impl Fn(usize) -> P,
+        chi_flag: P,
+        theta_flag: P,
+        mut yield_constr: impl FnMut(P),
+    ) {
+        let two = P::Scalar::TWO;
+        let xor = |a: P, b: P| a + b - a * b * two;
+        let lane = |x: usize, y: usize| x % 5 + 5 * y;
+
+        // χ and ι steps.
+        for i in 0..NUM_LANES {
+            let (x, y) = (i % 5, i / 5);
+            let b = wire(Self::wire_input(i));
+            let b_1 = wire(Self::wire_input(lane(x + 1, y)));
+            let b_2 = wire(Self::wire_input(lane(x + 2, y)));
+            let mut computed = b + chi_flag * (P::ONES - b * two) * (P::ONES - b_1) * b_2;
+            if i == 0 {
+                computed = xor(computed, wire(Self::WIRE_ROUND_CONSTANT));
+            }
+            yield_constr(wire(Self::wire_chi(i)) - computed);
+        }
+
+        // Column parities.
+        for x in 0..5 {
+            let parity = (1..5).fold(wire(Self::wire_chi(x)), |acc, y| {
+                xor(acc, wire(Self::wire_chi(lane(x, y))))
+            });
+            yield_constr(wire(Self::wire_parity(x)) - parity);
+        }
+
+        // θ step.
+        for i in 0..NUM_LANES {
+            let x = i % 5;
+            let d = xor(
+                wire(Self::wire_parity((x + 4) % 5)),
+                wire(Self::wire_prev_parity((x + 1) % 5)),
+            );
+            let a = wire(Self::wire_chi(i));
+            let computed = a + theta_flag * (d - a * d * two);
+            yield_constr(wire(Self::wire_output(i)) - computed);
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for KeccakSliceGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(
+        &self,
+        _dst: &mut Vec<u8>,
+        _common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Self)
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(<Self as Gate<F, D>>::num_constraints(self));
+        Self::eval_constraints(
+            |i| vars.local_wires[i],
+            vars.local_constants[0],
+            vars.local_constants[1],
+            |c| constraints.push(c),
+        );
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let wire = |i: usize| vars.local_wires[i];
+        let lane = |x: usize, y: usize| x % 5 + 5 * y;
+        let chi_flag = vars.local_constants[0];
+        let theta_flag = vars.local_constants[1];
+        let xor = |builder: &mut CircuitBuilder<F, D>, a, b| {
+            let sum = builder.add_extension(a, b);
+            builder.arithmetic_extension(-F::TWO, F::ONE, a, b, sum)
+        };
+
+        let mut constraints = Vec::with_capacity(<Self as Gate<F, D>>::num_constraints(self));
+
+        // χ and ι steps.
+        let one = builder.one_extension();
+        for i in 0..NUM_LANES {
+            let (x, y) = (i % 5, i / 5);
+            let b = wire(Self::wire_input(i));
+            let b_1 = wire(Self::wire_input(lane(x + 1, y)));
+            let b_2 = wire(Self::wire_input(lane(x + 2, y)));
+            let one_minus_2b = builder.arithmetic_extension(-F::TWO, F::ONE, b, one, one);
+            let not_b_1 = builder.sub_extension(one, b_1);
+            let chi_term = builder.mul_many_extension([chi_flag, one_minus_2b, not_b_1, b_2]);
+            let mut computed = builder.add_extension(b, chi_term);
+            if i == 0 {
+                computed = xor(builder, computed, wire(Self::WIRE_ROUND_CONSTANT));
+            }
+            constraints.push(builder.sub_extension(wire(Self::wire_chi(i)), computed));
+        }
+
+        // Column parities.
+        for x in 0..5 {
+            let mut parity = wire(Self::wire_chi(x));
+            for y in 1..5 {
+                parity = xor(builder, parity, wire(Self::wire_chi(lane(x, y))));
+            }
+            constraints.push(builder.sub_extension(wire(Self::wire_parity(x)), parity));
+        }
+
+        // θ step.
+        for i in 0..NUM_LANES {
+            let x = i % 5;
+            let d = xor(
+                builder,
+                wire(Self::wire_parity((x + 4) % 5)),
+                wire(Self::wire_prev_parity((x + 1) % 5)),
+            );
+            let a = wire(Self::wire_chi(i));
+            let a_xor_d = xor(builder, a, d);
+            let delta = builder.sub_extension(a_xor_d, a);
+            let computed = builder.mul_add_extension(theta_flag, delta, a);
+            constraints.push(builder.sub_extension(wire(Self::wire_output(i)), computed));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        vec![
+            WitnessGeneratorRef::new(
+                KeccakChiGenerator {
+                    row,
+                    chi: local_constants[0].is_one(),
+                }
+                .adapter(),
+            ),
+            WitnessGeneratorRef::new(
+                KeccakThetaGenerator {
+                    row,
+                    theta: local_constants[1].is_one(),
+                }
+                .adapter(),
+            ),
+        ]
+    }
+
+    fn num_wires(&self) -> usize {
+        Self::NUM_ROUTED_WIRES + NUM_LANES
+    }
+
+    fn num_constants(&self) -> usize {
+        2
+    }
+
+    fn degree(&self) -> usize {
+        5
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * NUM_LANES + 5
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for KeccakSliceGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        Self::eval_constraints(
+            |i| vars.local_wires[i],
+            vars.local_constants[0],
+            vars.local_constants[1],
+            |c| yield_constr.one(c),
+        );
+    }
+}
+
+/// Generates the bits of a slice after the χ and ι steps, along with its column parities. This is
+/// separate from [`KeccakThetaGenerator`], since the θ step depends on the parities of the
+/// previous slice.
+#[derive(Clone, Debug, Default)]
+pub struct KeccakChiGenerator {
+    row: usize,
+    chi: bool,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for KeccakChiGenerator {
+    fn id(&self) -> String {
+        "KeccakChiGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..NUM_LANES)
+            .map(KeccakSliceGate::wire_input)
+            .chain([KeccakSliceGate::WIRE_ROUND_CONSTANT])
+            .map(|i| Target::wire(self.row, i))
+            .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+        let input = |x: usize, y: usize| get_wire(KeccakSliceGate::wire_input(x % 5 + 5 * y));
+
+        let mut state = [F::ZERO; NUM_LANES];
+        for (i, s) in state.iter_mut().enumerate() {
+            let (x, y) = (i % 5, i / 5);
+            *s = input(x, y);
+            if self.chi && input(x + 1, y).is_zero() {
+                *s += input(x + 2, y) * (F::ONE - s.double());
+            }
+        }
+        let round_constant = get_wire(KeccakSliceGate::WIRE_ROUND_CONSTANT);
+        if round_constant.is_one() {
+            state[0] = F::ONE - state[0];
+        }
+
+        for (i, &s) in state.iter().enumerate() {
+            out_buffer.set_target(Target::wire(self.row, KeccakSliceGate::wire_chi(i)), s)?;
+        }
+        for x in 0..5 {
+            let parity = (0..5).filter(|&y| state[x + 5 * y].is_one()).count();
+            out_buffer.set_target(
+                Target::wire(self.row, KeccakSliceGate::wire_parity(x)),
+                F::from_bool(parity % 2 == 1),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_bool(self.chi)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let chi = src.read_bool()?;
+        Ok(Self { row, chi })
+    }
+}
+
+/// Generates the bits of a slice after the θ step.
+#[derive(Clone, Debug, Default)]
+pub struct KeccakThetaGenerator {
+    row: usize,
+    theta: bool,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for KeccakThetaGenerator {
+    fn id(&self) -> String {
+        "KeccakThetaGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..NUM_LANES)
+            .map(KeccakSliceGate::wire_chi)
+            .chain((0..5).map(KeccakSliceGate::wire_parity))
+            .chain((0..5).map(KeccakSliceGate::wire_prev_parity))
+            .map(|i| Target::wire(self.row, i))
+            .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let get_wire = |wire: usize| -> F { witness.get_target(Target::wire(self.row, wire)) };
+
+        for i in 0..NUM_LANES {
+            let x = i % 5;
+            let mut output = get_wire(KeccakSliceGate::wire_chi(i));
+            if self.theta {
+                let d = get_wire(KeccakSliceGate::wire_parity((x + 4) % 5))
+                    != get_wire(KeccakSliceGate::wire_prev_parity((x + 1) % 5));
+                if d {
+                    output = F::ONE - output;
+                }
+            }
+            out_buffer.set_target(
+                Target::wire(self.row, KeccakSliceGate::wire_output(i)),
+                output,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)?;
+        dst.write_bool(self.theta)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        let theta = src.read_bool()?;
+        Ok(Self { row, theta })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::gates::keccak::KeccakSliceGate;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        test_low_degree::<GoldilocksField, _, 4>(KeccakSliceGate::new())
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        test_eval_fns::<F, C, _, D>(KeccakSliceGate::new())
+    }
+}
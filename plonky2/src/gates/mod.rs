@@ -30,18 +30,22 @@ pub mod constant;
 pub mod coset_interpolation;
 pub mod exponentiation;
 pub mod gate;
+pub mod keccak;
 pub mod lookup;
 pub mod lookup_table;
 pub mod multiplication_extension;
 pub mod noop;
 pub mod packed_util;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod poseidon_mds;
 pub mod public_input;
 pub mod random_access;
 pub mod reducing;
 pub mod reducing_extension;
 pub(crate) mod selectors;
+pub mod u32_arithmetic;
+pub mod u32_subtraction;
 pub mod util;
 
 // Can't use #[cfg(test)] here because it needs to be visible to other crates.
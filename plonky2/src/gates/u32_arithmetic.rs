@@ -0,0 +1,469 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use anyhow::Result;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A gate which computes `x * y + z` for 32-bit inputs, and splits the result into its low and
+/// high 32-bit halves, i.e. `output_low + 2^32 * output_high`. The halves are range-checked with
+/// 2-bit limbs, and the result is checked to be canonical, i.e. less than the field order. If the
+/// config has enough wires, it can support several such operations in one gate.
+///
+/// The inputs are not range-checked by this gate; the decomposition is correct as long as
+/// `x * y + z` is less than the field order, which holds in particular for 32-bit inputs.
+#[derive(Debug, Clone, Default)]
+pub struct U32ArithmeticGate {
+    /// Number of multiply-add operations performed by the gate.
+    pub num_ops: usize,
+}
+
+impl U32ArithmeticGate {
+    /// The number of bits in each range-checked limb of the outputs.
+    const LIMB_BITS: usize = 2;
+    /// The number of limbs in each half of the output.
+    const NUM_LIMBS_PER_HALF: usize = 32 / Self::LIMB_BITS;
+    /// The number of limbs per operation.
+    const NUM_LIMBS: usize = 2 * Self::NUM_LIMBS_PER_HALF;
+    /// The number of routed wires per operation.
+    const ROUTED_WIRES_PER_OP: usize = 5;
+    /// The total number of wires per operation, including the inverse and limb wires.
+    const WIRES_PER_OP: usize = Self::ROUTED_WIRES_PER_OP + 1 + Self::NUM_LIMBS;
+
+    pub const fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) const fn num_ops(config: &CircuitConfig) -> usize {
+        let num_ops_routed = config.num_routed_wires / Self::ROUTED_WIRES_PER_OP;
+        let num_ops_total = config.num_wires / Self::WIRES_PER_OP;
+        if num_ops_routed < num_ops_total {
+            num_ops_routed
+        } else {
+            num_ops_total
+        }
+    }
+
+    pub(crate) const fn wire_ith_multiplicand_0(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i
+    }
+    pub(crate) const fn wire_ith_multiplicand_1(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 1
+    }
+    pub(crate) const fn wire_ith_addend(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 2
+    }
+    pub(crate) const fn wire_ith_output_low_half(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 3
+    }
+    pub(crate) const fn wire_ith_output_high_half(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 4
+    }
+
+    /// The wire holding the inverse of `2^32 - 1 - output_high`, or zero if it is not invertible.
+    const fn wire_ith_inverse(&self, i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * self.num_ops + (1 + Self::NUM_LIMBS) * i
+    }
+
+    /// The wire of the `j`-th limb of the `i`-th output, in little-endian order, starting with the
+    /// limbs of the low half.
+    const fn wire_ith_output_jth_limb(&self, i: usize, j: usize) -> usize {
+        debug_assert!(j < Self::NUM_LIMBS);
+        Self::ROUTED_WIRES_PER_OP * self.num_ops + (1 + Self::NUM_LIMBS) * i + 1 + j
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32ArithmeticGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_ops)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_ops = src.read_usize()?;
+        Ok(Self { num_ops })
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops * (4 + Self::NUM_LIMBS));
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.local_wires[Self::wire_ith_multiplicand_0(i)];
+            let multiplicand_1 = vars.local_wires[Self::wire_ith_multiplicand_1(i)];
+            let addend = vars.local_wires[Self::wire_ith_addend(i)];
+            let output_low = vars.local_wires[Self::wire_ith_output_low_half(i)];
+            let output_high = vars.local_wires[Self::wire_ith_output_high_half(i)];
+            let inverse = vars.local_wires[self.wire_ith_inverse(i)];
+
+            let base = F::Extension::from_canonical_u64(1 << 32);
+            let computed_output = multiplicand_0 * multiplicand_1 + addend;
+            constraints.push(output_low + output_high * base - computed_output);
+
+            // If `output_high = 2^32 - 1`, the output must be less than the field order, which
+            // forces `output_low = 0`.
+            let u32_max = F::Extension::from_canonical_u32(u32::MAX);
+            let diff = u32_max - output_high;
+            constraints.push((diff * inverse - F::Extension::ONE) * output_low);
+
+            let limb_base = F::Extension::from_canonical_u64(1 << Self::LIMB_BITS);
+            let mut combined_low = F::Extension::ZERO;
+            let mut combined_high = F::Extension::ZERO;
+            for j in (0..Self::NUM_LIMBS).rev() {
+                let limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                let product = (0..1 << Self::LIMB_BITS)
+                    .map(|x| limb - F::Extension::from_canonical_usize(x))
+                    .product::<F::Extension>();
+                constraints.push(product);
+
+                if j < Self::NUM_LIMBS_PER_HALF {
+                    combined_low = combined_low * limb_base + limb;
+                } else {
+                    combined_high = combined_high * limb_base + limb;
+                }
+            }
+            constraints.push(combined_low - output_low);
+            constraints.push(combined_high - output_high);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_ops * (4 + Self::NUM_LIMBS));
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.local_wires[Self::wire_ith_multiplicand_0(i)];
+            let multiplicand_1 = vars.local_wires[Self::wire_ith_multiplicand_1(i)];
+            let addend = vars.local_wires[Self::wire_ith_addend(i)];
+            let output_low = vars.local_wires[Self::wire_ith_output_low_half(i)];
+            let output_high = vars.local_wires[Self::wire_ith_output_high_half(i)];
+            let inverse = vars.local_wires[self.wire_ith_inverse(i)];
+
+            let base = F::from_canonical_u64(1 << 32);
+            let computed_output = builder.mul_add_extension(multiplicand_0, multiplicand_1, addend);
+            let combined_output = builder.mul_const_add_extension(base, output_high, output_low);
+            constraints.push(builder.sub_extension(combined_output, computed_output));
+
+            let u32_max = builder.constant_extension(F::Extension::from_canonical_u32(u32::MAX));
+            let one = builder.one_extension();
+            let diff = builder.sub_extension(u32_max, output_high);
+            let diff_inverse_minus_one = builder.mul_sub_extension(diff, inverse, one);
+            constraints.push(builder.mul_extension(diff_inverse_minus_one, output_low));
+
+            let limb_base = F::from_canonical_u64(1 << Self::LIMB_BITS);
+            let mut combined_low = builder.zero_extension();
+            let mut combined_high = builder.zero_extension();
+            for j in (0..Self::NUM_LIMBS).rev() {
+                let limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                let mut product = builder.one_extension();
+                for x in 0..1 << Self::LIMB_BITS {
+                    let x = builder.constant_extension(F::Extension::from_canonical_usize(x));
+                    let diff = builder.sub_extension(limb, x);
+                    product = builder.mul_extension(product, diff);
+                }
+                constraints.push(product);
+
+                if j < Self::NUM_LIMBS_PER_HALF {
+                    combined_low = builder.mul_const_add_extension(limb_base, combined_low, limb);
+                } else {
+                    combined_high = builder.mul_const_add_extension(limb_base, combined_high, limb);
+                }
+            }
+            constraints.push(builder.sub_extension(combined_low, output_low));
+            constraints.push(builder.sub_extension(combined_high, output_high));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    U32ArithmeticGenerator {
+                        gate: self.clone(),
+                        row,
+                        i,
+                    }
+                    .adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * Self::WIRES_PER_OP
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        1 << Self::LIMB_BITS
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops * (4 + Self::NUM_LIMBS)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D> for U32ArithmeticGate {
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.local_wires[Self::wire_ith_multiplicand_0(i)];
+            let multiplicand_1 = vars.local_wires[Self::wire_ith_multiplicand_1(i)];
+            let addend = vars.local_wires[Self::wire_ith_addend(i)];
+            let output_low = vars.local_wires[Self::wire_ith_output_low_half(i)];
+            let output_high = vars.local_wires[Self::wire_ith_output_high_half(i)];
+            let inverse = vars.local_wires[self.wire_ith_inverse(i)];
+
+            let base = F::from_canonical_u64(1 << 32);
+            let computed_output = multiplicand_0 * multiplicand_1 + addend;
+            yield_constr.one(output_low + output_high * base - computed_output);
+
+            let u32_max = P::from(F::from_canonical_u32(u32::MAX));
+            let diff = u32_max - output_high;
+            yield_constr.one((diff * inverse - P::ONES) * output_low);
+
+            let limb_base = F::from_canonical_u64(1 << Self::LIMB_BITS);
+            let mut combined_low = P::ZEROS;
+            let mut combined_high = P::ZEROS;
+            for j in (0..Self::NUM_LIMBS).rev() {
+                let limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                let product = (0..1 << Self::LIMB_BITS)
+                    .map(|x| limb - F::from_canonical_usize(x))
+                    .fold(P::ONES, |acc, diff| acc * diff);
+                yield_constr.one(product);
+
+                if j < Self::NUM_LIMBS_PER_HALF {
+                    combined_low = combined_low * limb_base + limb;
+                } else {
+                    combined_high = combined_high * limb_base + limb;
+                }
+            }
+            yield_constr.one(combined_low - output_low);
+            yield_constr.one(combined_high - output_high);
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct U32ArithmeticGenerator {
+    gate: U32ArithmeticGate,
+    row: usize,
+    i: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for U32ArithmeticGenerator
+{
+    fn id(&self) -> String {
+        "U32ArithmeticGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        [
+            U32ArithmeticGate::wire_ith_multiplicand_0(self.i),
+            U32ArithmeticGate::wire_ith_multiplicand_1(self.i),
+            U32ArithmeticGate::wire_ith_addend(self.i),
+        ]
+        .iter()
+        .map(|&i| Target::wire(self.row, i))
+        .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let local_wire = |column| Target::wire(self.row, column);
+        let get_wire = |column| witness.get_target(local_wire(column));
+
+        let multiplicand_0 = get_wire(U32ArithmeticGate::wire_ith_multiplicand_0(self.i));
+        let multiplicand_1 = get_wire(U32ArithmeticGate::wire_ith_multiplicand_1(self.i));
+        let addend = get_wire(U32ArithmeticGate::wire_ith_addend(self.i));
+
+        let output = (multiplicand_0 * multiplicand_1 + addend).to_canonical_u64();
+        let output_low = output & u32::MAX as u64;
+        let output_high = output >> 32;
+
+        out_buffer.set_target(
+            local_wire(U32ArithmeticGate::wire_ith_output_low_half(self.i)),
+            F::from_canonical_u64(output_low),
+        )?;
+        out_buffer.set_target(
+            local_wire(U32ArithmeticGate::wire_ith_output_high_half(self.i)),
+            F::from_canonical_u64(output_high),
+        )?;
+
+        let diff = F::from_canonical_u32(u32::MAX) - F::from_canonical_u64(output_high);
+        out_buffer.set_target(
+            local_wire(self.gate.wire_ith_inverse(self.i)),
+            diff.try_inverse().unwrap_or(F::ZERO),
+        )?;
+
+        let limb_mask = (1 << U32ArithmeticGate::LIMB_BITS) - 1;
+        for j in 0..U32ArithmeticGate::NUM_LIMBS {
+            let limb = (output >> (U32ArithmeticGate::LIMB_BITS * j)) & limb_mask;
+            out_buffer.set_target(
+                local_wire(self.gate.wire_ith_output_jth_limb(self.i, j)),
+                F::from_canonical_u64(limb),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.gate.num_ops)?;
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.i)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let gate = U32ArithmeticGate {
+            num_ops: src.read_usize()?,
+        };
+        let row = src.read_usize()?;
+        let i = src.read_usize()?;
+        Ok(Self { gate, row, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::Sample;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::hash::hash_types::HashOut;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = U32ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = U32ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+
+    #[test]
+    fn test_gate_constraint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        let gate = U32ArithmeticGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        // Computes the wires of each operation, given its inputs and output halves.
+        let get_wires = |ops: &[(u64, u64, u64, u64, u64)]| -> Vec<FF> {
+            let mut routed = Vec::new();
+            let mut unrouted = Vec::new();
+            for &(x, y, z, low, high) in ops {
+                routed.extend([x, y, z, low, high].map(F::from_canonical_u64));
+
+                let diff = F::from_canonical_u32(u32::MAX) - F::from_canonical_u64(high);
+                unrouted.push(diff.try_inverse().unwrap_or(F::ZERO));
+                let output = low + (high << 32);
+                unrouted.extend((0..32).map(|j| F::from_canonical_u64((output >> (2 * j)) & 3)));
+            }
+            routed
+                .into_iter()
+                .chain(unrouted)
+                .map(|x| x.into())
+                .collect()
+        };
+
+        let mut rng = OsRng;
+        let mut ops = (0..gate.num_ops)
+            .map(|_| {
+                let [x, y, z] = [(); 3].map(|_| rng.gen::<u32>() as u64);
+                let output = x * y + z;
+                (x, y, z, output & u32::MAX as u64, output >> 32)
+            })
+            .collect::<Vec<_>>();
+        // The largest possible output has a high half of `2^32 - 1`.
+        let max = u32::MAX as u64;
+        ops[0] = (max, max, max, 0, max);
+
+        let good_vars = EvaluationVars::<F, D> {
+            local_constants: &[],
+            local_wires: &get_wires(&ops),
+            public_inputs_hash: &HashOut::rand(),
+        };
+        assert!(
+            gate.eval_unfiltered(good_vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are not satisfied."
+        );
+
+        // `1 + 2^32 * (2^32 - 1)` is the field order, so it is a non-canonical encoding of zero.
+        ops[0] = (0, 0, 0, 1, max);
+        let bad_vars = EvaluationVars::<F, D> {
+            local_constants: &[],
+            local_wires: &get_wires(&ops),
+            public_inputs_hash: &HashOut::rand(),
+        };
+        assert!(
+            !gate.eval_unfiltered(bad_vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are satisfied with a non-canonical output."
+        );
+    }
+}
@@ -0,0 +1,430 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use anyhow::Result;
+
+use crate::field::extension::Extendable;
+use crate::field::packed::PackedField;
+use crate::field::types::Field;
+use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
+use crate::gates::util::StridedConstraintConsumer;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// A gate which computes `x - y - borrow` for 32-bit inputs `x`, `y` and a boolean `borrow`, as
+/// `result - 2^32 * output_borrow` where `result` is range-checked with 2-bit limbs and
+/// `output_borrow` is boolean. If the config has enough wires, it can support several such
+/// operations in one gate.
+#[derive(Debug, Clone, Default)]
+pub struct U32SubtractionGate {
+    /// Number of subtractions performed by the gate.
+    pub num_ops: usize,
+}
+
+impl U32SubtractionGate {
+    /// The number of bits in each range-checked limb of the result.
+    const LIMB_BITS: usize = 2;
+    /// The number of limbs of the result.
+    const NUM_LIMBS: usize = 32 / Self::LIMB_BITS;
+    /// The number of routed wires per operation.
+    const ROUTED_WIRES_PER_OP: usize = 5;
+    /// The total number of wires per operation, including the limb wires.
+    const WIRES_PER_OP: usize = Self::ROUTED_WIRES_PER_OP + Self::NUM_LIMBS;
+
+    pub const fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+        }
+    }
+
+    /// Determine the maximum number of operations that can fit in one gate for the given config.
+    pub(crate) const fn num_ops(config: &CircuitConfig) -> usize {
+        let num_ops_routed = config.num_routed_wires / Self::ROUTED_WIRES_PER_OP;
+        let num_ops_total = config.num_wires / Self::WIRES_PER_OP;
+        if num_ops_routed < num_ops_total {
+            num_ops_routed
+        } else {
+            num_ops_total
+        }
+    }
+
+    pub(crate) const fn wire_ith_input_x(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i
+    }
+    pub(crate) const fn wire_ith_input_y(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 1
+    }
+    pub(crate) const fn wire_ith_input_borrow(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 2
+    }
+    pub(crate) const fn wire_ith_output_result(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 3
+    }
+    pub(crate) const fn wire_ith_output_borrow(i: usize) -> usize {
+        Self::ROUTED_WIRES_PER_OP * i + 4
+    }
+
+    /// The wire of the `j`-th limb of the `i`-th result, in little-endian order.
+    const fn wire_ith_output_jth_limb(&self, i: usize, j: usize) -> usize {
+        debug_assert!(j < Self::NUM_LIMBS);
+        Self::ROUTED_WIRES_PER_OP * self.num_ops + Self::NUM_LIMBS * i + j
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U32SubtractionGate {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.num_ops)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_ops = src.read_usize()?;
+        Ok(Self { num_ops })
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_ops * (3 + Self::NUM_LIMBS));
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[Self::wire_ith_input_x(i)];
+            let input_y = vars.local_wires[Self::wire_ith_input_y(i)];
+            let input_borrow = vars.local_wires[Self::wire_ith_input_borrow(i)];
+            let output_result = vars.local_wires[Self::wire_ith_output_result(i)];
+            let output_borrow = vars.local_wires[Self::wire_ith_output_borrow(i)];
+
+            let base = F::Extension::from_canonical_u64(1 << 32);
+            let result_initial = input_x - input_y - input_borrow;
+            constraints.push(output_result - (result_initial + output_borrow * base));
+
+            let limb_base = F::Extension::from_canonical_u64(1 << Self::LIMB_BITS);
+            let mut combined_limbs = F::Extension::ZERO;
+            for j in (0..Self::NUM_LIMBS).rev() {
+                let limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                let product = (0..1 << Self::LIMB_BITS)
+                    .map(|x| limb - F::Extension::from_canonical_usize(x))
+                    .product::<F::Extension>();
+                constraints.push(product);
+
+                combined_limbs = combined_limbs * limb_base + limb;
+            }
+            constraints.push(combined_limbs - output_result);
+
+            constraints.push(output_borrow * (F::Extension::ONE - output_borrow));
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_ops * (3 + Self::NUM_LIMBS));
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[Self::wire_ith_input_x(i)];
+            let input_y = vars.local_wires[Self::wire_ith_input_y(i)];
+            let input_borrow = vars.local_wires[Self::wire_ith_input_borrow(i)];
+            let output_result = vars.local_wires[Self::wire_ith_output_result(i)];
+            let output_borrow = vars.local_wires[Self::wire_ith_output_borrow(i)];
+
+            let base = F::from_canonical_u64(1 << 32);
+            let diff = builder.sub_extension(input_x, input_y);
+            let result_initial = builder.sub_extension(diff, input_borrow);
+            let computed_output =
+                builder.mul_const_add_extension(base, output_borrow, result_initial);
+            constraints.push(builder.sub_extension(output_result, computed_output));
+
+            let limb_base = F::from_canonical_u64(1 << Self::LIMB_BITS);
+            let mut combined_limbs = builder.zero_extension();
+            for j in (0..Self::NUM_LIMBS).rev() {
+                let limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                let mut product = builder.one_extension();
+                for x in 0..1 << Self::LIMB_BITS {
+                    let x = builder.constant_extension(F::Extension::from_canonical_usize(x));
+                    let diff = builder.sub_extension(limb, x);
+                    product = builder.mul_extension(product, diff);
+                }
+                constraints.push(product);
+
+                combined_limbs = builder.mul_const_add_extension(limb_base, combined_limbs, limb);
+            }
+            constraints.push(builder.sub_extension(combined_limbs, output_result));
+
+            let one = builder.one_extension();
+            let not_borrow = builder.sub_extension(one, output_borrow);
+            constraints.push(builder.mul_extension(output_borrow, not_borrow));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        (0..self.num_ops)
+            .map(|i| {
+                WitnessGeneratorRef::new(
+                    U32SubtractionGenerator {
+                        gate: self.clone(),
+                        row,
+                        i,
+                    }
+                    .adapter(),
+                )
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * Self::WIRES_PER_OP
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        1 << Self::LIMB_BITS
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.num_ops * (3 + Self::NUM_LIMBS)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
+    for U32SubtractionGate
+{
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[Self::wire_ith_input_x(i)];
+            let input_y = vars.local_wires[Self::wire_ith_input_y(i)];
+            let input_borrow = vars.local_wires[Self::wire_ith_input_borrow(i)];
+            let output_result = vars.local_wires[Self::wire_ith_output_result(i)];
+            let output_borrow = vars.local_wires[Self::wire_ith_output_borrow(i)];
+
+            let base = F::from_canonical_u64(1 << 32);
+            let result_initial = input_x - input_y - input_borrow;
+            yield_constr.one(output_result - (result_initial + output_borrow * base));
+
+            let limb_base = F::from_canonical_u64(1 << Self::LIMB_BITS);
+            let mut combined_limbs = P::ZEROS;
+            for j in (0..Self::NUM_LIMBS).rev() {
+                let limb = vars.local_wires[self.wire_ith_output_jth_limb(i, j)];
+                let product = (0..1 << Self::LIMB_BITS)
+                    .map(|x| limb - F::from_canonical_usize(x))
+                    .fold(P::ONES, |acc, diff| acc * diff);
+                yield_constr.one(product);
+
+                combined_limbs = combined_limbs * limb_base + limb;
+            }
+            yield_constr.one(combined_limbs - output_result);
+
+            yield_constr.one(output_borrow * (P::ONES - output_borrow));
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct U32SubtractionGenerator {
+    gate: U32SubtractionGate,
+    row: usize,
+    i: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for U32SubtractionGenerator
+{
+    fn id(&self) -> String {
+        "U32SubtractionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        [
+            U32SubtractionGate::wire_ith_input_x(self.i),
+            U32SubtractionGate::wire_ith_input_y(self.i),
+            U32SubtractionGate::wire_ith_input_borrow(self.i),
+        ]
+        .iter()
+        .map(|&i| Target::wire(self.row, i))
+        .collect()
+    }
+
+    fn run_once(
+        &self,
+        witness: &PartitionWitness<F>,
+        out_buffer: &mut GeneratedValues<F>,
+    ) -> Result<()> {
+        let local_wire = |column| Target::wire(self.row, column);
+        let get_wire = |column| witness.get_target(local_wire(column));
+
+        let input_x = get_wire(U32SubtractionGate::wire_ith_input_x(self.i));
+        let input_y = get_wire(U32SubtractionGate::wire_ith_input_y(self.i));
+        let input_borrow = get_wire(U32SubtractionGate::wire_ith_input_borrow(self.i));
+
+        let result_initial = input_x - input_y - input_borrow;
+        let result_initial_u64 = result_initial.to_canonical_u64();
+        let output_borrow = if result_initial_u64 > u32::MAX as u64 {
+            F::ONE
+        } else {
+            F::ZERO
+        };
+
+        let base = F::from_canonical_u64(1 << 32);
+        let output_result = result_initial + base * output_borrow;
+        out_buffer.set_target(
+            local_wire(U32SubtractionGate::wire_ith_output_result(self.i)),
+            output_result,
+        )?;
+        out_buffer.set_target(
+            local_wire(U32SubtractionGate::wire_ith_output_borrow(self.i)),
+            output_borrow,
+        )?;
+
+        let output_result = output_result.to_canonical_u64();
+        let limb_mask = (1 << U32SubtractionGate::LIMB_BITS) - 1;
+        for j in 0..U32SubtractionGate::NUM_LIMBS {
+            let limb = (output_result >> (U32SubtractionGate::LIMB_BITS * j)) & limb_mask;
+            out_buffer.set_target(
+                local_wire(self.gate.wire_ith_output_jth_limb(self.i, j)),
+                F::from_canonical_u64(limb),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.gate.num_ops)?;
+        dst.write_usize(self.row)?;
+        dst.write_usize(self.i)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let gate = U32SubtractionGate {
+            num_ops: src.read_usize()?,
+        };
+        let row = src.read_usize()?;
+        let i = src.read_usize()?;
+        Ok(Self { gate, row, i })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::Sample;
+    use crate::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use crate::hash::hash_types::HashOut;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn low_degree() {
+        let gate = U32SubtractionGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_low_degree::<GoldilocksField, _, 4>(gate);
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let gate = U32SubtractionGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        test_eval_fns::<F, C, _, D>(gate)
+    }
+
+    #[test]
+    fn test_gate_constraint() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        let gate = U32SubtractionGate::new_from_config(&CircuitConfig::standard_recursion_config());
+        // Computes the wires of each operation, given its inputs and outputs.
+        let get_wires = |ops: &[(u32, u32, bool, u32, bool)]| -> Vec<FF> {
+            let mut routed = Vec::new();
+            let mut limbs = Vec::new();
+            for &(x, y, borrow, result, output_borrow) in ops {
+                routed.extend([
+                    F::from_canonical_u32(x),
+                    F::from_canonical_u32(y),
+                    F::from_bool(borrow),
+                    F::from_canonical_u32(result),
+                    F::from_bool(output_borrow),
+                ]);
+                limbs.extend((0..16).map(|j| F::from_canonical_u32((result >> (2 * j)) & 3)));
+            }
+            routed.into_iter().chain(limbs).map(|x| x.into()).collect()
+        };
+
+        let mut rng = OsRng;
+        let mut ops = (0..gate.num_ops)
+            .map(|_| {
+                let (x, y, borrow) = (rng.gen::<u32>(), rng.gen::<u32>(), rng.gen::<bool>());
+                let (diff, b0) = x.overflowing_sub(y);
+                let (result, b1) = diff.overflowing_sub(borrow as u32);
+                (x, y, borrow, result, b0 || b1)
+            })
+            .collect::<Vec<_>>();
+
+        let good_vars = EvaluationVars::<F, D> {
+            local_constants: &[],
+            local_wires: &get_wires(&ops),
+            public_inputs_hash: &HashOut::rand(),
+        };
+        assert!(
+            gate.eval_unfiltered(good_vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are not satisfied."
+        );
+
+        // The output borrow must be set when the subtraction underflows.
+        ops[0] = (0, 1, false, u32::MAX, false);
+        let bad_vars = EvaluationVars::<F, D> {
+            local_constants: &[],
+            local_wires: &get_wires(&ops),
+            public_inputs_hash: &HashOut::rand(),
+        };
+        assert!(
+            !gate.eval_unfiltered(bad_vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are satisfied with a wrong output."
+        );
+    }
+}
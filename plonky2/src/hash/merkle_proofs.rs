@@ -3,7 +3,9 @@ use alloc::{vec, vec::Vec};
 use core::ops::RangeInclusive;
 
 use anyhow::{ensure, Result};
+use hashbrown::HashMap;
 use itertools::Itertools;
+use num::Integer;
 use serde::{Deserialize, Serialize};
 
 use crate::field::extension::Extendable;
@@ -14,6 +16,7 @@ use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::VerifierCircuitTarget;
 use crate::plonk::config::{AlgebraicHasher, GenericHashOut, Hasher};
+use crate::util::log2_strict;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(bound = "")]
@@ -294,6 +297,127 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
+    /// Verifies that `old_leaf_data` is present at the given index in the Merkle tree with root
+    /// `old_root`, and returns the root of the tree in which it is replaced by `new_leaf_data`. Both
+    /// paths are computed from the siblings of `proof`. The index is given by its little-endian bits.
+    pub fn update_merkle_leaf_circuit<H: AlgebraicHasher<F>>(
+        &mut self,
+        old_leaf_data: Vec<Target>,
+        new_leaf_data: Vec<Target>,
+        leaf_index_bits: &[BoolTarget],
+        old_root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) -> HashOutTarget {
+        assert_eq!(leaf_index_bits.len(), proof.siblings.len());
+
+        let computed_old_root =
+            self.merkle_root_from_path::<H>(old_leaf_data, leaf_index_bits, proof);
+        self.connect_hashes(computed_old_root, old_root);
+        self.merkle_root_from_path::<H>(new_leaf_data, leaf_index_bits, proof)
+    }
+
+    /// Verifies a batch of Merkle proofs compressed with [`compress_merkle_proofs`], i.e. checks
+    /// that each `leaves_data[k]` is present at index `leaf_indices[k]` in the Merkle tree of the
+    /// given height with the given cap.
+    ///
+    /// The indices are fixed when building the circuit, which determines the shape of the
+    /// compressed proofs. Each node shared by several paths is only hashed once.
+    ///
+    /// [`compress_merkle_proofs`]: crate::hash::path_compression::compress_merkle_proofs
+    pub fn verify_merkle_multiproof_circuit<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaves_data: &[Vec<Target>],
+        leaf_indices: &[usize],
+        height: usize,
+        merkle_cap: &MerkleCapTarget,
+        compressed_proofs: &[MerkleProofTarget],
+    ) {
+        assert_eq!(leaves_data.len(), leaf_indices.len());
+        assert_eq!(compressed_proofs.len(), leaf_indices.len());
+        let cap_height = log2_strict(merkle_cap.0.len());
+        let num_leaves = 1 << height;
+
+        // The nodes of the tree computed so far, indexed as in `compress_merkle_proofs`.
+        let mut seen = HashMap::new();
+        for (&i, data) in leaf_indices.iter().zip(leaves_data) {
+            let digest = self.hash_or_noop::<H>(data.clone());
+            if let Some(&other_digest) = seen.get(&(i + num_leaves)) {
+                self.connect_hashes(digest, other_digest);
+            } else {
+                seen.insert(i + num_leaves, digest);
+            }
+        }
+
+        let mut siblings = compressed_proofs
+            .iter()
+            .map(|p| p.siblings.iter())
+            .collect::<Vec<_>>();
+        for layer_height in 0..height - cap_height {
+            for (&i, p) in leaf_indices.iter().zip(siblings.iter_mut()) {
+                let index = (i + num_leaves) >> layer_height;
+                if seen.contains_key(&(index >> 1)) {
+                    // The parent was computed from another path, which also went through `index`.
+                    continue;
+                }
+                let current_digest = seen[&index];
+                let sibling_digest = *seen
+                    .entry(index ^ 1)
+                    .or_insert_with(|| *p.next().expect("Compressed Merkle proof is too short"));
+                let swap = self.constant_bool(index.is_odd());
+                let parent_digest =
+                    self.hash_merkle_siblings::<H>(current_digest, sibling_digest, swap);
+                seen.insert(index >> 1, parent_digest);
+            }
+        }
+        assert!(
+            siblings.iter_mut().all(|p| p.next().is_none()),
+            "Compressed Merkle proof is too long"
+        );
+
+        for &i in leaf_indices {
+            let index = (i + num_leaves) >> (height - cap_height);
+            self.connect_hashes(seen[&index], merkle_cap.0[index - (1 << cap_height)]);
+        }
+    }
+
+    /// Computes the root of the Merkle tree from the given leaf data and path.
+    fn merkle_root_from_path<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf_data: Vec<Target>,
+        leaf_index_bits: &[BoolTarget],
+        proof: &MerkleProofTarget,
+    ) -> HashOutTarget {
+        let mut state = self.hash_or_noop::<H>(leaf_data);
+        for (&bit, &sibling) in leaf_index_bits.iter().zip(&proof.siblings) {
+            state = self.hash_merkle_siblings::<H>(state, sibling, bit);
+        }
+        state
+    }
+
+    /// Hashes `state` with its sibling, swapping them if `swap` is true, i.e. if `state` is a
+    /// right child.
+    pub(crate) fn hash_merkle_siblings<H: AlgebraicHasher<F>>(
+        &mut self,
+        state: HashOutTarget,
+        sibling: HashOutTarget,
+        swap: BoolTarget,
+    ) -> HashOutTarget {
+        debug_assert!(H::AlgebraicPermutation::RATE >= NUM_HASH_OUT_ELTS);
+
+        let zero = self.zero();
+        let mut perm_inputs = H::AlgebraicPermutation::default();
+        perm_inputs.set_from_slice(&state.elements, 0);
+        perm_inputs.set_from_slice(&sibling.elements, NUM_HASH_OUT_ELTS);
+        // Ensure the rest of the state, if any, is zero:
+        perm_inputs.set_from_iter(core::iter::repeat(zero), 2 * NUM_HASH_OUT_ELTS);
+        let perm_outs = self.permute_swapped::<H>(perm_inputs, swap);
+        HashOutTarget {
+            elements: perm_outs.squeeze()[0..NUM_HASH_OUT_ELTS]
+                .try_into()
+                .unwrap(),
+        }
+    }
+
     pub fn connect_hashes(&mut self, x: HashOutTarget, y: HashOutTarget) {
         for i in 0..NUM_HASH_OUT_ELTS {
             self.connect(x.elements[i], y.elements[i]);
@@ -320,6 +444,7 @@ mod tests {
     use super::*;
     use crate::field::types::Field;
     use crate::hash::merkle_tree::MerkleTree;
+    use crate::hash::path_compression::compress_merkle_proofs;
     use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
@@ -373,4 +498,109 @@ mod tests {
 
         verify(proof, &data.verifier_only, &data.common)
     }
+
+    #[test]
+    fn test_merkle_multiproof_circuit() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let log_n = 6;
+        let n = 1 << log_n;
+        let cap_height = 2;
+        let leaves = random_data::<F>(n, 7);
+        let tree = MerkleTree::<F, H>::new(leaves, cap_height);
+        // Include a repeated index and two siblings.
+        let mut indices = (0..8).map(|_| OsRng.gen_range(0..n)).collect::<Vec<_>>();
+        indices.extend([indices[0], 12, 13]);
+        let proofs = indices.iter().map(|&i| tree.prove(i)).collect::<Vec<_>>();
+        let compressed_proofs = compress_merkle_proofs(cap_height, &indices, &proofs);
+
+        let proofs_t = compressed_proofs
+            .iter()
+            .map(|p| MerkleProofTarget {
+                siblings: builder.add_virtual_hashes(p.siblings.len()),
+            })
+            .collect::<Vec<_>>();
+        for (p, p_t) in compressed_proofs.iter().zip(&proofs_t) {
+            for (&sibling, &sibling_t) in p.siblings.iter().zip(&p_t.siblings) {
+                pw.set_hash_target(sibling_t, sibling)?;
+            }
+        }
+
+        let cap_t = builder.add_virtual_cap(cap_height);
+        pw.set_cap_target(&cap_t, &tree.cap)?;
+
+        let data_t = indices
+            .iter()
+            .map(|_| builder.add_virtual_targets(7))
+            .collect::<Vec<_>>();
+        for (&i, d_t) in indices.iter().zip(&data_t) {
+            pw.set_target_arr(d_t, &tree.leaves[i])?;
+        }
+
+        builder.verify_merkle_multiproof_circuit::<<C as GenericConfig<D>>::InnerHasher>(
+            &data_t, &indices, log_n, &cap_t, &proofs_t,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_update_merkle_leaf_circuit() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let log_n = 8;
+        let n = 1 << log_n;
+        let mut leaves = random_data::<F>(n, 7);
+        let old_tree = MerkleTree::<F, H>::new(leaves.clone(), 0);
+        let i: usize = OsRng.gen_range(0..n);
+        let proof = old_tree.prove(i);
+        leaves[i] = random_data::<F>(1, 7).remove(0);
+        let new_tree = MerkleTree::<F, H>::new(leaves.clone(), 0);
+
+        let proof_t = MerkleProofTarget {
+            siblings: builder.add_virtual_hashes(proof.siblings.len()),
+        };
+        for (&sibling, &sibling_t) in proof.siblings.iter().zip(&proof_t.siblings) {
+            pw.set_hash_target(sibling_t, sibling)?;
+        }
+
+        let old_root_t = builder.add_virtual_hash();
+        pw.set_hash_target(old_root_t, old_tree.cap.0[0])?;
+        let new_root_t = builder.add_virtual_hash();
+        pw.set_hash_target(new_root_t, new_tree.cap.0[0])?;
+
+        let i_t = builder.add_virtual_target();
+        pw.set_target(i_t, F::from_canonical_usize(i))?;
+        let i_bits = builder.split_le(i_t, log_n);
+
+        let old_data_t = builder.add_virtual_targets(7);
+        pw.set_target_arr(&old_data_t, &old_tree.leaves[i])?;
+        let new_data_t = builder.add_virtual_targets(7);
+        pw.set_target_arr(&new_data_t, &leaves[i])?;
+
+        let root = builder.update_merkle_leaf_circuit::<<C as GenericConfig<D>>::InnerHasher>(
+            old_data_t, new_data_t, &i_bits, old_root_t, &proof_t,
+        );
+        builder.connect_hashes(root, new_root_t);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
 }
@@ -0,0 +1,422 @@
+//! Implementation of the Poseidon2 hash function, as described in
+//! <https://eprint.iacr.org/2023/323.pdf>
+
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+use core::fmt::Debug;
+
+use unroll::unroll_for_loops;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::PrimeField64;
+use crate::gates::poseidon2::Poseidon2Gate;
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, Hasher};
+
+pub const SPONGE_RATE: usize = 8;
+pub const SPONGE_CAPACITY: usize = 4;
+pub const SPONGE_WIDTH: usize = SPONGE_RATE + SPONGE_CAPACITY;
+
+// The number of full rounds and partial rounds are the ones recommended in the Poseidon2 paper
+// for a width of 12 and s-box x^7 over 64-bit fields.
+//
+// NB: Changing any of these values will require regenerating all of
+// the round constants of the implementations of `Poseidon2`.
+pub const HALF_N_FULL_ROUNDS: usize = 4;
+pub(crate) const N_FULL_ROUNDS_TOTAL: usize = 2 * HALF_N_FULL_ROUNDS;
+pub const N_PARTIAL_ROUNDS: usize = 22;
+pub const N_ROUNDS: usize = N_FULL_ROUNDS_TOTAL + N_PARTIAL_ROUNDS;
+
+pub trait Poseidon2: PrimeField64 {
+    // The internal matrix, used in partial rounds, is 1 + D, where 1 is the matrix whose entries
+    // are all ones, and D is the diagonal matrix whose diagonal is given by
+    // `INTERNAL_MATRIX_DIAG_M_1`.
+    const INTERNAL_MATRIX_DIAG_M_1: [u64; SPONGE_WIDTH];
+
+    // Round constants of the full rounds.
+    const EXTERNAL_ROUND_CONSTANTS: [[u64; SPONGE_WIDTH]; N_FULL_ROUNDS_TOTAL];
+
+    // Round constants of the partial rounds, which are only added to the first element of the
+    // state.
+    const INTERNAL_ROUND_CONSTANTS: [u64; N_PARTIAL_ROUNDS];
+
+    /// Multiplies the four elements of `x` by the matrix
+    ///
+    ///    [ 5 7 1 3 ]
+    ///    [ 4 6 1 1 ]
+    ///    [ 1 3 5 7 ]
+    ///    [ 1 1 4 6 ]
+    ///
+    /// using only additions.
+    #[inline(always)]
+    fn matmul_m4<F: FieldExtension<D, BaseField = Self>, const D: usize>(x: &mut [F]) {
+        let t0 = x[0] + x[1];
+        let t1 = x[2] + x[3];
+        let t2 = x[1].double() + t1;
+        let t3 = x[3].double() + t0;
+        let t4 = t1.double().double() + t3;
+        let t5 = t0.double().double() + t2;
+        let t6 = t3 + t5;
+        let t7 = t2 + t4;
+        x[0] = t6;
+        x[1] = t5;
+        x[2] = t7;
+        x[3] = t4;
+    }
+
+    /// Recursive version of `matmul_m4`.
+    fn matmul_m4_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        x: &mut [ExtensionTarget<D>],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        let t0 = builder.add_extension(x[0], x[1]);
+        let t1 = builder.add_extension(x[2], x[3]);
+        let t2 = builder.mul_const_add_extension(Self::TWO, x[1], t1);
+        let t3 = builder.mul_const_add_extension(Self::TWO, x[3], t0);
+        let t4 = builder.mul_const_add_extension(Self::from_canonical_u64(4), t1, t3);
+        let t5 = builder.mul_const_add_extension(Self::from_canonical_u64(4), t0, t2);
+        x[0] = builder.add_extension(t3, t5);
+        x[1] = t5;
+        x[2] = builder.add_extension(t2, t4);
+        x[3] = t4;
+    }
+
+    /// Applies the external matrix, used in full rounds, to the state. This is the matrix
+    /// `circ(2 M4, M4, M4)`, where `M4` is the matrix of `matmul_m4`.
+    #[inline(always)]
+    #[unroll_for_loops]
+    fn external_linear_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+    ) {
+        for i in 0..3 {
+            Self::matmul_m4(&mut state[4 * i..4 * i + 4]);
+        }
+
+        let mut sums = [F::ZERO; 4];
+        for i in 0..4 {
+            sums[i] = state[i] + state[4 + i] + state[8 + i];
+        }
+        for i in 0..SPONGE_WIDTH {
+            state[i] += sums[i % 4];
+        }
+    }
+
+    /// Recursive version of `external_linear_layer`.
+    fn external_linear_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        for i in 0..3 {
+            Self::matmul_m4_circuit(builder, &mut state[4 * i..4 * i + 4]);
+        }
+
+        let sums: [ExtensionTarget<D>; 4] = core::array::from_fn(|i| {
+            builder.add_many_extension([state[i], state[4 + i], state[8 + i]])
+        });
+        for i in 0..SPONGE_WIDTH {
+            state[i] = builder.add_extension(state[i], sums[i % 4]);
+        }
+    }
+
+    /// Applies the internal matrix, used in partial rounds, to the state.
+    #[inline(always)]
+    #[unroll_for_loops]
+    fn internal_linear_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+    ) {
+        let sum = state.iter().copied().sum::<F>();
+        for i in 0..12 {
+            if i < SPONGE_WIDTH {
+                let diag = F::from_canonical_u64(Self::INTERNAL_MATRIX_DIAG_M_1[i]);
+                state[i] = state[i] * diag + sum;
+            }
+        }
+    }
+
+    /// Recursive version of `internal_linear_layer`.
+    fn internal_linear_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        let sum = builder.add_many_extension(*state);
+        for i in 0..SPONGE_WIDTH {
+            let diag = Self::from_canonical_u64(Self::INTERNAL_MATRIX_DIAG_M_1[i]);
+            state[i] = builder.mul_const_add_extension(diag, state[i], sum);
+        }
+    }
+
+    /// Adds the round constants of the `round`-th full round to the state.
+    #[inline(always)]
+    #[unroll_for_loops]
+    fn external_constant_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+        round: usize,
+    ) {
+        for i in 0..12 {
+            if i < SPONGE_WIDTH {
+                state[i] += F::from_canonical_u64(Self::EXTERNAL_ROUND_CONSTANTS[round][i]);
+            }
+        }
+    }
+
+    /// Recursive version of `external_constant_layer`.
+    fn external_constant_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+        round: usize,
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        for i in 0..SPONGE_WIDTH {
+            let c = Self::from_canonical_u64(Self::EXTERNAL_ROUND_CONSTANTS[round][i]);
+            state[i] = builder.add_const_extension(state[i], c);
+        }
+    }
+
+    /// Adds the round constant of the `round`-th partial round to the state.
+    #[inline(always)]
+    fn internal_constant_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+        round: usize,
+    ) {
+        state[0] += F::from_canonical_u64(Self::INTERNAL_ROUND_CONSTANTS[round]);
+    }
+
+    /// Recursive version of `internal_constant_layer`.
+    fn internal_constant_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+        round: usize,
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        let c = Self::from_canonical_u64(Self::INTERNAL_ROUND_CONSTANTS[round]);
+        state[0] = builder.add_const_extension(state[0], c);
+    }
+
+    #[inline(always)]
+    fn sbox_monomial<F: FieldExtension<D, BaseField = Self>, const D: usize>(x: F) -> F {
+        // x |--> x^7
+        let x2 = x.square();
+        let x4 = x2.square();
+        let x3 = x * x2;
+        x3 * x4
+    }
+
+    /// Recursive version of `sbox_monomial`.
+    fn sbox_monomial_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        x: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D>
+    where
+        Self: RichField + Extendable<D>,
+    {
+        // x |--> x^7
+        builder.exp_u64_extension(x, 7)
+    }
+
+    #[inline(always)]
+    #[unroll_for_loops]
+    fn sbox_layer<F: FieldExtension<D, BaseField = Self>, const D: usize>(
+        state: &mut [F; SPONGE_WIDTH],
+    ) {
+        for i in 0..12 {
+            if i < SPONGE_WIDTH {
+                state[i] = Self::sbox_monomial(state[i]);
+            }
+        }
+    }
+
+    /// Recursive version of `sbox_layer`.
+    fn sbox_layer_circuit<const D: usize>(
+        builder: &mut CircuitBuilder<Self, D>,
+        state: &mut [ExtensionTarget<D>; SPONGE_WIDTH],
+    ) where
+        Self: RichField + Extendable<D>,
+    {
+        for i in 0..SPONGE_WIDTH {
+            state[i] = <Self as Poseidon2>::sbox_monomial_circuit(builder, state[i]);
+        }
+    }
+
+    #[inline]
+    fn full_rounds(state: &mut [Self; SPONGE_WIDTH], round_ctr: &mut usize) {
+        for _ in 0..HALF_N_FULL_ROUNDS {
+            Self::external_constant_layer(state, *round_ctr);
+            Self::sbox_layer(state);
+            Self::external_linear_layer(state);
+            *round_ctr += 1;
+        }
+    }
+
+    #[inline]
+    fn partial_rounds(state: &mut [Self; SPONGE_WIDTH]) {
+        for r in 0..N_PARTIAL_ROUNDS {
+            Self::internal_constant_layer(state, r);
+            state[0] = Self::sbox_monomial(state[0]);
+            Self::internal_linear_layer(state);
+        }
+    }
+
+    #[inline]
+    fn poseidon2(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        let mut state = input;
+        let mut round_ctr = 0;
+
+        Self::external_linear_layer(&mut state);
+        Self::full_rounds(&mut state, &mut round_ctr);
+        Self::partial_rounds(&mut state);
+        Self::full_rounds(&mut state, &mut round_ctr);
+        debug_assert_eq!(round_ctr, N_FULL_ROUNDS_TOTAL);
+
+        state
+    }
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct Poseidon2Permutation<T> {
+    state: [T; SPONGE_WIDTH],
+}
+
+impl<T: Eq> Eq for Poseidon2Permutation<T> {}
+
+impl<T> AsRef<[T]> for Poseidon2Permutation<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.state
+    }
+}
+
+trait Permuter: Sized {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH];
+}
+
+impl<F: Poseidon2> Permuter for F {
+    fn permute(input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        <F as Poseidon2>::poseidon2(input)
+    }
+}
+
+impl Permuter for Target {
+    fn permute(_input: [Self; SPONGE_WIDTH]) -> [Self; SPONGE_WIDTH] {
+        panic!("Call `permute_swapped()` instead of `permute()`");
+    }
+}
+
+impl<T: Copy + Debug + Default + Eq + Permuter + Send + Sync> PlonkyPermutation<T>
+    for Poseidon2Permutation<T>
+{
+    const RATE: usize = SPONGE_RATE;
+    const WIDTH: usize = SPONGE_WIDTH;
+
+    fn new<I: IntoIterator<Item = T>>(elts: I) -> Self {
+        let mut perm = Self {
+            state: [T::default(); SPONGE_WIDTH],
+        };
+        perm.set_from_iter(elts, 0);
+        perm
+    }
+
+    fn set_elt(&mut self, elt: T, idx: usize) {
+        self.state[idx] = elt;
+    }
+
+    fn set_from_slice(&mut self, elts: &[T], start_idx: usize) {
+        let begin = start_idx;
+        let end = start_idx + elts.len();
+        self.state[begin..end].copy_from_slice(elts);
+    }
+
+    fn set_from_iter<I: IntoIterator<Item = T>>(&mut self, elts: I, start_idx: usize) {
+        for (s, e) in self.state[start_idx..].iter_mut().zip(elts) {
+            *s = e;
+        }
+    }
+
+    fn permute(&mut self) {
+        self.state = T::permute(self.state);
+    }
+
+    fn squeeze(&self) -> &[T] {
+        &self.state[..Self::RATE]
+    }
+}
+
+/// Poseidon2 hash function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Poseidon2Hash;
+impl<F: RichField + Poseidon2> Hasher<F> for Poseidon2Hash {
+    const HASH_SIZE: usize = 4 * 8;
+    type Hash = HashOut<F>;
+    type Permutation = Poseidon2Permutation<F>;
+
+    fn hash_no_pad(input: &[F]) -> Self::Hash {
+        hash_n_to_hash_no_pad::<F, Self::Permutation>(input)
+    }
+
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
+        compress::<F, Self::Permutation>(left, right)
+    }
+}
+
+impl<F: RichField + Poseidon2> AlgebraicHasher<F> for Poseidon2Hash {
+    type AlgebraicPermutation = Poseidon2Permutation<Target>;
+
+    fn permute_swapped<const D: usize>(
+        inputs: Self::AlgebraicPermutation,
+        swap: BoolTarget,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self::AlgebraicPermutation
+    where
+        F: RichField + Extendable<D>,
+    {
+        let gate_type = Poseidon2Gate::<F, D>::new();
+        let gate = builder.add_gate(gate_type, vec![]);
+
+        let swap_wire = Poseidon2Gate::<F, D>::WIRE_SWAP;
+        let swap_wire = Target::wire(gate, swap_wire);
+        builder.connect(swap.target, swap_wire);
+
+        // Route input wires.
+        let inputs = inputs.as_ref();
+        for i in 0..SPONGE_WIDTH {
+            let in_wire = Poseidon2Gate::<F, D>::wire_input(i);
+            let in_wire = Target::wire(gate, in_wire);
+            builder.connect(inputs[i], in_wire);
+        }
+
+        // Collect output wires.
+        Self::AlgebraicPermutation::new(
+            (0..SPONGE_WIDTH).map(|i| Target::wire(gate, Poseidon2Gate::<F, D>::wire_output(i))),
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    use super::*;
+
+    pub(crate) fn check_test_vectors<F>(
+        test_vectors: Vec<([u64; SPONGE_WIDTH], [u64; SPONGE_WIDTH])>,
+    ) where
+        F: Poseidon2,
+    {
+        for (input_, expected_output_) in test_vectors.into_iter() {
+            let input = input_.map(F::from_canonical_u64);
+            let output = F::poseidon2(input);
+            for i in 0..SPONGE_WIDTH {
+                let ex_output = F::from_canonical_u64(expected_output_[i]);
+                assert_eq!(output[i], ex_output);
+            }
+        }
+    }
+}
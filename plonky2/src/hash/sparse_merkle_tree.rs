@@ -0,0 +1,379 @@
+//! Sparse Merkle trees, whose leaves are indexed by 256-bit keys.
+//!
+//! A key is a [`HashOut`], read as a little-endian integer whose limbs are the canonical values of
+//! its elements. The leaf at index `key` is either empty, with digest zero, or holds some value,
+//! with digest `hash(key || value)`. Empty subtrees hash to precomputed default digests, so only
+//! the nodes above non-empty leaves need to be stored.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+use num::{BigUint, One, Zero};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
+use crate::hash::merkle_proofs::{MerkleProof, MerkleProofTarget};
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, Hasher};
+
+/// The depth of a sparse Merkle tree, i.e. the number of bits of its keys.
+pub const SPARSE_MERKLE_TREE_DEPTH: usize = NUM_HASH_OUT_ELTS * 64;
+
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: RichField, H: Hasher<F, Hash = HashOut<F>>> {
+    /// The values of the non-empty leaves.
+    leaves: HashMap<HashOut<F>, Vec<F>>,
+
+    /// The digests of the non-empty subtrees, indexed by their height and by the common prefix of
+    /// the indices of their leaves.
+    nodes: HashMap<(usize, BigUint), HashOut<F>>,
+
+    /// The digests of the empty subtrees of each height.
+    default_hashes: Vec<HashOut<F>>,
+
+    _phantom: PhantomData<H>,
+}
+
+impl<F: RichField, H: Hasher<F, Hash = HashOut<F>>> Default for SparseMerkleTree<F, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: RichField, H: Hasher<F, Hash = HashOut<F>>> SparseMerkleTree<F, H> {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        let mut default_hashes = Vec::with_capacity(SPARSE_MERKLE_TREE_DEPTH + 1);
+        default_hashes.push(HashOut::ZERO);
+        for height in 0..SPARSE_MERKLE_TREE_DEPTH {
+            let h = default_hashes[height];
+            default_hashes.push(H::two_to_one(h, h));
+        }
+
+        Self {
+            leaves: HashMap::new(),
+            nodes: HashMap::new(),
+            default_hashes,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn root(&self) -> HashOut<F> {
+        self.node(SPARSE_MERKLE_TREE_DEPTH, &BigUint::zero())
+    }
+
+    pub fn get(&self, key: HashOut<F>) -> Option<&[F]> {
+        self.leaves.get(&key).map(|v| v.as_slice())
+    }
+
+    /// Inserts `value` at `key`, which must be empty.
+    pub fn insert(&mut self, key: HashOut<F>, value: Vec<F>) -> Result<()> {
+        ensure!(!self.leaves.contains_key(&key), "Key already present");
+        self.set_leaf(key, Some(value));
+        Ok(())
+    }
+
+    /// Replaces the value at `key`, which must not be empty, and returns the previous value.
+    pub fn update(&mut self, key: HashOut<F>, value: Vec<F>) -> Result<Vec<F>> {
+        ensure!(self.leaves.contains_key(&key), "Key not present");
+        let old_value = self.leaves[&key].clone();
+        self.set_leaf(key, Some(value));
+        Ok(old_value)
+    }
+
+    /// Empties the leaf at `key`, which must not be empty, and returns its value.
+    pub fn delete(&mut self, key: HashOut<F>) -> Result<Vec<F>> {
+        ensure!(self.leaves.contains_key(&key), "Key not present");
+        let old_value = self.leaves[&key].clone();
+        self.set_leaf(key, None);
+        Ok(old_value)
+    }
+
+    /// Returns the path from the leaf at `key` to the root. This proves either the value at `key`,
+    /// or that the leaf is empty.
+    pub fn prove(&self, key: HashOut<F>) -> MerkleProof<F, H> {
+        let index = key_to_index(key);
+        let siblings = (0..SPARSE_MERKLE_TREE_DEPTH)
+            .map(|height| self.node(height, &((&index >> height) ^ BigUint::one())))
+            .collect();
+        MerkleProof { siblings }
+    }
+
+    fn node(&self, height: usize, prefix: &BigUint) -> HashOut<F> {
+        self.nodes
+            .get(&(height, prefix.clone()))
+            .copied()
+            .unwrap_or(self.default_hashes[height])
+    }
+
+    /// Sets the leaf at `key` to `value`, or empties it if `value` is `None`, and recomputes the
+    /// nodes on its path.
+    fn set_leaf(&mut self, key: HashOut<F>, value: Option<Vec<F>>) {
+        let mut digest = match value {
+            Some(value) => {
+                let digest = leaf_digest::<F, H>(key, &value);
+                self.leaves.insert(key, value);
+                digest
+            }
+            None => {
+                self.leaves.remove(&key);
+                self.default_hashes[0]
+            }
+        };
+
+        let mut index = key_to_index(key);
+        for height in 0..=SPARSE_MERKLE_TREE_DEPTH {
+            if digest == self.default_hashes[height] {
+                self.nodes.remove(&(height, index.clone()));
+            } else {
+                self.nodes.insert((height, index.clone()), digest);
+            }
+            if height == SPARSE_MERKLE_TREE_DEPTH {
+                break;
+            }
+
+            let sibling = self.node(height, &(&index ^ BigUint::one()));
+            digest = if index.bit(0) {
+                H::two_to_one(sibling, digest)
+            } else {
+                H::two_to_one(digest, sibling)
+            };
+            index >>= 1;
+        }
+    }
+}
+
+fn key_to_index<F: RichField>(key: HashOut<F>) -> BigUint {
+    let limbs = key
+        .elements
+        .iter()
+        .flat_map(|x| {
+            let x = x.to_canonical_u64();
+            [x as u32, (x >> 32) as u32]
+        })
+        .collect();
+    BigUint::new(limbs)
+}
+
+fn leaf_digest<F: RichField, H: Hasher<F, Hash = HashOut<F>>>(
+    key: HashOut<F>,
+    value: &[F],
+) -> HashOut<F> {
+    let mut inputs = key.elements.to_vec();
+    inputs.extend_from_slice(value);
+    H::hash_no_pad(&inputs)
+}
+
+/// Verifies that `value` is present at `key` in the sparse Merkle tree with the given root.
+pub fn verify_sparse_merkle_membership<F: RichField, H: Hasher<F, Hash = HashOut<F>>>(
+    key: HashOut<F>,
+    value: &[F],
+    root: HashOut<F>,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    let digest = leaf_digest::<F, H>(key, value);
+    verify_sparse_merkle_path(key, digest, root, proof)
+}
+
+/// Verifies that the leaf at `key` is empty in the sparse Merkle tree with the given root.
+pub fn verify_sparse_merkle_non_membership<F: RichField, H: Hasher<F, Hash = HashOut<F>>>(
+    key: HashOut<F>,
+    root: HashOut<F>,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    verify_sparse_merkle_path(key, HashOut::ZERO, root, proof)
+}
+
+fn verify_sparse_merkle_path<F: RichField, H: Hasher<F, Hash = HashOut<F>>>(
+    key: HashOut<F>,
+    leaf_digest: HashOut<F>,
+    root: HashOut<F>,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    ensure!(
+        proof.len() == SPARSE_MERKLE_TREE_DEPTH,
+        "Invalid sparse Merkle proof length."
+    );
+
+    let index = key_to_index(key);
+    let mut current_digest = leaf_digest;
+    for (height, &sibling_digest) in proof.siblings.iter().enumerate() {
+        current_digest = if index.bit(height as u64) {
+            H::two_to_one(sibling_digest, current_digest)
+        } else {
+            H::two_to_one(current_digest, sibling_digest)
+        };
+    }
+    ensure!(current_digest == root, "Invalid sparse Merkle proof.");
+
+    Ok(())
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Verifies that `value` is present at `key` in the sparse Merkle tree with the given root.
+    pub fn verify_sparse_merkle_membership<H: AlgebraicHasher<F>>(
+        &mut self,
+        key: HashOutTarget,
+        value: Vec<Target>,
+        root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        let mut inputs = key.elements.to_vec();
+        inputs.extend(value);
+        let digest = self.hash_n_to_hash_no_pad::<H>(inputs);
+        self.verify_sparse_merkle_path::<H>(key, digest, root, proof);
+    }
+
+    /// Verifies that the leaf at `key` is empty in the sparse Merkle tree with the given root.
+    pub fn verify_sparse_merkle_non_membership<H: AlgebraicHasher<F>>(
+        &mut self,
+        key: HashOutTarget,
+        root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        let digest = self.constant_hash(HashOut::ZERO);
+        self.verify_sparse_merkle_path::<H>(key, digest, root, proof);
+    }
+
+    fn verify_sparse_merkle_path<H: AlgebraicHasher<F>>(
+        &mut self,
+        key: HashOutTarget,
+        leaf_digest: HashOutTarget,
+        root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        assert_eq!(proof.siblings.len(), SPARSE_MERKLE_TREE_DEPTH);
+
+        // The decomposition must be canonical, or a key could be proven at two different indices.
+        let index_bits = key
+            .elements
+            .iter()
+            .flat_map(|&x| self.split_le_canonical(x))
+            .collect::<Vec<_>>();
+
+        let mut state = leaf_digest;
+        for (&bit, &sibling) in index_bits.iter().zip(&proof.siblings) {
+            state = self.hash_merkle_siblings::<H>(state, sibling, bit);
+        }
+        self.connect_hashes(state, root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    #[test]
+    fn test_sparse_merkle_tree() -> Result<()> {
+        let keys = (0..10).map(|_| HashOut::<F>::rand()).collect::<Vec<_>>();
+        let values = (0..10).map(|_| F::rand_vec(3)).collect::<Vec<_>>();
+
+        let mut tree = SparseMerkleTree::<F, H>::new();
+        let empty_root = tree.root();
+        for (&key, value) in keys.iter().zip(&values) {
+            tree.insert(key, value.clone())?;
+        }
+        assert!(tree.insert(keys[0], values[0].clone()).is_err());
+
+        // The root does not depend on the order of insertions.
+        let mut other_tree = SparseMerkleTree::<F, H>::new();
+        for (&key, value) in keys.iter().zip(&values).rev() {
+            other_tree.insert(key, value.clone())?;
+        }
+        assert_eq!(tree.root(), other_tree.root());
+
+        for (&key, value) in keys.iter().zip(&values) {
+            verify_sparse_merkle_membership(key, value, tree.root(), &tree.prove(key))?;
+            assert!(
+                verify_sparse_merkle_non_membership(key, tree.root(), &tree.prove(key)).is_err()
+            );
+        }
+        let absent_key = HashOut::rand();
+        verify_sparse_merkle_non_membership(absent_key, tree.root(), &tree.prove(absent_key))?;
+
+        let new_value = F::rand_vec(3);
+        assert_eq!(tree.update(keys[1], new_value.clone())?, values[1]);
+        assert_eq!(tree.get(keys[1]), Some(new_value.as_slice()));
+        verify_sparse_merkle_membership(keys[1], &new_value, tree.root(), &tree.prove(keys[1]))?;
+        assert!(tree.update(absent_key, new_value).is_err());
+
+        for &key in &keys {
+            tree.delete(key)?;
+        }
+        assert!(tree.delete(keys[0]).is_err());
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.nodes.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sparse_merkle_tree_circuit() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, H>::new();
+        let key = HashOut::rand();
+        let value = F::rand_vec(3);
+        tree.insert(key, value.clone())?;
+        tree.insert(HashOut::rand(), F::rand_vec(3))?;
+        let absent_key = HashOut::rand();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let root_t = builder.add_virtual_hash();
+        pw.set_hash_target(root_t, tree.root())?;
+
+        let key_t = builder.add_virtual_hash();
+        pw.set_hash_target(key_t, key)?;
+        let value_t = builder.add_virtual_targets(value.len());
+        pw.set_target_arr(&value_t, &value)?;
+        let proof_t = MerkleProofTarget {
+            siblings: builder.add_virtual_hashes(SPARSE_MERKLE_TREE_DEPTH),
+        };
+        for (&sibling_t, &sibling) in proof_t.siblings.iter().zip(&tree.prove(key).siblings) {
+            pw.set_hash_target(sibling_t, sibling)?;
+        }
+        builder.verify_sparse_merkle_membership::<<C as GenericConfig<D>>::InnerHasher>(
+            key_t, value_t, root_t, &proof_t,
+        );
+
+        let absent_key_t = builder.add_virtual_hash();
+        pw.set_hash_target(absent_key_t, absent_key)?;
+        let absent_proof_t = MerkleProofTarget {
+            siblings: builder.add_virtual_hashes(SPARSE_MERKLE_TREE_DEPTH),
+        };
+        for (&sibling_t, &sibling) in absent_proof_t
+            .siblings
+            .iter()
+            .zip(&tree.prove(absent_key).siblings)
+        {
+            pw.set_hash_target(sibling_t, sibling)?;
+        }
+        builder.verify_sparse_merkle_non_membership::<<C as GenericConfig<D>>::InnerHasher>(
+            absent_key_t,
+            root_t,
+            &absent_proof_t,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -216,6 +216,13 @@ pub trait SimpleGenerator<F: RichField + Extendable<D>, const D: usize>:
         out_buffer: &mut GeneratedValues<F>,
     ) -> Result<()>;
 
+    /// Runs this generator, returning whether it is finished. By default, this waits for all
+    /// dependencies to be present and then calls `run_once`. Generators which can make progress
+    /// with only some of their dependencies present may override it.
+    fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool {
+        witness.contains_all(&self.dependencies()) && self.run_once(witness, out_buffer).is_ok()
+    }
+
     fn adapter(self) -> SimpleGeneratorAdapter<F, Self, D>
     where
         Self: Sized,
@@ -255,11 +262,7 @@ impl<F: RichField + Extendable<D>, SG: SimpleGenerator<F, D>, const D: usize> Wi
     }
 
     fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool {
-        if witness.contains_all(&self.inner.dependencies()) {
-            self.inner.run_once(witness, out_buffer).is_ok()
-        } else {
-            false
-        }
+        self.inner.run(witness, out_buffer)
     }
 
     fn serialize(&self, dst: &mut Vec<u8>, common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
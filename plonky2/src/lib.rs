@@ -12,6 +12,7 @@ pub extern crate alloc;
 pub use plonky2_field as field;
 
 pub mod batch_fri;
+pub mod curve;
 pub mod fri;
 pub mod gadgets;
 pub mod gates;
@@ -19,6 +20,7 @@ pub mod hash;
 pub mod iop;
 pub mod plonk;
 pub mod recursion;
+pub mod sumcheck;
 pub mod util;
 
 #[cfg(test)]
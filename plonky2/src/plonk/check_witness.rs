@@ -0,0 +1,482 @@
+//! Row-level checking of a witness against the constraints of a circuit, to locate the gates,
+//! copy constraints and lookups at fault when a witness does not satisfy a circuit.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use hashbrown::{HashMap, HashSet};
+use plonky2_maybe_rayon::*;
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::generate_partial_witness;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::iop::witness::{PartialWitness, Witness};
+use crate::plonk::circuit_data::{CommonCircuitData, ProverOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, Hasher};
+use crate::plonk::vars::EvaluationVars;
+
+/// The stacks of builder contexts which were open when each gate of a circuit was added, as
+/// recorded by [`CircuitBuilder::gate_contexts`](crate::plonk::circuit_builder::CircuitBuilder::gate_contexts).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GateContexts {
+    /// The distinct stacks of builder contexts in which gates were added.
+    contexts: Vec<String>,
+    /// For each recorded row, the index in `contexts` of the stack of its gate.
+    context_indices: Vec<usize>,
+}
+
+impl GateContexts {
+    pub(crate) const fn new(contexts: Vec<String>, context_indices: Vec<usize>) -> Self {
+        Self {
+            contexts,
+            context_indices,
+        }
+    }
+
+    /// The stack of builder contexts which were open when the gate of the given row was added.
+    /// Gates added after the contexts were recorded, e.g. while building the circuit, belong to
+    /// the root context.
+    pub fn context(&self, row: usize) -> &str {
+        self.context_indices
+            .get(row)
+            .map_or("root", |&index| &self.contexts[index])
+    }
+}
+
+/// A failure found while checking a witness against a circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WitnessFailure<F: RichField + Extendable<D>, const D: usize> {
+    /// A gate constraint does not vanish.
+    Constraint {
+        /// The row of the gate.
+        row: usize,
+        /// The [`id`](crate::gates::gate::Gate::id) of the gate.
+        gate: String,
+        /// The index of the constraint, in the output of
+        /// [`Gate::eval_unfiltered`](crate::gates::gate::Gate::eval_unfiltered).
+        constraint_index: usize,
+        /// The value of the constraint on this row.
+        value: F::Extension,
+        /// The stack of builder contexts which were open when the gate was added.
+        context: String,
+    },
+    /// Two routed wires which are copies of each other, according to the wire permutation,
+    /// hold different values.
+    CopyConstraint {
+        /// The first wire.
+        wire: Wire,
+        /// The next wire in the permutation cycle of `wire`.
+        copy: Wire,
+        /// The value of `wire`.
+        value: F,
+        /// The value of `copy`.
+        copy_value: F,
+    },
+    /// A looked up pair of values does not belong to its lookup table.
+    Lookup {
+        /// The index of the lookup table.
+        lut: usize,
+        /// The input target of the lookup.
+        input: Target,
+        /// The value of the input.
+        input_value: F,
+        /// The value of the output.
+        output_value: F,
+    },
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Display for WitnessFailure<F, D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Constraint {
+                row,
+                gate,
+                constraint_index,
+                value,
+                context,
+            } => write!(
+                f,
+                "constraint {constraint_index} of gate {gate} fails at row {row} with value \
+                 {value} (context: {context})",
+            ),
+            Self::CopyConstraint {
+                wire,
+                copy,
+                value,
+                copy_value,
+            } => write!(
+                f,
+                "copy constraint between {wire:?} and {copy:?} fails with values {value} and \
+                 {copy_value}",
+            ),
+            Self::Lookup {
+                lut,
+                input,
+                input_value,
+                output_value,
+            } => write!(
+                f,
+                "lookup of {input:?} in table {lut} fails: ({input_value}, {output_value}) is \
+                 not in the table",
+            ),
+        }
+    }
+}
+
+/// Generates the witness of a circuit from the provided inputs, and checks it against the gate
+/// constraints, copy constraints and lookups of the circuit. Returns all the failures found.
+///
+/// The gate of each row is recovered from the selector polynomials, and its constants from the
+/// constant polynomials, as committed to in `prover_data`.
+pub(crate) fn check_witness<F, C, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    contexts: &GateContexts,
+) -> Result<Vec<WitnessFailure<F, D>>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let constants_polys = &prover_data.constants_sigmas_commitment.polynomials;
+    ensure!(
+        constants_polys.len() >= common_data.num_constants,
+        "The constant polynomials of the circuit are not available"
+    );
+
+    let partition_witness = generate_partial_witness(inputs, prover_data, common_data)
+        .context("Failed to generate the witness")?;
+
+    let public_inputs = prover_data
+        .public_inputs
+        .iter()
+        .map(|&t| {
+            partition_witness
+                .try_get_target(t)
+                .ok_or_else(|| anyhow!("Public input {:?} was not set", t))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let public_inputs_hash = C::InnerHasher::hash_no_pad(&public_inputs);
+
+    let lookup_failures = check_lookups(prover_data, common_data, |t| {
+        partition_witness.get_target(t)
+    });
+
+    let witness = partition_witness.full_witness();
+    let wire_value = |wire: Wire| witness.wire_values[wire.column][wire.row];
+
+    let degree = common_data.degree();
+    let constants_values = constants_polys[..common_data.num_constants]
+        .par_iter()
+        .map(|poly| poly.clone().fft().values)
+        .collect::<Vec<_>>();
+    let num_prefix_constants =
+        common_data.selectors_info.num_selectors() + common_data.num_lookup_selectors;
+
+    let constraint_failures = (0..degree)
+        .into_par_iter()
+        .flat_map_iter(|row| {
+            // The selector of the group containing the gate of this row holds the index of the
+            // gate, while all other selectors are unused.
+            let gate_index = common_data
+                .selectors_info
+                .groups
+                .iter()
+                .zip(&constants_values)
+                .find_map(|(group, selector)| {
+                    let index = selector[row].to_canonical_u64() as usize;
+                    group.contains(&index).then_some(index)
+                })
+                .expect("Invalid selector values");
+            let gate = &common_data.gates[gate_index].0;
+
+            let local_constants = constants_values
+                [num_prefix_constants..num_prefix_constants + gate.num_constants()]
+                .iter()
+                .map(|column| F::Extension::from_basefield(column[row]))
+                .collect::<Vec<_>>();
+            let local_wires = witness
+                .wire_values
+                .iter()
+                .map(|column| F::Extension::from_basefield(column[row]))
+                .collect::<Vec<_>>();
+            let vars = EvaluationVars {
+                local_constants: &local_constants,
+                local_wires: &local_wires,
+                public_inputs_hash: &public_inputs_hash,
+            };
+
+            gate.eval_unfiltered(vars)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, value)| value.is_nonzero())
+                .map(|(constraint_index, value)| WitnessFailure::Constraint {
+                    row,
+                    gate: gate.id(),
+                    constraint_index,
+                    value,
+                    context: contexts.context(row).into(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // Each sigma value `k_i * g^j` encodes the wire in column `i` and row `j`.
+    let wires_by_sigma = (0..common_data.config.num_routed_wires)
+        .flat_map(|column| {
+            prover_data
+                .subgroup
+                .iter()
+                .enumerate()
+                .map(move |(row, &x)| (common_data.k_is[column] * x, Wire { row, column }))
+        })
+        .collect::<HashMap<_, _>>();
+    let copy_failures = prover_data
+        .sigmas
+        .iter()
+        .enumerate()
+        .flat_map(|(row, row_sigmas)| {
+            row_sigmas
+                .iter()
+                .enumerate()
+                .map(move |(column, sigma)| (Wire { row, column }, sigma))
+        })
+        .filter_map(|(wire, sigma)| {
+            let copy = wires_by_sigma[sigma];
+            let (value, copy_value) = (wire_value(wire), wire_value(copy));
+            (value != copy_value).then_some(WitnessFailure::CopyConstraint {
+                wire,
+                copy,
+                value,
+                copy_value,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(constraint_failures
+        .into_iter()
+        .chain(copy_failures)
+        .chain(lookup_failures)
+        .collect())
+}
+
+/// Checks that all the pairs looked up in each lookup table belong to this table.
+fn check_lookups<F, C, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    target_value: impl Fn(Target) -> F,
+) -> Vec<WitnessFailure<F, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    prover_data
+        .lut_to_lookups
+        .iter()
+        .zip(&common_data.luts)
+        .enumerate()
+        .flat_map(|(lut, (lookups, table))| {
+            let table = table
+                .iter()
+                .map(|&(input, output)| {
+                    (F::from_canonical_u16(input), F::from_canonical_u16(output))
+                })
+                .collect::<HashSet<_>>();
+            lookups
+                .iter()
+                .map(|&(input, output)| (input, target_value(input), target_value(output)))
+                .filter(|&(_, input_value, output_value)| {
+                    !table.contains(&(input_value, output_value))
+                })
+                .map(
+                    |(input, input_value, output_value)| WitnessFailure::Lookup {
+                        lut,
+                        input,
+                        input_value,
+                        output_value,
+                    },
+                )
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::WitnessFailure;
+    use crate::field::extension::Extendable;
+    use crate::field::types::{Field, Sample};
+    use crate::gates::arithmetic_base::ArithmeticGate;
+    use crate::gates::gate::Gate;
+    use crate::iop::target::Target;
+    use crate::iop::wire::Wire;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::with_context;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_check_witness() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config.clone());
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = with_context!(builder, "multiply", builder.mul(x, y));
+        builder.register_public_input(z);
+        let contexts = builder.gate_contexts();
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand())?;
+        pw.set_target(y, F::rand())?;
+        assert_eq!(data.check_witness(pw.clone(), &contexts)?, vec![]);
+
+        // The last operation of the arithmetic gate is unused, so that nothing prevents its
+        // output from being set to an inconsistent value.
+        let Target::Wire(wire) = z else {
+            panic!("The product should be a gate output.");
+        };
+        let gate = ArithmeticGate::new_from_config(&config);
+        let last_op = gate.num_ops - 1;
+        pw.set_target(
+            Target::wire(wire.row, ArithmeticGate::wire_ith_output(last_op)),
+            F::ONE,
+        )?;
+
+        let failures = data.check_witness(pw, &contexts)?;
+        assert_eq!(
+            failures,
+            vec![WitnessFailure::Constraint {
+                row: wire.row,
+                gate: Gate::<F, D>::id(&gate),
+                constraint_index: last_op,
+                value: <F as Extendable<D>>::Extension::ONE,
+                context: "root > multiply".into(),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_contexts_of_build_gates() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = with_context!(builder, "multiply", builder.mul(x, x));
+        // The constant gate holding this constant is only added while building the circuit.
+        let four = with_context!(
+            builder,
+            "constant",
+            builder.constant(F::from_canonical_u8(4))
+        );
+        builder.connect(y, four);
+        let num_gates = builder.num_gates();
+        let contexts = builder.gate_contexts();
+        let data = builder.build::<C>();
+
+        assert_eq!(contexts.context(num_gates - 1), "root > multiply");
+        assert!(data
+            .common
+            .gates
+            .iter()
+            .any(|gate| gate.0.id().starts_with("ConstantGate")));
+        assert!((num_gates..data.common.degree()).all(|row| contexts.context(row) == "root"));
+    }
+
+    #[test]
+    fn test_check_witness_copy_constraints() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.add(x, y);
+        builder.register_public_input(z);
+        let contexts = builder.gate_contexts();
+        let mut data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::ONE)?;
+        pw.set_target(y, F::TWO)?;
+        assert_eq!(data.check_witness(pw.clone(), &contexts)?, vec![]);
+
+        // Swapping the sigmas of the wires of `x` and `y` in the arithmetic gate computing `z`
+        // makes each of them a copy of the other, although they hold different values.
+        let Target::Wire(z_wire) = z else {
+            panic!("The sum should be a gate output.");
+        };
+        let op = (0..ArithmeticGate::new_from_config(&data.common.config).num_ops)
+            .find(|&i| ArithmeticGate::wire_ith_output(i) == z_wire.column)
+            .unwrap();
+        let x_wire = Wire {
+            row: z_wire.row,
+            column: ArithmeticGate::wire_ith_multiplicand_0(op),
+        };
+        let y_wire = Wire {
+            row: z_wire.row,
+            column: ArithmeticGate::wire_ith_addend(op),
+        };
+        let sigmas = &mut data.prover_only.sigmas;
+        let x_sigma = sigmas[x_wire.row][x_wire.column];
+        sigmas[x_wire.row][x_wire.column] = sigmas[y_wire.row][y_wire.column];
+        sigmas[y_wire.row][y_wire.column] = x_sigma;
+
+        let failures = data.check_witness(pw, &contexts)?;
+        assert_eq!(failures.len(), 2);
+        assert!(failures.iter().all(|failure| matches!(
+            failure,
+            WitnessFailure::CopyConstraint { wire, .. } if *wire == x_wire || *wire == y_wire
+        )));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_witness_lookups() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let inputs = [0, 1, 2, 3];
+        let lut_index = builder.add_lookup_table_from_fn(|x| x * x, &inputs);
+        let x = builder.add_virtual_target();
+        let y = builder.add_lookup_from_index(x, lut_index);
+        builder.register_public_input(y);
+        let contexts = builder.gate_contexts();
+        let mut data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::TWO)?;
+        assert_eq!(data.check_witness(pw.clone(), &contexts)?, vec![]);
+
+        // Checking the lookup against another table, which does not contain `(2, 4)`.
+        data.common.luts[lut_index] = inputs
+            .iter()
+            .map(|&x| (x, x + 1))
+            .collect::<Vec<_>>()
+            .into();
+
+        let failures = data.check_witness(pw, &contexts)?;
+        assert_eq!(
+            failures,
+            vec![WitnessFailure::Lookup {
+                lut: lut_index,
+                input: x,
+                input_value: F::TWO,
+                output_value: F::from_canonical_u16(4),
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -21,6 +21,8 @@ use crate::fri::oracle::PolynomialBatch;
 use crate::fri::{FriConfig, FriParams};
 use crate::gadgets::arithmetic::BaseArithmeticOperation;
 use crate::gadgets::arithmetic_extension::ExtensionArithmeticOperation;
+use crate::gadgets::bitwise::BitwiseTable;
+use crate::gadgets::memory::MemoryOp;
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::gates::arithmetic_base::ArithmeticGate;
 use crate::gates::arithmetic_extension::ArithmeticExtensionGate;
@@ -40,6 +42,7 @@ use crate::iop::generator::{
 };
 use crate::iop::target::{BoolTarget, Target};
 use crate::iop::wire::Wire;
+use crate::plonk::check_witness::GateContexts;
 use crate::plonk::circuit_data::{
     CircuitConfig, CircuitData, CommonCircuitData, MockCircuitData, ProverCircuitData,
     ProverOnlyCircuitData, VerifierCircuitData, VerifierCircuitTarget, VerifierOnlyCircuitData,
@@ -192,6 +195,12 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     // Lookup tables in the form of `Vec<(input_value, output_value)>`.
     luts: Vec<LookupTable>,
 
+    /// Indices in `luts` of the tables used by bitwise gadgets.
+    pub(crate) bitwise_luts: HashMap<BitwiseTable, usize>,
+
+    /// Memory operations issued by `mem_read` and `mem_write`, in program order.
+    pub(crate) memory_ops: Vec<MemoryOp>,
+
     /// Optional common data. When it is `Some(goal_data)`, the `build` function panics if the resulting
     /// common data doesn't equal `goal_data`.
     /// This is used in cyclic recursion.
@@ -227,6 +236,8 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             lookup_rows: Vec::new(),
             lut_to_lookups: Vec::new(),
             luts: Vec::new(),
+            bitwise_luts: HashMap::new(),
+            memory_ops: Vec::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
         };
@@ -1027,6 +1038,18 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         )
     }
 
+    /// Records, for each gate added so far, the stack of builder contexts which were open when it
+    /// was added. This is meant to be taken right before building the circuit, to locate the
+    /// failures reported by [`CircuitData::check_witness`].
+    ///
+    /// Gates added by [`Self::build`] itself, such as constant gates, lookup table gates and
+    /// memory checks, are not recorded, and are attributed to the root context regardless of the
+    /// context in which they were requested.
+    pub fn gate_contexts(&self) -> GateContexts {
+        let (contexts, context_indices) = self.context_log.gate_stacks(self.num_gates());
+        GateContexts::new(contexts, context_indices)
+    }
+
     pub fn print_gate_counts(&self, min_delta: usize) {
         // Print gate counts for each context.
         self.context_log
@@ -1078,6 +1101,10 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         #[cfg(feature = "timing")]
         let start = Instant::now();
 
+        // Check the consistency of all memory operations. This may add gates, generators and
+        // lookups, so it must happen before anything below inspects them.
+        self.add_all_memory_checks();
+
         let rate_bits = self.config.fri_config.rate_bits;
         let cap_height = self.config.fri_config.cap_height;
         // Total number of LUTs.
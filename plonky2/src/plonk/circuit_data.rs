@@ -42,6 +42,7 @@ use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::{generate_partial_witness, WitnessGeneratorRef};
 use crate::iop::target::Target;
 use crate::iop::witness::{PartialWitness, PartitionWitness};
+use crate::plonk::check_witness::{check_witness, GateContexts, WitnessFailure};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
@@ -195,6 +196,21 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         )
     }
 
+    /// Generates the witness from the provided inputs, and checks it against the gate
+    /// constraints, copy constraints and lookups of the circuit, without generating a proof.
+    /// Returns all the failures found, with gate constraint failures ordered by row.
+    ///
+    /// Failing gates are located with the builder contexts recorded in `contexts`, which can be
+    /// obtained with [`CircuitBuilder::gate_contexts`] right before building the circuit.
+    /// An empty output means that the witness satisfies the circuit.
+    pub fn check_witness(
+        &self,
+        inputs: PartialWitness<F>,
+        contexts: &GateContexts,
+    ) -> Result<Vec<WitnessFailure<F, D>>> {
+        check_witness::<F, C, D>(&self.prover_only, &self.common, inputs, contexts)
+    }
+
     pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
         verify::<F, C, D>(proof_with_pis, &self.verifier_only, &self.common)
     }
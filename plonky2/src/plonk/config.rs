@@ -2,9 +2,9 @@
 //!
 //! This module defines a [`Hasher`] trait as well as its recursive
 //! counterpart [`AlgebraicHasher`] for in-circuit hashing. It also
-//! provides concrete configurations, one fully recursive leveraging
-//! the Poseidon hash function both internally and natively, and one
-//! mixing Poseidon internally and truncated Keccak externally.
+//! provides concrete configurations, two fully recursive leveraging
+//! the Poseidon and Poseidon2 hash functions both internally and natively,
+//! and one mixing Poseidon internally and truncated Keccak externally.
 
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
@@ -20,6 +20,7 @@ use crate::hash::hash_types::{HashOut, RichField};
 use crate::hash::hashing::PlonkyPermutation;
 use crate::hash::keccak::KeccakHash;
 use crate::hash::poseidon::PoseidonHash;
+use crate::hash::poseidon2::Poseidon2Hash;
 use crate::iop::target::{BoolTarget, Target};
 use crate::plonk::circuit_builder::CircuitBuilder;
 
@@ -115,6 +116,16 @@ impl GenericConfig<2> for PoseidonGoldilocksConfig {
     type InnerHasher = PoseidonHash;
 }
 
+/// Configuration using Poseidon2 over the Goldilocks field.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize)]
+pub struct Poseidon2GoldilocksConfig;
+impl GenericConfig<2> for Poseidon2GoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = QuadraticExtension<Self::F>;
+    type Hasher = Poseidon2Hash;
+    type InnerHasher = Poseidon2Hash;
+}
+
 /// Configuration using truncated Keccak over the Goldilocks field.
 #[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
 pub struct KeccakGoldilocksConfig;
@@ -3,6 +3,7 @@
 //! This module also defines the [CircuitBuilder](circuit_builder::CircuitBuilder)
 //! structure, used to build custom plonky2 circuits satisfying arbitrary statements.
 
+pub mod check_witness;
 pub mod circuit_builder;
 pub mod circuit_data;
 pub mod config;
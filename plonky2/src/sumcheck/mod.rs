@@ -0,0 +1,18 @@
+//! The sumcheck protocol, for sums of polynomial combinations of multilinear polynomials over the
+//! boolean hypercube.
+//!
+//! The prover convinces the verifier that
+//! `sum_{x in {0, 1}^n} g(p_1(x), ..., p_k(x)) = claimed_sum`, where the `p_i` are multilinear
+//! polynomials over the extension field and `g` is a polynomial of known degree. The protocol
+//! reduces this claim to a claim about the evaluations of the `p_i` at a random point, which the
+//! prover includes in the proof. Checking these evaluations, e.g. with a polynomial commitment
+//! scheme or another round of a GKR-style protocol, is left to the caller.
+//!
+//! It provides both a native implementation and an in-circuit version of the verifier for
+//! recursive proof composition. Challenges are derived from a Fiat-Shamir transcript, which the
+//! caller may have used before.
+
+pub mod proof;
+pub mod prover;
+pub mod recursive_verifier;
+pub mod verifier;
@@ -0,0 +1,53 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::witness::WitnessWrite;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct SumcheckProof<F: RichField + Extendable<D>, const D: usize> {
+    /// For each round, the evaluations of the round polynomial at `0, 1, ..., degree`.
+    pub round_polys: Vec<Vec<F::Extension>>,
+    /// The evaluations of the multilinear polynomials at the point the sum was reduced to.
+    pub final_evals: Vec<F::Extension>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SumcheckProofTarget<const D: usize> {
+    pub round_polys: Vec<Vec<ExtensionTarget<D>>>,
+    pub final_evals: Vec<ExtensionTarget<D>>,
+}
+
+/// Set the targets in a `SumcheckProofTarget` to their corresponding values in a `SumcheckProof`.
+pub fn set_sumcheck_proof_target<F, W, const D: usize>(
+    witness: &mut W,
+    proof_target: &SumcheckProofTarget<D>,
+    proof: &SumcheckProof<F, D>,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    W: WitnessWrite<F> + ?Sized,
+{
+    ensure!(
+        proof_target.round_polys.len() == proof.round_polys.len(),
+        "Number of sumcheck rounds mismatch"
+    );
+    for (round_poly_target, round_poly) in proof_target.round_polys.iter().zip(&proof.round_polys) {
+        ensure!(
+            round_poly_target.len() == round_poly.len(),
+            "Sumcheck round polynomial degree mismatch"
+        );
+        witness.set_extension_targets(round_poly_target, round_poly)?;
+    }
+    ensure!(
+        proof_target.final_evals.len() == proof.final_evals.len(),
+        "Number of sumcheck final evaluations mismatch"
+    );
+    witness.set_extension_targets(&proof_target.final_evals, &proof.final_evals)
+}
@@ -0,0 +1,86 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::field::extension::Extendable;
+use crate::field::polynomial::multilinear::MultilinearPolynomial;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::Challenger;
+use crate::plonk::config::Hasher;
+use crate::sumcheck::proof::SumcheckProof;
+
+/// Proves that the sum of `combine(p_1(x), ..., p_k(x))` over the boolean hypercube is the sum
+/// given to the verifier, where the `p_i` are `polys`, which must all have the same number of
+/// variables, and `combine` is a polynomial of degree at most `degree`.
+///
+/// Returns the proof along with the random point the sum was reduced to. The proof contains the
+/// evaluations of the `p_i` at that point, which the caller should then prove by other means.
+pub fn prove_sumcheck<F, H, const D: usize>(
+    mut polys: Vec<MultilinearPolynomial<F::Extension>>,
+    degree: usize,
+    combine: impl Fn(&[F::Extension]) -> F::Extension,
+    challenger: &mut Challenger<F, H>,
+) -> (SumcheckProof<F, D>, Vec<F::Extension>)
+where
+    F: RichField + Extendable<D>,
+    H: Hasher<F>,
+{
+    assert!(degree >= 1, "Sumcheck degree must be at least 1");
+    let num_vars = polys[0].num_vars();
+    assert!(
+        polys.iter().all(|p| p.num_vars() == num_vars),
+        "All polynomials must have the same number of variables"
+    );
+
+    let mut round_polys = Vec::with_capacity(num_vars);
+    let mut point = Vec::with_capacity(num_vars);
+    for _ in 0..num_vars {
+        let round_poly = round_poly(&polys, degree, &combine);
+        challenger.observe_extension_elements(&round_poly);
+        round_polys.push(round_poly);
+
+        let r = challenger.get_extension_challenge::<D>();
+        polys
+            .iter_mut()
+            .for_each(|p| p.fix_first_variable_in_place(r));
+        point.push(r);
+    }
+
+    let final_evals = polys.iter().map(|p| p.evals[0]).collect::<Vec<_>>();
+    challenger.observe_extension_elements(&final_evals);
+
+    (
+        SumcheckProof {
+            round_polys,
+            final_evals,
+        },
+        point,
+    )
+}
+
+/// Evaluates the polynomial `t -> sum_x combine(p_1(t, x), ..., p_k(t, x))` at `0, 1, ..., degree`,
+/// where `x` ranges over the boolean hypercube of the remaining variables.
+fn round_poly<F: Field>(
+    polys: &[MultilinearPolynomial<F>],
+    degree: usize,
+    combine: &impl Fn(&[F]) -> F,
+) -> Vec<F> {
+    let half = polys[0].evals.len() / 2;
+    let mut sums = vec![F::ZERO; degree + 1];
+    let mut values = vec![F::ZERO; polys.len()];
+    let mut diffs = vec![F::ZERO; polys.len()];
+    for i in 0..half {
+        // Each `p_j(t, x)` is linear in `t`, so we can go from one value of `t` to the next by
+        // adding `p_j(1, x) - p_j(0, x)`.
+        for (j, p) in polys.iter().enumerate() {
+            values[j] = p.evals[i];
+            diffs[j] = p.evals[i + half] - p.evals[i];
+        }
+        sums[0] += combine(&values);
+        for sum in &mut sums[1..] {
+            values.iter_mut().zip(&diffs).for_each(|(v, &d)| *v += d);
+            *sum += combine(&values);
+        }
+    }
+    sums
+}
@@ -0,0 +1,190 @@
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use crate::field::extension::Extendable;
+use crate::field::interpolation::barycentric_weights;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::RecursiveChallenger;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::AlgebraicHasher;
+use crate::sumcheck::proof::SumcheckProofTarget;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds targets for a sumcheck proof over `num_vars` variables, for a combination of
+    /// `num_polys` multilinear polynomials of degree at most `degree`.
+    pub fn add_virtual_sumcheck_proof(
+        &mut self,
+        num_vars: usize,
+        degree: usize,
+        num_polys: usize,
+    ) -> SumcheckProofTarget<D> {
+        assert!(degree >= 1, "Sumcheck degree must be at least 1");
+        SumcheckProofTarget {
+            round_polys: (0..num_vars)
+                .map(|_| self.add_virtual_extension_targets(degree + 1))
+                .collect(),
+            final_evals: self.add_virtual_extension_targets(num_polys),
+        }
+    }
+
+    /// In-circuit version of `verify_sumcheck`. The number of variables and the degree of
+    /// `combine` are those `proof` was created with.
+    ///
+    /// Returns the random point the sum was reduced to. The caller must check that each `p_i`
+    /// evaluates to `proof.final_evals[i]` at this point.
+    pub fn verify_sumcheck_circuit<H: AlgebraicHasher<F>>(
+        &mut self,
+        claimed_sum: ExtensionTarget<D>,
+        combine: impl Fn(&mut Self, &[ExtensionTarget<D>]) -> ExtensionTarget<D>,
+        proof: &SumcheckProofTarget<D>,
+        challenger: &mut RecursiveChallenger<F, H, D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut claim = claimed_sum;
+        let mut point = Vec::with_capacity(proof.round_polys.len());
+        for round_poly in &proof.round_polys {
+            assert!(
+                round_poly.len() >= 2,
+                "Sumcheck round polynomials must have degree at least 1"
+            );
+            let sum = self.add_extension(round_poly[0], round_poly[1]);
+            self.connect_extension(sum, claim);
+            challenger.observe_extension_elements(round_poly);
+
+            let r = challenger.get_extension_challenge(self);
+            claim = self.eval_round_poly(round_poly, r);
+            point.push(r);
+        }
+
+        let final_eval = combine(self, &proof.final_evals);
+        self.connect_extension(final_eval, claim);
+        challenger.observe_extension_elements(&proof.final_evals);
+
+        point
+    }
+
+    /// Evaluates at `x` the polynomial taking the given values at `0, 1, ..., values.len() - 1`,
+    /// using the Lagrange formula `sum_i w_i values[i] prod_{j != i} (x - j)`. The barycentric
+    /// weights `w_i` only depend on the number of values, so they are constants.
+    fn eval_round_poly(
+        &mut self,
+        values: &[ExtensionTarget<D>],
+        x: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D> {
+        let n = values.len();
+        let weights = barycentric_weights(
+            &(0..n)
+                .map(|i| (F::from_canonical_usize(i), F::ZERO))
+                .collect::<Vec<_>>(),
+        );
+        let diffs = (0..n)
+            .map(|j| {
+                let j = self.constant_extension(F::Extension::from_canonical_usize(j));
+                self.sub_extension(x, j)
+            })
+            .collect::<Vec<_>>();
+
+        // `prefix_products[i]` is the product of the first `i` differences, and
+        // `suffix_products[i]` that of the last `n - i` ones.
+        let one = self.one_extension();
+        let mut prefix_products = vec![one];
+        for &d in &diffs[..n - 1] {
+            let product = self.mul_extension(*prefix_products.last().unwrap(), d);
+            prefix_products.push(product);
+        }
+        let mut suffix_products = vec![one];
+        for &d in diffs[1..].iter().rev() {
+            let product = self.mul_extension(*suffix_products.last().unwrap(), d);
+            suffix_products.push(product);
+        }
+        suffix_products.reverse();
+
+        let mut result = self.zero_extension();
+        for i in 0..n {
+            let basis = self.mul_extension(prefix_products[i], suffix_products[i]);
+            result = self.arithmetic_extension(weights[i], F::ONE, basis, values[i], result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    use anyhow::Result;
+
+    use crate::field::polynomial::multilinear::MultilinearPolynomial;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::challenger::{Challenger, RecursiveChallenger};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::sumcheck::proof::set_sumcheck_proof_target;
+    use crate::sumcheck::prover::prove_sumcheck;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type FE = <C as GenericConfig<D>>::FE;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    #[test]
+    fn test_recursive_sumcheck() -> Result<()> {
+        // Prove the sum of `a(x) b(x) + c(x)`.
+        let num_vars = 5;
+        let polys = (0..3)
+            .map(|_| MultilinearPolynomial::new(FE::rand_vec(1 << num_vars)))
+            .collect::<Vec<_>>();
+        let claimed_sum = (0..1 << num_vars)
+            .map(|i| polys[0].evals[i] * polys[1].evals[i] + polys[2].evals[i])
+            .sum::<FE>();
+        let (proof, point) = prove_sumcheck::<F, H, D>(
+            polys.clone(),
+            2,
+            |v| v[0] * v[1] + v[2],
+            &mut Challenger::new(),
+        );
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let claimed_sum_target = builder.add_virtual_extension_target();
+        let proof_target = builder.add_virtual_sumcheck_proof(num_vars, 2, 3);
+        let mut challenger = RecursiveChallenger::<F, H, D>::new(&mut builder);
+        let point_target = builder.verify_sumcheck_circuit(
+            claimed_sum_target,
+            |builder, v| builder.mul_add_extension(v[0], v[1], v[2]),
+            &proof_target,
+            &mut challenger,
+        );
+        builder.register_public_inputs(&point_target.iter().flat_map(|t| t.0).collect::<Vec<_>>());
+
+        let data = builder.build::<C>();
+        let mut pw = PartialWitness::new();
+        pw.set_extension_target(claimed_sum_target, claimed_sum)?;
+        set_sumcheck_proof_target(&mut pw, &proof_target, &proof)?;
+        let proof_with_pis = data.prove(pw)?;
+        let expected_point = point.iter().flat_map(|p| p.0).collect::<Vec<_>>();
+        assert_eq!(proof_with_pis.public_inputs, expected_point);
+        data.verify(proof_with_pis)?;
+
+        // A wrong sum cannot be proven.
+        let mut pw = PartialWitness::new();
+        pw.set_extension_target(claimed_sum_target, claimed_sum + FE::ONE)?;
+        set_sumcheck_proof_target(&mut pw, &proof_target, &proof)?;
+        assert!(data.prove(pw).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Sumcheck degree must be at least 1")]
+    fn test_recursive_sumcheck_zero_degree() {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        builder.add_virtual_sumcheck_proof(3, 0, 1);
+    }
+}
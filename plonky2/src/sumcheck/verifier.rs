@@ -0,0 +1,171 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::field::interpolation::{barycentric_weights, interpolate};
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::Challenger;
+use crate::plonk::config::Hasher;
+use crate::sumcheck::proof::SumcheckProof;
+
+/// Verifies a sumcheck proof that the sum of `combine(p_1(x), ..., p_k(x))` over the boolean
+/// hypercube with `num_vars` variables is `claimed_sum`, where `combine` has degree at most
+/// `degree` and `k = num_polys`.
+///
+/// Returns the random point the sum was reduced to. The caller must check that each `p_i`
+/// evaluates to `proof.final_evals[i]` at this point.
+pub fn verify_sumcheck<F, H, const D: usize>(
+    claimed_sum: F::Extension,
+    num_vars: usize,
+    degree: usize,
+    num_polys: usize,
+    combine: impl Fn(&[F::Extension]) -> F::Extension,
+    proof: &SumcheckProof<F, D>,
+    challenger: &mut Challenger<F, H>,
+) -> Result<Vec<F::Extension>>
+where
+    F: RichField + Extendable<D>,
+    H: Hasher<F>,
+{
+    // Round polynomials are given by their values at `0, 1, ..., degree`, and the verifier needs
+    // those at `0` and `1`.
+    ensure!(degree >= 1, "Sumcheck degree must be at least 1");
+    ensure!(
+        proof.round_polys.len() == num_vars,
+        "Wrong number of sumcheck rounds"
+    );
+    ensure!(
+        proof.final_evals.len() == num_polys,
+        "Wrong number of sumcheck final evaluations"
+    );
+
+    let mut claim = claimed_sum;
+    let mut point = Vec::with_capacity(num_vars);
+    for round_poly in &proof.round_polys {
+        ensure!(
+            round_poly.len() == degree + 1,
+            "Wrong degree of sumcheck round polynomial"
+        );
+        ensure!(
+            round_poly[0] + round_poly[1] == claim,
+            "Sumcheck round polynomial inconsistent with the claimed sum"
+        );
+        challenger.observe_extension_elements(round_poly);
+
+        let r = challenger.get_extension_challenge::<D>();
+        claim = eval_round_poly(round_poly, r);
+        point.push(r);
+    }
+
+    ensure!(
+        combine(&proof.final_evals) == claim,
+        "Sumcheck final evaluations inconsistent with the reduced claim"
+    );
+    challenger.observe_extension_elements(&proof.final_evals);
+
+    Ok(point)
+}
+
+/// Evaluates at `x` the polynomial taking the given values at `0, 1, ..., values.len() - 1`.
+fn eval_round_poly<F: Field>(values: &[F], x: F) -> F {
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (F::from_canonical_usize(i), v))
+        .collect::<Vec<_>>();
+    interpolate(&points, x, &barycentric_weights(&points))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
+
+    use super::*;
+    use crate::field::polynomial::multilinear::MultilinearPolynomial;
+    use crate::field::types::Sample;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::sumcheck::prover::prove_sumcheck;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type FE = <C as GenericConfig<D>>::FE;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    #[test]
+    fn test_sumcheck() -> Result<()> {
+        // Prove the sum of `eq(r, x) a(x) b(x)`, i.e. the evaluation of the multilinear extension
+        // of `a(x) b(x)` at `r`.
+        let num_vars = 6;
+        let r = FE::rand_vec(num_vars);
+        let a = MultilinearPolynomial::new(FE::rand_vec(1 << num_vars));
+        let b = MultilinearPolynomial::new(FE::rand_vec(1 << num_vars));
+        let eq = MultilinearPolynomial::eq(&r);
+        let combine = |v: &[FE]| v[0] * v[1] * v[2];
+        let claimed_sum = (0..1 << num_vars)
+            .map(|i| eq.evals[i] * a.evals[i] * b.evals[i])
+            .sum::<FE>();
+
+        let polys = vec![eq.clone(), a.clone(), b.clone()];
+        let (proof, prover_point) =
+            prove_sumcheck::<F, H, D>(polys, 3, combine, &mut Challenger::new());
+        let point = verify_sumcheck::<F, H, D>(
+            claimed_sum,
+            num_vars,
+            3,
+            3,
+            combine,
+            &proof,
+            &mut Challenger::new(),
+        )?;
+        assert_eq!(point, prover_point);
+        assert_eq!(
+            proof.final_evals,
+            [eq.evaluate(&point), a.evaluate(&point), b.evaluate(&point)]
+        );
+
+        // A wrong sum is rejected.
+        assert!(verify_sumcheck::<F, H, D>(
+            claimed_sum + FE::ONE,
+            num_vars,
+            3,
+            3,
+            combine,
+            &proof,
+            &mut Challenger::new(),
+        )
+        .is_err());
+
+        // A zero degree is rejected rather than panicking.
+        assert!(verify_sumcheck::<F, H, D>(
+            claimed_sum,
+            num_vars,
+            0,
+            3,
+            combine,
+            &proof,
+            &mut Challenger::new(),
+        )
+        .is_err());
+
+        // A proof with a missing final evaluation is rejected.
+        let mut truncated_proof = proof.clone();
+        truncated_proof.final_evals.pop();
+        assert!(verify_sumcheck::<F, H, D>(
+            claimed_sum,
+            num_vars,
+            3,
+            3,
+            combine,
+            &truncated_proof,
+            &mut Challenger::new(),
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
@@ -54,6 +54,38 @@ impl ContextTree {
         }
     }
 
+    /// For each of the first `num_gates` gates, the stack of scopes which were open when the gate
+    /// was added. Stacks are deduplicated: this returns the list of distinct stacks, along with
+    /// the index of the stack of each gate in this list.
+    pub fn gate_stacks(&self, num_gates: usize) -> (Vec<String>, Vec<usize>) {
+        let mut stacks = Vec::new();
+        let mut gate_stacks = vec![0; num_gates];
+        self.gate_stacks_helper(&mut Vec::new(), &mut stacks, &mut gate_stacks);
+        (stacks, gate_stacks)
+    }
+
+    fn gate_stacks_helper<'a>(
+        &'a self,
+        stack: &mut Vec<&'a str>,
+        stacks: &mut Vec<String>,
+        gate_stacks: &mut [usize],
+    ) {
+        let num_gates = gate_stacks.len();
+        let end = self.exit_gate_count.unwrap_or(num_gates).min(num_gates);
+        if self.enter_gate_count >= end {
+            // No gate was added in this scope.
+            return;
+        }
+
+        stack.push(&self.name);
+        gate_stacks[self.enter_gate_count..end].fill(stacks.len());
+        stacks.push(stack.join(" > "));
+        for child in &self.children {
+            child.gate_stacks_helper(stack, stacks, gate_stacks);
+        }
+        stack.pop();
+    }
+
     pub fn push(&mut self, ctx: &str, mut level: log::Level, current_gate_count: usize) {
         assert!(self.is_open());
 
@@ -100,26 +100,41 @@ pub mod default {
     use core::marker::PhantomData;
 
     use plonky2_field::extension::Extendable;
+    use plonky2_field::secp256k1_base::Secp256K1Base;
+    use plonky2_field::secp256k1_scalar::Secp256K1Scalar;
 
     use crate::gadgets::arithmetic::EqualityGenerator;
     use crate::gadgets::arithmetic_extension::QuotientGeneratorExtension;
+    use crate::gadgets::biguint::BigUintDivRemGenerator;
+    use crate::gadgets::bitwise::LimbSplitGenerator;
+    use crate::gadgets::memory::{MemoryReadGenerator, MemorySortGenerator};
+    use crate::gadgets::nonnative::{
+        NonNativeAdditionGenerator, NonNativeInverseGenerator, NonNativeMultiplicationGenerator,
+        NonNativeSubtractionGenerator,
+    };
     use crate::gadgets::range_check::LowHighGenerator;
     use crate::gadgets::split_base::BaseSumGenerator;
     use crate::gadgets::split_join::{SplitGenerator, WireSplitGenerator};
+    use crate::gadgets::uint::UintDivRemGenerator;
     use crate::gates::arithmetic_base::ArithmeticBaseGenerator;
     use crate::gates::arithmetic_extension::ArithmeticExtensionGenerator;
     use crate::gates::base_sum::BaseSplitGenerator;
     use crate::gates::coset_interpolation::InterpolationGenerator;
     use crate::gates::exponentiation::ExponentiationGenerator;
+    use crate::gates::keccak::{KeccakChiGenerator, KeccakThetaGenerator};
     use crate::gates::lookup::LookupGenerator;
     use crate::gates::lookup_table::LookupTableGenerator;
     use crate::gates::multiplication_extension::MulExtensionGenerator;
     use crate::gates::poseidon::PoseidonGenerator;
+    use crate::gates::poseidon2::Poseidon2Generator;
     use crate::gates::poseidon_mds::PoseidonMdsGenerator;
     use crate::gates::random_access::RandomAccessGenerator;
     use crate::gates::reducing::ReducingGenerator;
     use crate::gates::reducing_extension::ReducingGenerator as ReducingExtensionGenerator;
+    use crate::gates::u32_arithmetic::U32ArithmeticGenerator;
+    use crate::gates::u32_subtraction::U32SubtractionGenerator;
     use crate::hash::hash_types::RichField;
+    use crate::hash::poseidon2::Poseidon2;
     use crate::iop::generator::{
         ConstantGenerator, CopyGenerator, NonzeroTestGenerator, RandomValueGenerator,
     };
@@ -127,6 +142,50 @@ pub mod default {
     use crate::recursion::dummy_circuit::DummyProofGenerator;
     use crate::util::serialization::WitnessGeneratorSerializer;
 
+    /// Implements [`WitnessGeneratorSerializer`] for all default generators, followed by the
+    /// provided additional generators.
+    macro_rules! impl_default_generator_serializer {
+        ($target:ty $(, $generator_types:ty)*) => {
+            impl_generator_serializer! {
+                $target,
+                ArithmeticBaseGenerator<F, D>,
+                ArithmeticExtensionGenerator<F, D>,
+                BaseSplitGenerator<2>,
+                BaseSumGenerator<2>,
+                ConstantGenerator<F>,
+                CopyGenerator,
+                DummyProofGenerator<F, C, D>,
+                EqualityGenerator,
+                ExponentiationGenerator<F, D>,
+                InterpolationGenerator<F, D>,
+                LookupGenerator,
+                LookupTableGenerator,
+                LowHighGenerator,
+                MulExtensionGenerator<F, D>,
+                NonzeroTestGenerator,
+                PoseidonGenerator<F, D>,
+                PoseidonMdsGenerator<D>,
+                QuotientGeneratorExtension<D>,
+                RandomAccessGenerator<F, D>,
+                RandomValueGenerator,
+                ReducingGenerator<D>,
+                ReducingExtensionGenerator<D>,
+                SplitGenerator,
+                WireSplitGenerator,
+                KeccakChiGenerator,
+                KeccakThetaGenerator,
+                U32ArithmeticGenerator,
+                U32SubtractionGenerator,
+                UintDivRemGenerator,
+                LimbSplitGenerator,
+                BigUintDivRemGenerator,
+                MemoryReadGenerator,
+                MemorySortGenerator
+                $(, $generator_types)*
+            }
+        };
+    }
+
     /// A generator serializer that can be used to serialize all default generators supported
     /// by the `plonky2` library. It can simply be called as
     /// ```rust
@@ -151,32 +210,51 @@ pub mod default {
         C: GenericConfig<D, F = F> + 'static,
         C::Hasher: AlgebraicHasher<F>,
     {
-        impl_generator_serializer! {
-            DefaultGeneratorSerializer,
-            ArithmeticBaseGenerator<F, D>,
-            ArithmeticExtensionGenerator<F, D>,
-            BaseSplitGenerator<2>,
-            BaseSumGenerator<2>,
-            ConstantGenerator<F>,
-            CopyGenerator,
-            DummyProofGenerator<F, C, D>,
-            EqualityGenerator,
-            ExponentiationGenerator<F, D>,
-            InterpolationGenerator<F, D>,
-            LookupGenerator,
-            LookupTableGenerator,
-            LowHighGenerator,
-            MulExtensionGenerator<F, D>,
-            NonzeroTestGenerator,
-            PoseidonGenerator<F, D>,
-            PoseidonMdsGenerator<D>,
-            QuotientGeneratorExtension<D>,
-            RandomAccessGenerator<F, D>,
-            RandomValueGenerator,
-            ReducingGenerator<D>,
-            ReducingExtensionGenerator<D>,
-            SplitGenerator,
-            WireSplitGenerator
+        impl_default_generator_serializer! { DefaultGeneratorSerializer }
+    }
+
+    /// A generator serializer supporting all default generators, along with the
+    /// [`Poseidon2Generator`], for fields implementing [`Poseidon2`].
+    #[derive(Debug, Default)]
+    pub struct Poseidon2GeneratorSerializer<C: GenericConfig<D>, const D: usize> {
+        pub _phantom: PhantomData<C>,
+    }
+
+    impl<F, C, const D: usize> WitnessGeneratorSerializer<F, D> for Poseidon2GeneratorSerializer<C, D>
+    where
+        F: RichField + Poseidon2 + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        impl_default_generator_serializer! {
+            Poseidon2GeneratorSerializer,
+            Poseidon2Generator<F, D>
+        }
+    }
+
+    /// A generator serializer supporting all default generators, along with the nonnative
+    /// arithmetic generators over the secp256k1 base and scalar fields used by ECDSA gadgets.
+    #[derive(Debug, Default)]
+    pub struct EcdsaGeneratorSerializer<C: GenericConfig<D>, const D: usize> {
+        pub _phantom: PhantomData<C>,
+    }
+
+    impl<F, C, const D: usize> WitnessGeneratorSerializer<F, D> for EcdsaGeneratorSerializer<C, D>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + 'static,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        impl_default_generator_serializer! {
+            EcdsaGeneratorSerializer,
+            NonNativeAdditionGenerator<Secp256K1Base>,
+            NonNativeAdditionGenerator<Secp256K1Scalar>,
+            NonNativeInverseGenerator<Secp256K1Base>,
+            NonNativeInverseGenerator<Secp256K1Scalar>,
+            NonNativeMultiplicationGenerator<Secp256K1Base>,
+            NonNativeMultiplicationGenerator<Secp256K1Scalar>,
+            NonNativeSubtractionGenerator<Secp256K1Base>,
+            NonNativeSubtractionGenerator<Secp256K1Scalar>
         }
     }
 }
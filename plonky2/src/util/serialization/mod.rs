@@ -13,9 +13,11 @@ use core::ops::Range;
 #[cfg(feature = "std")]
 use std::{collections::BTreeMap, sync::Arc};
 
-pub use gate_serialization::default::DefaultGateSerializer;
+pub use gate_serialization::default::{DefaultGateSerializer, Poseidon2GateSerializer};
 pub use gate_serialization::GateSerializer;
-pub use generator_serialization::default::DefaultGeneratorSerializer;
+pub use generator_serialization::default::{
+    DefaultGeneratorSerializer, EcdsaGeneratorSerializer, Poseidon2GeneratorSerializer,
+};
 pub use generator_serialization::WitnessGeneratorSerializer;
 use hashbrown::HashMap;
 
@@ -26,6 +26,10 @@ pub struct StarkConfig {
 
     /// The configuration of the FRI sub-protocol.
     pub fri_config: FriConfig,
+
+    /// Whether to use the zero-knowledge variant of the STARK prover. See
+    /// [`StarkConfig::with_zero_knowledge`].
+    pub(crate) zero_knowledge: bool,
 }
 
 impl Default for StarkConfig {
@@ -41,6 +45,7 @@ impl StarkConfig {
             security_bits,
             num_challenges,
             fri_config,
+            zero_knowledge: false,
         }
     }
 
@@ -57,12 +62,51 @@ impl StarkConfig {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            zero_knowledge: false,
         }
     }
 
-    /// Outputs the [`FriParams`] used during the FRI sub-protocol by this [`StarkConfig`].
+    /// Same as [`StarkConfig::standard_fast_config`], with zero-knowledge enabled.
+    pub const fn standard_fast_zk_config() -> Self {
+        Self::standard_fast_config().with_zero_knowledge(true)
+    }
+
+    /// Returns this configuration with the zero-knowledge variant of the STARK prover enabled
+    /// or disabled.
+    ///
+    /// In this mode, each committed trace or auxiliary polynomial `p` is replaced by
+    /// `p + Z_H r` for some random low-degree `r`, which agrees with `p` on the trace domain
+    /// `H` but hides its openings. The quotient chunks are masked with random polynomials
+    /// cancelling out in their recombination, and all Merkle leaves are salted. Committed
+    /// polynomials then have degree up to twice the trace length.
+    pub const fn with_zero_knowledge(mut self, zero_knowledge: bool) -> Self {
+        self.zero_knowledge = zero_knowledge;
+        self
+    }
+
+    /// Whether this configuration uses the zero-knowledge variant of the STARK prover.
+    pub const fn zero_knowledge(&self) -> bool {
+        self.zero_knowledge
+    }
+
+    /// Outputs the [`FriParams`] used during the FRI sub-protocol by this [`StarkConfig`],
+    /// for a trace of length `2^degree_bits`.
     pub fn fri_params(&self, degree_bits: usize) -> FriParams {
-        self.fri_config.fri_params(degree_bits, false)
+        self.fri_config
+            .fri_params(self.committed_degree_bits(degree_bits), self.zero_knowledge)
+    }
+
+    /// Outputs the log of the degree bound of the polynomials committed to when proving a
+    /// trace of length `2^degree_bits`, which is doubled in zero-knowledge mode.
+    pub const fn committed_degree_bits(&self, degree_bits: usize) -> usize {
+        degree_bits + self.zero_knowledge as usize
+    }
+
+    /// Outputs the number of random coefficients blinding each committed polynomial in
+    /// zero-knowledge mode. Each polynomial reveals `D` values per opening at `zeta` and
+    /// `g * zeta`, and one value per FRI query.
+    pub const fn num_blinding_coeffs<const D: usize>(&self) -> usize {
+        2 * (D + self.fri_config.num_query_rounds)
     }
 
     /// Checks that this STARK configuration is consistent, i.e. that the different
@@ -124,6 +168,24 @@ mod tests {
         assert!(high_rate_config.check_config::<F, D>().is_ok());
     }
 
+    #[test]
+    fn test_zk_config() {
+        type F = GoldilocksField;
+        const D: usize = 2;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        assert!(config.check_config::<F, D>().is_ok());
+        assert!(config.fri_params(10).hiding);
+        assert_eq!(config.fri_params(10).degree_bits, 11);
+        assert!(!StarkConfig::standard_fast_config().fri_params(10).hiding);
+        assert_eq!(
+            StarkConfig::standard_fast_config()
+                .fri_params(10)
+                .degree_bits,
+            10
+        );
+    }
+
     #[test]
     fn test_invalid_config() {
         type F = GoldilocksField;
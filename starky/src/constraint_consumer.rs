@@ -34,6 +34,39 @@ pub struct ConstraintConsumer<P: PackedField> {
     /// The evaluation of the Lagrange basis polynomial which is nonzero at the point associated
     /// with the last trace row, and zero at other points in the subgroup.
     lagrange_basis_last: P,
+
+    /// If set, all emitted constraints are also recorded individually, before being filtered.
+    /// This is only used to debug traces, and is compiled out of the prover otherwise.
+    #[cfg(any(test, feature = "debug_trace"))]
+    recorded_constraints: Option<Vec<(ConstraintKind, P)>>,
+}
+
+/// The rows of the trace on which a constraint must hold.
+#[cfg(any(test, feature = "debug_trace"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// The constraint must hold on all rows.
+    EveryRow,
+    /// The constraint must hold on the first row only.
+    FirstRow,
+    /// The constraint must hold on the last row only.
+    LastRow,
+    /// The constraint must hold on all rows but the last.
+    Transition,
+}
+
+#[cfg(any(test, feature = "debug_trace"))]
+impl ConstraintKind {
+    /// Indicates whether a constraint of this kind must hold on the given row of a trace
+    /// of length `degree`.
+    pub const fn applies_to_row(self, row: usize, degree: usize) -> bool {
+        match self {
+            Self::EveryRow => true,
+            Self::FirstRow => row == 0,
+            Self::LastRow => row == degree - 1,
+            Self::Transition => row != degree - 1,
+        }
+    }
 }
 
 impl<P: PackedField> ConstraintConsumer<P> {
@@ -50,6 +83,22 @@ impl<P: PackedField> ConstraintConsumer<P> {
             z_last,
             lagrange_basis_first,
             lagrange_basis_last,
+            #[cfg(any(test, feature = "debug_trace"))]
+            recorded_constraints: None,
+        }
+    }
+
+    /// Creates a new instance of [`ConstraintConsumer`] which does not accumulate constraints,
+    /// but records each of them individually along with its [`ConstraintKind`].
+    #[cfg(any(test, feature = "debug_trace"))]
+    pub(crate) fn new_recording() -> Self {
+        Self {
+            constraint_accs: vec![],
+            alphas: vec![],
+            z_last: P::ONES,
+            lagrange_basis_first: P::ONES,
+            lagrange_basis_last: P::ONES,
+            recorded_constraints: Some(vec![]),
         }
     }
 
@@ -59,29 +108,55 @@ impl<P: PackedField> ConstraintConsumer<P> {
         self.constraint_accs
     }
 
+    /// Consumes this [`ConstraintConsumer`] and outputs all recorded constraints, in the
+    /// order in which they were emitted.
+    #[cfg(any(test, feature = "debug_trace"))]
+    pub(crate) fn recorded_constraints(self) -> Vec<(ConstraintKind, P)> {
+        self.recorded_constraints.unwrap_or_default()
+    }
+
     /// Add one constraint valid on all rows except the last.
     pub fn constraint_transition(&mut self, constraint: P) {
-        self.constraint(constraint * self.z_last);
+        #[cfg(any(test, feature = "debug_trace"))]
+        self.record(ConstraintKind::Transition, constraint);
+        self.accumulate(constraint * self.z_last);
     }
 
     /// Add one constraint on all rows.
     pub fn constraint(&mut self, constraint: P) {
-        for (&alpha, acc) in self.alphas.iter().zip(&mut self.constraint_accs) {
-            *acc *= alpha;
-            *acc += constraint;
-        }
+        #[cfg(any(test, feature = "debug_trace"))]
+        self.record(ConstraintKind::EveryRow, constraint);
+        self.accumulate(constraint);
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
     /// first row of the trace.
     pub fn constraint_first_row(&mut self, constraint: P) {
-        self.constraint(constraint * self.lagrange_basis_first);
+        #[cfg(any(test, feature = "debug_trace"))]
+        self.record(ConstraintKind::FirstRow, constraint);
+        self.accumulate(constraint * self.lagrange_basis_first);
     }
 
     /// Add one constraint, but first multiply it by a filter such that it will only apply to the
     /// last row of the trace.
     pub fn constraint_last_row(&mut self, constraint: P) {
-        self.constraint(constraint * self.lagrange_basis_last);
+        #[cfg(any(test, feature = "debug_trace"))]
+        self.record(ConstraintKind::LastRow, constraint);
+        self.accumulate(constraint * self.lagrange_basis_last);
+    }
+
+    fn accumulate(&mut self, constraint: P) {
+        for (&alpha, acc) in self.alphas.iter().zip(&mut self.constraint_accs) {
+            *acc *= alpha;
+            *acc += constraint;
+        }
+    }
+
+    #[cfg(any(test, feature = "debug_trace"))]
+    fn record(&mut self, kind: ConstraintKind, constraint: P) {
+        if let Some(recorded_constraints) = &mut self.recorded_constraints {
+            recorded_constraints.push((kind, constraint));
+        }
     }
 }
 
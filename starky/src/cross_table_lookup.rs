@@ -65,9 +65,9 @@ pub type TableIdx = usize;
 /// `columns` represents linear combinations of the columns of `table`.
 #[derive(Clone, Debug)]
 pub struct TableWithColumns<F: Field> {
-    table: TableIdx,
-    columns: Vec<Column<F>>,
-    filter: Filter<F>,
+    pub(crate) table: TableIdx,
+    pub(crate) columns: Vec<Column<F>>,
+    pub(crate) filter: Filter<F>,
 }
 
 impl<F: Field> TableWithColumns<F> {
@@ -935,8 +935,9 @@ pub fn verify_cross_table_lookups_circuit<
             );
 
             // Get elements looking into `looked_table` that are not associated to any STARK.
-            let extra_sum = ctl_extra_looking_sum.map(|v| v[c]).unwrap_or_default();
-            looking_zs_sum = builder.add(looking_zs_sum, extra_sum);
+            if let Some(extra_sum) = ctl_extra_looking_sum.map(|v| v[c]) {
+                looking_zs_sum = builder.add(looking_zs_sum, extra_sum);
+            }
 
             // Get the looked table CTL polynomial opening.
             let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
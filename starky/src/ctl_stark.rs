@@ -0,0 +1,470 @@
+//! An example of generating and verifying proofs for a multi-STARK system,
+//! made of two toy STARK tables connected through a cross-table lookup.
+//! The first table computes a Fibonacci sequence, while the second one
+//! stores the same states in reverse order.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{CrossTableLookup, TableWithColumns};
+use crate::evaluation_frame::{StarkEvaluationFrame, StarkFrame};
+use crate::lookup::{Column, Filter};
+use crate::stark::Stark;
+use crate::util::trace_rows_to_poly_values;
+
+/// Index of the Fibonacci table within the multi-STARK system.
+const FIBONACCI_TABLE: usize = 0;
+/// Index of the reversed Fibonacci table within the multi-STARK system.
+const REVERSED_TABLE: usize = 1;
+
+const CTL_COLUMNS: usize = 2;
+const FIBONACCI_PUBLIC_INPUTS: usize = 3;
+
+/// Generates the Fibonacci states `[x0, x1]`, starting from the provided initial values.
+fn fibonacci_rows<F: RichField>(num_rows: usize, x0: F, x1: F) -> Vec<[F; CTL_COLUMNS]> {
+    (0..num_rows)
+        .scan([x0, x1], |acc, _| {
+            let tmp = *acc;
+            acc[0] = tmp[1];
+            acc[1] = tmp[0] + tmp[1];
+            Some(tmp)
+        })
+        .collect()
+}
+
+/// Returns the cross-table lookup stating that all rows of the Fibonacci table
+/// appear in the reversed Fibonacci table.
+fn ctls<F: RichField>() -> Vec<CrossTableLookup<F>> {
+    vec![CrossTableLookup::new(
+        vec![TableWithColumns::new(
+            FIBONACCI_TABLE,
+            Column::singles(0..CTL_COLUMNS).collect(),
+            Filter::default(),
+        )],
+        TableWithColumns::new(
+            REVERSED_TABLE,
+            Column::singles(0..CTL_COLUMNS).collect(),
+            Filter::default(),
+        ),
+    )]
+}
+
+/// Toy STARK system used for testing.
+/// Computes a Fibonacci sequence with state `[x0, x1]` using the state transition
+/// `x0' <- x1, x1' <- x0 + x1`, and exposes its rows to a cross-table lookup.
+#[derive(Copy, Clone)]
+struct FibonacciCtlStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FibonacciCtlStark<F, D> {
+    // The first public input is `x0`.
+    const PI_INDEX_X0: usize = 0;
+    // The second public input is `x1`.
+    const PI_INDEX_X1: usize = 1;
+    // The third public input is the second element of the last row.
+    const PI_INDEX_RES: usize = 2;
+
+    const fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace using `x0, x1` as initial state values.
+    fn generate_trace(&self, x0: F, x1: F) -> Vec<PolynomialValues<F>> {
+        trace_rows_to_poly_values(fibonacci_rows(self.num_rows, x0, x1))
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciCtlStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, CTL_COLUMNS, FIBONACCI_PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, CTL_COLUMNS, FIBONACCI_PUBLIC_INPUTS>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Check public inputs.
+        yield_constr.constraint_first_row(local_values[0] - public_inputs[Self::PI_INDEX_X0]);
+        yield_constr.constraint_first_row(local_values[1] - public_inputs[Self::PI_INDEX_X1]);
+        yield_constr.constraint_last_row(local_values[1] - public_inputs[Self::PI_INDEX_RES]);
+
+        // x0' <- x1
+        yield_constr.constraint_transition(next_values[0] - local_values[1]);
+        // x1' <- x0 + x1
+        yield_constr.constraint_transition(next_values[1] - local_values[0] - local_values[1]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+        // Check public inputs.
+        let pis_constraints = [
+            builder.sub_extension(local_values[0], public_inputs[Self::PI_INDEX_X0]),
+            builder.sub_extension(local_values[1], public_inputs[Self::PI_INDEX_X1]),
+            builder.sub_extension(local_values[1], public_inputs[Self::PI_INDEX_RES]),
+        ];
+        yield_constr.constraint_first_row(builder, pis_constraints[0]);
+        yield_constr.constraint_first_row(builder, pis_constraints[1]);
+        yield_constr.constraint_last_row(builder, pis_constraints[2]);
+
+        // x0' <- x1
+        let first_col_constraint = builder.sub_extension(next_values[0], local_values[1]);
+        yield_constr.constraint_transition(builder, first_col_constraint);
+        // x1' <- x0 + x1
+        let second_col_constraint = {
+            let tmp = builder.sub_extension(next_values[1], local_values[0]);
+            builder.sub_extension(tmp, local_values[1])
+        };
+        yield_constr.constraint_transition(builder, second_col_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn requires_ctls(&self) -> bool {
+        true
+    }
+}
+
+/// Toy STARK system used for testing.
+/// Stores the states `[x0, x1]` of a Fibonacci sequence in reverse order, using the
+/// state transition `x0' <- x1 - x0, x1' <- x0`.
+#[derive(Copy, Clone)]
+struct ReversedFibonacciStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> ReversedFibonacciStark<F, D> {
+    const fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace of the Fibonacci sequence starting with `x0, x1`, in reverse order.
+    fn generate_trace(&self, x0: F, x1: F) -> Vec<PolynomialValues<F>> {
+        let mut trace_rows = fibonacci_rows(self.num_rows, x0, x1);
+        trace_rows.reverse();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for ReversedFibonacciStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkFrame<P, P::Scalar, CTL_COLUMNS, 0>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget = StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, CTL_COLUMNS, 0>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+
+        // x0' <- x1 - x0
+        yield_constr.constraint_transition(next_values[0] - local_values[1] + local_values[0]);
+        // x1' <- x0
+        yield_constr.constraint_transition(next_values[1] - local_values[0]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+
+        // x0' <- x1 - x0
+        let first_col_constraint = {
+            let tmp = builder.sub_extension(next_values[0], local_values[1]);
+            builder.add_extension(tmp, local_values[0])
+        };
+        yield_constr.constraint_transition(builder, first_col_constraint);
+        // x1' <- x0
+        let second_col_constraint = builder.sub_extension(next_values[1], local_values[0]);
+        yield_constr.constraint_transition(builder, second_col_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+
+    fn requires_ctls(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::ctl_stark::{ctls, FibonacciCtlStark, ReversedFibonacciStark};
+    use crate::debug_trace::{debug_multi_trace, MultiTraceFailure};
+    use crate::multi_stark::MultiStark;
+    use crate::prover::prove_multi;
+    use crate::recursive_verifier::{
+        add_virtual_multi_stark_proof, set_multi_stark_proof_target,
+        verify_multi_stark_proof_circuit,
+    };
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::verifier::verify_multi;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S0 = FibonacciCtlStark<F, D>;
+    type S1 = ReversedFibonacciStark<F, D>;
+
+    fn fibonacci<F: Field>(n: usize, x0: F, x1: F) -> F {
+        (0..n).fold((x0, x1), |x, _| (x.1, x.0 + x.1)).1
+    }
+
+    fn multi_stark(num_rows: usize) -> MultiStark<F, C, D, 2> {
+        MultiStark::new(
+            [Box::new(S0::new(num_rows)), Box::new(S1::new(num_rows))],
+            ctls(),
+        )
+    }
+
+    #[test]
+    fn test_ctl_stark() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [
+            vec![F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)],
+            vec![],
+        ];
+
+        let traces = [
+            S0::new(num_rows).generate_trace(F::ZERO, F::ONE),
+            S1::new(num_rows).generate_trace(F::ZERO, F::ONE),
+        ];
+        let multi_stark = multi_stark(num_rows);
+        let proof = prove_multi(
+            &multi_stark,
+            &config,
+            traces,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        assert_eq!(proof.recover_degree_bits(&config), [5, 5]);
+        verify_multi(&multi_stark, &proof, &config)?;
+
+        // Proofs of tables with missing cross-table lookup openings must be rejected.
+        let mut malformed_proof = proof;
+        malformed_proof.stark_proofs[1].proof.openings.ctl_zs_first = None;
+        assert!(verify_multi(&multi_stark, &malformed_proof, &config).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctl_stark_debug_trace() {
+        let num_rows = 1 << 5;
+        let public_inputs = [
+            vec![F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)],
+            vec![],
+        ];
+
+        let mut traces = [
+            S0::new(num_rows).generate_trace(F::ZERO, F::ONE),
+            S1::new(num_rows).generate_trace(F::ZERO, F::ONE),
+        ];
+        let multi_stark = multi_stark(num_rows);
+        assert!(debug_multi_trace(&multi_stark, &traces, &public_inputs).is_empty());
+
+        // Alter the first row of the reversed table, which corresponds to the last row
+        // of the Fibonacci table.
+        traces[1][0].values[0] += F::ONE;
+        let failures = debug_multi_trace(&multi_stark, &traces, &public_inputs);
+        assert!(failures.iter().all(|failure| match failure {
+            MultiTraceFailure::Table { table, .. } => *table == 1,
+            MultiTraceFailure::CrossTableLookup { .. } => true,
+        }));
+        let ctl_locations = failures
+            .iter()
+            .filter_map(|failure| match failure {
+                MultiTraceFailure::CrossTableLookup { location, .. } => Some(*location),
+                MultiTraceFailure::Table { .. } => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ctl_locations, [(0, num_rows - 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_ctl_stark_zk() -> Result<()> {
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 10;
+        let public_inputs = [
+            vec![F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)],
+            vec![],
+        ];
+
+        let traces = [
+            S0::new(num_rows).generate_trace(F::ZERO, F::ONE),
+            S1::new(num_rows).generate_trace(F::ZERO, F::ONE),
+        ];
+        let multi_stark = multi_stark(num_rows);
+        let proof = prove_multi(
+            &multi_stark,
+            &config,
+            traces,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        verify_multi(&multi_stark, &proof, &config)
+    }
+
+    #[test]
+    fn test_recursive_ctl_stark_verifier() -> Result<()> {
+        init_logger();
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [
+            vec![F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)],
+            vec![],
+        ];
+
+        let traces = [
+            S0::new(num_rows).generate_trace(F::ZERO, F::ONE),
+            S1::new(num_rows).generate_trace(F::ZERO, F::ONE),
+        ];
+        let multi_stark = multi_stark(num_rows);
+        let proof = prove_multi(
+            &multi_stark,
+            &config,
+            traces,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_multi(&multi_stark, &proof, &config)?;
+
+        let circuit_config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
+        let mut pw = PartialWitness::new();
+        let degree_bits = proof.recover_degree_bits(&config);
+        let pt = add_virtual_multi_stark_proof(&mut builder, &multi_stark, &config, degree_bits);
+        set_multi_stark_proof_target(&mut pw, &pt, &proof, &config, builder.zero())?;
+
+        verify_multi_stark_proof_circuit::<F, C, D, 2>(&mut builder, &multi_stark, &pt, &config);
+        builder.print_gate_counts(0);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+
+    fn init_logger() {
+        let _ = env_logger::builder().format_timestamp(None).try_init();
+    }
+
+    #[test]
+    fn test_ctl_stark_invalid_lookup() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [
+            vec![F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)],
+            vec![],
+        ];
+
+        // Both traces are valid on their own, but the reversed table
+        // doesn't contain the rows of the Fibonacci table.
+        let traces = [
+            S0::new(num_rows).generate_trace(F::ZERO, F::ONE),
+            S1::new(num_rows).generate_trace(F::ONE, F::ONE),
+        ];
+        let multi_stark = multi_stark(num_rows);
+        let proof = prove_multi(
+            &multi_stark,
+            &config,
+            traces,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        assert!(verify_multi(&multi_stark, &proof, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ctl_stark_degree() -> Result<()> {
+        let num_rows = 1 << 5;
+        test_stark_low_degree(S0::new(num_rows))?;
+        test_stark_low_degree(S1::new(num_rows))
+    }
+
+    #[test]
+    fn test_ctl_stark_circuit() -> Result<()> {
+        let num_rows = 1 << 5;
+        test_stark_circuit_constraints::<F, C, S0, D>(S0::new(num_rows))?;
+        test_stark_circuit_constraints::<F, C, S1, D>(S1::new(num_rows))
+    }
+
+    #[test]
+    #[should_panic(expected = "should require CTLs")]
+    fn test_ctl_stark_unused_table() {
+        let num_rows = 1 << 5;
+        let _ = MultiStark::<F, C, D, 2>::new(
+            [Box::new(S0::new(num_rows)), Box::new(S1::new(num_rows))],
+            vec![],
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! Tools to debug STARK traces, by checking them row by row against the constraints,
+//! lookups and cross-table lookups of a [`Stark`] or a [`MultiStark`] system.
+//!
+//! Unlike the checks performed by the prover when debug assertions are enabled, these
+//! checks are available in release builds, and report every failure along with its
+//! location in the trace instead of panicking at the first one. They are run on the
+//! unextended trace only, and do not generate any proof.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use core::iter::once;
+
+use hashbrown::HashMap;
+use itertools::Itertools;
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use plonky2_maybe_rayon::*;
+
+use crate::constraint_consumer::{ConstraintConsumer, ConstraintKind};
+use crate::cross_table_lookup::{CrossTableLookup, TableIdx};
+use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::lookup::Lookup;
+use crate::multi_stark::MultiStark;
+use crate::stark::Stark;
+
+/// A failure found while debugging the trace of a single [`Stark`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceFailure<F: Field> {
+    /// A constraint of the STARK does not vanish on some row.
+    Constraint {
+        /// The row on which the constraint fails.
+        row: usize,
+        /// The index of the constraint, in the order in which constraints are emitted
+        /// by [`Stark::eval_packed_generic`].
+        index: usize,
+        /// The rows on which the constraint must hold.
+        kind: ConstraintKind,
+        /// The value of the constraint on this row.
+        value: F,
+        /// The trace values on this row.
+        local_values: Vec<F>,
+        /// The trace values on the following row.
+        next_values: Vec<F>,
+    },
+    /// The values looked up by a [`Lookup`] do not match the values of its table, along
+    /// with their frequencies.
+    Lookup {
+        /// The index of the lookup in [`Stark::lookups`].
+        lookup: usize,
+        /// The first row on which the offending value appears, either in the looking
+        /// columns or in the table column.
+        row: usize,
+        /// The offending value.
+        value: F,
+        /// The number of times this value is looked up.
+        looking_count: F,
+        /// The number of times this value is provided by the table, according to the
+        /// frequencies column.
+        table_count: F,
+    },
+}
+
+/// A failure found while debugging the traces of a [`MultiStark`] system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MultiTraceFailure<F: Field> {
+    /// A failure within a single table.
+    Table {
+        /// The index of the table.
+        table: TableIdx,
+        /// The failure found in this table.
+        failure: TraceFailure<F>,
+    },
+    /// The rows selected by the looking tables of a [`CrossTableLookup`] do not match
+    /// the rows selected by its looked table.
+    CrossTableLookup {
+        /// The index of the cross-table lookup.
+        ctl: usize,
+        /// The first table and row in which the offending combination of values appears.
+        location: (TableIdx, usize),
+        /// The offending combination of values.
+        values: Vec<F>,
+        /// The number of times these values are selected in the looking tables.
+        looking_count: F,
+        /// The number of times these values are selected in the looked table.
+        looked_count: F,
+    },
+}
+
+impl<F: Field> Display for TraceFailure<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Constraint {
+                row,
+                index,
+                kind,
+                value,
+                local_values,
+                next_values,
+            } => write!(
+                f,
+                "constraint {index} ({kind:?}) fails at row {row} with value {value}, \
+                 local values: [{}], next values: [{}]",
+                local_values.iter().join(", "),
+                next_values.iter().join(", "),
+            ),
+            Self::Lookup {
+                lookup,
+                row,
+                value,
+                looking_count,
+                table_count,
+            } => write!(
+                f,
+                "lookup {lookup} fails for value {value} (first seen at row {row}): \
+                 looked up {looking_count} times, provided {table_count} times by the table",
+            ),
+        }
+    }
+}
+
+impl<F: Field> Display for MultiTraceFailure<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Table { table, failure } => write!(f, "table {table}: {failure}"),
+            Self::CrossTableLookup {
+                ctl,
+                location: (table, row),
+                values,
+                looking_count,
+                looked_count,
+            } => write!(
+                f,
+                "cross-table lookup {ctl} fails for values [{}] (first seen in table {table} \
+                 at row {row}): selected {looking_count} times by the looking tables, \
+                 {looked_count} times by the looked table",
+                values.iter().join(", "),
+            ),
+        }
+    }
+}
+
+/// Checks the provided trace against all the constraints and lookups of a [`Stark`],
+/// and returns all the failures found, ordered by row for constraints.
+///
+/// An empty output means that the trace satisfies the STARK, up to its cross-table lookups,
+/// which are checked by [`debug_multi_trace`].
+pub fn debug_trace<F, S, const D: usize>(
+    stark: &S,
+    trace_poly_values: &[PolynomialValues<F>],
+    public_inputs: &[F],
+) -> Vec<TraceFailure<F>>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    assert_eq!(
+        trace_poly_values.len(),
+        S::COLUMNS,
+        "Invalid number of trace columns."
+    );
+    assert_eq!(
+        public_inputs.len(),
+        S::PUBLIC_INPUTS,
+        "Invalid number of public inputs."
+    );
+
+    let degree = trace_poly_values[0].len();
+    let preprocessed_columns = stark.generate_preprocessed_columns();
+    assert_eq!(
+        preprocessed_columns.len(),
+        S::PREPROCESSED_COLUMNS,
+        "Invalid number of preprocessed columns."
+    );
+    assert!(
+        trace_poly_values
+            .iter()
+            .chain(&preprocessed_columns)
+            .all(|column| column.len() == degree),
+        "All trace and preprocessed columns must have the same length."
+    );
+
+    let row_values = |columns: &[PolynomialValues<F>], row: usize| {
+        columns
+            .iter()
+            .map(|column| column.values[row])
+            .collect::<Vec<_>>()
+    };
+
+    let constraint_failures = (0..degree)
+        .into_par_iter()
+        .flat_map_iter(|row| {
+            let next_row = (row + 1) % degree;
+            let local_values = row_values(trace_poly_values, row);
+            let next_values = row_values(trace_poly_values, next_row);
+            let vars = S::EvaluationFrame::<F, F, 1>::from_values_with_preprocessed(
+                &local_values,
+                &next_values,
+                &row_values(&preprocessed_columns, row),
+                &row_values(&preprocessed_columns, next_row),
+                public_inputs,
+            );
+
+            let mut consumer = ConstraintConsumer::new_recording();
+            stark.eval_packed_base(&vars, &mut consumer);
+
+            consumer
+                .recorded_constraints()
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, (kind, value))| {
+                    kind.applies_to_row(row, degree) && value.is_nonzero()
+                })
+                .map(|(index, (kind, value))| TraceFailure::Constraint {
+                    row,
+                    index,
+                    kind,
+                    value,
+                    local_values: local_values.clone(),
+                    next_values: next_values.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let lookup_failures = stark
+        .lookups()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, lookup)| check_lookup(index, lookup, trace_poly_values))
+        .collect::<Vec<_>>();
+
+    constraint_failures
+        .into_iter()
+        .chain(lookup_failures)
+        .collect()
+}
+
+/// Checks the provided traces against all the constraints, lookups and cross-table lookups
+/// of a [`MultiStark`] system, and returns all the failures found.
+pub fn debug_multi_trace<F, C, const D: usize, const N: usize>(
+    multi_stark: &MultiStark<F, C, D, N>,
+    trace_poly_values: &[Vec<PolynomialValues<F>>; N],
+    public_inputs: &[Vec<F>; N],
+) -> Vec<MultiTraceFailure<F>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let table_failures = (0..N).flat_map(|table| {
+        multi_stark
+            .stark(table)
+            .debug_trace(&trace_poly_values[table], &public_inputs[table])
+            .into_iter()
+            .map(move |failure| MultiTraceFailure::Table { table, failure })
+    });
+    let ctl_failures = multi_stark
+        .cross_table_lookups()
+        .iter()
+        .enumerate()
+        .flat_map(|(index, ctl)| check_cross_table_lookup(index, ctl, trace_poly_values));
+
+    table_failures.chain(ctl_failures).collect()
+}
+
+/// Multiplicities of values, along with the location of their first occurrence.
+type Multiplicities<K, L, F> = HashMap<K, (L, F, F)>;
+
+/// Checks that the values looked up by a [`Lookup`] all appear in its table, with the
+/// expected frequencies.
+fn check_lookup<F: Field>(
+    index: usize,
+    lookup: &Lookup<F>,
+    trace_poly_values: &[PolynomialValues<F>],
+) -> Vec<TraceFailure<F>> {
+    let degree = trace_poly_values[0].len();
+    let mut multiplicities: Multiplicities<F, usize, F> = HashMap::new();
+
+    for row in 0..degree {
+        for (column, filter) in lookup.columns.iter().zip(&lookup.filter_columns) {
+            let filter = filter.eval_table(trace_poly_values, row);
+            if filter.is_nonzero() {
+                let value = column.eval_table(trace_poly_values, row);
+                multiplicities
+                    .entry(value)
+                    .or_insert((row, F::ZERO, F::ZERO))
+                    .1 += filter;
+            }
+        }
+        let value = lookup.table_column.eval_table(trace_poly_values, row);
+        let frequency = lookup.frequencies_column.eval_table(trace_poly_values, row);
+        multiplicities
+            .entry(value)
+            .or_insert((row, F::ZERO, F::ZERO))
+            .2 += frequency;
+    }
+
+    multiplicities
+        .into_iter()
+        .filter(|(_, (_, looking_count, table_count))| looking_count != table_count)
+        .sorted_by_key(|(_, (row, _, _))| *row)
+        .map(
+            |(value, (row, looking_count, table_count))| TraceFailure::Lookup {
+                lookup: index,
+                row,
+                value,
+                looking_count,
+                table_count,
+            },
+        )
+        .collect()
+}
+
+/// Checks that the rows selected by the looking tables of a [`CrossTableLookup`] match
+/// the rows selected by its looked table.
+fn check_cross_table_lookup<F: Field, const N: usize>(
+    index: usize,
+    ctl: &CrossTableLookup<F>,
+    trace_poly_values: &[Vec<PolynomialValues<F>>; N],
+) -> Vec<MultiTraceFailure<F>> {
+    let mut multiplicities: Multiplicities<Vec<F>, (TableIdx, usize), F> = HashMap::new();
+
+    let looking = ctl.looking_tables.iter().map(|twc| (twc, true));
+    for (twc, is_looking) in looking.chain(once((&ctl.looked_table, false))) {
+        let trace = &trace_poly_values[twc.table];
+        for row in 0..trace[0].len() {
+            let filter = twc.filter.eval_table(trace, row);
+            if filter.is_zero() {
+                continue;
+            }
+            let values = twc
+                .columns
+                .iter()
+                .map(|column| column.eval_table(trace, row))
+                .collect::<Vec<_>>();
+            let entry =
+                multiplicities
+                    .entry(values)
+                    .or_insert(((twc.table, row), F::ZERO, F::ZERO));
+            if is_looking {
+                entry.1 += filter;
+            } else {
+                entry.2 += filter;
+            }
+        }
+    }
+
+    multiplicities
+        .into_iter()
+        .filter(|(_, (_, looking_count, looked_count))| looking_count != looked_count)
+        .sorted_by_key(|(_, (location, _, _))| *location)
+        .map(|(values, (location, looking_count, looked_count))| {
+            MultiTraceFailure::CrossTableLookup {
+                ctl: index,
+                location,
+                values,
+                looking_count,
+                looked_count,
+            }
+        })
+        .collect()
+}
@@ -12,6 +12,8 @@ pub trait StarkEvaluationFrame<T: Copy + Clone + Default, U: Copy + Clone + Defa
     const COLUMNS: usize;
     /// The number of public inputs for the STARK.
     const PUBLIC_INPUTS: usize;
+    /// The number of preprocessed columns for the STARK table this evaluation frame views.
+    const PREPROCESSED_COLUMNS: usize = 0;
 
     /// Returns the local values (i.e. current row) for this evaluation frame.
     fn get_local_values(&self) -> &[T];
@@ -21,11 +23,37 @@ pub trait StarkEvaluationFrame<T: Copy + Clone + Default, U: Copy + Clone + Defa
     /// Returns the public inputs for this evaluation frame.
     fn get_public_inputs(&self) -> &[U];
 
+    /// Returns the local values (i.e. current row) of the preprocessed columns
+    /// for this evaluation frame.
+    fn get_local_preprocessed_values(&self) -> &[T] {
+        &[]
+    }
+    /// Returns the next values (i.e. next row) of the preprocessed columns
+    /// for this evaluation frame.
+    fn get_next_preprocessed_values(&self) -> &[T] {
+        &[]
+    }
+
     /// Outputs a new evaluation frame from the provided local and next values.
     ///
     /// **NOTE**: Concrete implementations of this method SHOULD ensure that
     /// the provided slices lengths match the `Self::COLUMNS` value.
     fn from_values(lv: &[T], nv: &[T], pis: &[U]) -> Self;
+
+    /// Outputs a new evaluation frame from the provided local and next values,
+    /// along with the local and next values of the preprocessed columns.
+    ///
+    /// The default implementation only supports frames without preprocessed columns.
+    fn from_values_with_preprocessed(
+        lv: &[T],
+        nv: &[T],
+        preprocessed_lv: &[T],
+        preprocessed_nv: &[T],
+        pis: &[U],
+    ) -> Self {
+        assert!(preprocessed_lv.is_empty() && preprocessed_nv.is_empty());
+        Self::from_values(lv, nv, pis)
+    }
 }
 
 /// An evaluation frame to be used when defining constraints of a STARK system, that
@@ -72,3 +100,79 @@ impl<T: Copy + Clone + Default, U: Copy + Clone + Default, const N: usize, const
         }
     }
 }
+
+/// An evaluation frame to be used when defining constraints of a STARK system with
+/// preprocessed columns, that implements the [`StarkEvaluationFrame`] trait.
+#[derive(Debug)]
+pub struct StarkPreprocessedFrame<
+    T: Copy + Clone + Default,
+    U: Copy + Clone + Default,
+    const N: usize,
+    const N2: usize,
+    const NP: usize,
+> {
+    local_values: [T; N],
+    next_values: [T; N],
+    local_preprocessed_values: [T; NP],
+    next_preprocessed_values: [T; NP],
+    public_inputs: [U; N2],
+}
+
+impl<
+        T: Copy + Clone + Default,
+        U: Copy + Clone + Default,
+        const N: usize,
+        const N2: usize,
+        const NP: usize,
+    > StarkEvaluationFrame<T, U> for StarkPreprocessedFrame<T, U, N, N2, NP>
+{
+    const COLUMNS: usize = N;
+    const PUBLIC_INPUTS: usize = N2;
+    const PREPROCESSED_COLUMNS: usize = NP;
+
+    fn get_local_values(&self) -> &[T] {
+        &self.local_values
+    }
+
+    fn get_next_values(&self) -> &[T] {
+        &self.next_values
+    }
+
+    fn get_public_inputs(&self) -> &[U] {
+        &self.public_inputs
+    }
+
+    fn get_local_preprocessed_values(&self) -> &[T] {
+        &self.local_preprocessed_values
+    }
+
+    fn get_next_preprocessed_values(&self) -> &[T] {
+        &self.next_preprocessed_values
+    }
+
+    fn from_values(lv: &[T], nv: &[T], pis: &[U]) -> Self {
+        Self::from_values_with_preprocessed(lv, nv, &[], &[], pis)
+    }
+
+    fn from_values_with_preprocessed(
+        lv: &[T],
+        nv: &[T],
+        preprocessed_lv: &[T],
+        preprocessed_nv: &[T],
+        pis: &[U],
+    ) -> Self {
+        assert_eq!(lv.len(), Self::COLUMNS);
+        assert_eq!(nv.len(), Self::COLUMNS);
+        assert_eq!(preprocessed_lv.len(), Self::PREPROCESSED_COLUMNS);
+        assert_eq!(preprocessed_nv.len(), Self::PREPROCESSED_COLUMNS);
+        assert_eq!(pis.len(), Self::PUBLIC_INPUTS);
+
+        Self {
+            local_values: lv.try_into().unwrap(),
+            next_values: nv.try_into().unwrap(),
+            local_preprocessed_values: preprocessed_lv.try_into().unwrap(),
+            next_preprocessed_values: preprocessed_nv.try_into().unwrap(),
+            public_inputs: pis.try_into().unwrap(),
+        }
+    }
+}
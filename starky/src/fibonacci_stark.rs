@@ -140,7 +140,10 @@ mod tests {
     use anyhow::Result;
     use itertools::Itertools;
     use plonky2::field::extension::Extendable;
-    use plonky2::field::types::Field;
+    use plonky2::field::packable::Packable;
+    use plonky2::field::packed::PackedField;
+    use plonky2::field::packed_extension::PackedQuadraticExtension;
+    use plonky2::field::types::{Field, Sample};
     use plonky2::hash::hash_types::RichField;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_builder::CircuitBuilder;
@@ -149,15 +152,19 @@ mod tests {
     use plonky2::util::timing::TimingTree;
 
     use crate::config::StarkConfig;
-    use crate::fibonacci_stark::FibonacciStark;
+    use crate::constraint_consumer::{ConstraintConsumer, ConstraintKind};
+    use crate::debug_trace::{debug_trace, TraceFailure};
+    use crate::evaluation_frame::{StarkEvaluationFrame, StarkFrame};
+    use crate::fibonacci_stark::{FibonacciStark, FIBONACCI_COLUMNS, FIBONACCI_PUBLIC_INPUTS};
     use crate::proof::StarkProofWithPublicInputs;
-    use crate::prover::prove;
+    use crate::prover::{commit_to_columns, prove};
     use crate::recursive_verifier::{
         add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
         verify_stark_proof_circuit,
     };
     use crate::stark::Stark;
     use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::validate_shape::validate_stark_proof_shape;
     use crate::verifier::verify_stark_proof;
 
     const D: usize = 2;
@@ -189,6 +196,197 @@ mod tests {
         verify_stark_proof(stark, proof, &config, None)
     }
 
+    #[test]
+    fn test_fibonacci_stark_zk() -> Result<()> {
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 9;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+
+        // The blinded trace polynomials agree with the trace on `H`, but are randomized outside.
+        let commit = || {
+            commit_to_columns::<F, C, D>(trace.clone(), true, &config, &mut TimingTree::default())
+        };
+        let (commitment, other_commitment) = (commit(), commit());
+        let g = F::primitive_root_of_unity(9);
+        let x = F::rand();
+        for ((poly, other_poly), column) in commitment
+            .polynomials
+            .iter()
+            .zip(&other_commitment.polynomials)
+            .zip(&trace)
+        {
+            assert_eq!(poly.eval(g.exp_u64(7)), column.values[7]);
+            assert_eq!(other_poly.eval(g.exp_u64(7)), column.values[7]);
+            assert_ne!(poly.eval(x), other_poly.eval(x));
+        }
+
+        // Proving twice the same statement yields different proofs, which both verify.
+        let prove_zk = || {
+            prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace.clone(),
+                &public_inputs,
+                None,
+                &mut TimingTree::default(),
+            )
+        };
+        let (proof, other_proof) = (prove_zk()?, prove_zk()?);
+        assert_ne!(proof.proof.trace_cap, other_proof.proof.trace_cap);
+        assert_ne!(
+            proof.proof.openings.local_values,
+            other_proof.proof.openings.local_values
+        );
+        assert_ne!(
+            proof.proof.openings.quotient_polys,
+            other_proof.proof.openings.quotient_polys
+        );
+
+        let bytes = proof.to_bytes();
+        let decoded = StarkProofWithPublicInputs::<F, C, D>::from_bytes(bytes, &stark, &config)?;
+        verify_stark_proof(stark, decoded, &config, None)?;
+        verify_stark_proof(stark, other_proof, &config, None)?;
+
+        // Traces too short to be blinded are rejected.
+        let short_stark = S::new(1 << 8);
+        let short_trace = short_stark.generate_trace(F::ZERO, F::ONE);
+        assert!(prove::<F, C, S, D>(
+            short_stark,
+            &config,
+            short_trace,
+            &[F::ZERO, F::ONE, fibonacci((1 << 8) - 1, F::ZERO, F::ONE)],
+            None,
+            &mut TimingTree::default(),
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_serialization() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        let bytes = proof.to_bytes();
+        let decoded =
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(bytes.clone(), &stark, &config)?;
+        assert_eq!(bytes, decoded.to_bytes());
+        verify_stark_proof(stark, decoded, &config, None)?;
+
+        // Truncated or extended encodings must be rejected.
+        let truncated = bytes[..bytes.len() - 1].to_vec();
+        assert!(
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(truncated, &stark, &config).is_err()
+        );
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(extended, &stark, &config).is_err()
+        );
+
+        // So must encodings of proofs for a different trace length.
+        let mut wrong_degree = bytes;
+        wrong_degree[0] += 1;
+        assert!(
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(wrong_degree, &stark, &config)
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_malformed_proofs() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+        validate_stark_proof_shape(
+            &stark,
+            &proof.proof,
+            &proof.public_inputs,
+            None,
+            &config,
+            0,
+            0,
+        )?;
+
+        let malformations: [fn(&mut StarkProofWithPublicInputs<F, C, D>); 6] = [
+            |p| {
+                p.public_inputs.pop();
+            },
+            |p| p.proof.openings.local_values.push(Field::ZERO),
+            |p| p.proof.quotient_polys_cap = None,
+            |p| {
+                p.proof.trace_cap.0.pop();
+            },
+            |p| p.proof.opening_proof.query_round_proofs.clear(),
+            |p| p.proof.opening_proof.final_poly.coeffs.clear(),
+        ];
+        for malform in malformations {
+            let mut malformed_proof = proof.clone();
+            malform(&mut malformed_proof);
+            assert!(verify_stark_proof(stark, malformed_proof, &config, None).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_debug_trace() {
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+
+        let stark = S::new(num_rows);
+        let mut trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        assert!(debug_trace(&stark, &trace, &public_inputs).is_empty());
+
+        trace[1].values[10] += F::ONE;
+        let failures = debug_trace(&stark, &trace, &public_inputs)
+            .into_iter()
+            .map(|failure| match failure {
+                TraceFailure::Constraint {
+                    row, index, kind, ..
+                } => (row, index, kind),
+                TraceFailure::Lookup { .. } => panic!("Unexpected lookup failure"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            failures,
+            [
+                (9, 4, ConstraintKind::Transition),
+                (10, 3, ConstraintKind::Transition),
+                (10, 4, ConstraintKind::Transition),
+            ]
+        );
+    }
+
     #[test]
     fn test_fibonacci_stark_degree() -> Result<()> {
         let num_rows = 1 << 5;
@@ -196,6 +394,41 @@ mod tests {
         test_stark_low_degree(stark)
     }
 
+    #[test]
+    fn test_fibonacci_stark_packed_extension() {
+        type FE = <F as Extendable<D>>::Extension;
+        type P = PackedQuadraticExtension<<F as Packable>::Packing>;
+
+        let stark = S::new(1 << 5);
+        let alphas = FE::rand_vec(2);
+        let random_packed = |n| P::pack_slice(&FE::rand_vec(n * P::WIDTH)).to_vec();
+        let local_values = random_packed(FIBONACCI_COLUMNS);
+        let next_values = random_packed(FIBONACCI_COLUMNS);
+        let public_inputs = FE::rand_vec(FIBONACCI_PUBLIC_INPUTS);
+        let [z_last, lagrange_first, lagrange_last] = random_packed(3).try_into().unwrap();
+
+        let vars = StarkFrame::from_values(&local_values, &next_values, &public_inputs);
+        let mut consumer =
+            ConstraintConsumer::new(alphas.clone(), z_last, lagrange_first, lagrange_last);
+        stark.eval_packed_generic::<FE, P, D>(&vars, &mut consumer);
+        let packed_accs = consumer.accumulators();
+
+        // Each lane must agree with an evaluation over the scalar extension field.
+        for i in 0..P::WIDTH {
+            let lane = |xs: &[P]| xs.iter().map(|x| x.as_slice()[i]).collect::<Vec<_>>();
+            let vars =
+                StarkFrame::from_values(&lane(&local_values), &lane(&next_values), &public_inputs);
+            let mut consumer = ConstraintConsumer::new(
+                alphas.clone(),
+                z_last.as_slice()[i],
+                lagrange_first.as_slice()[i],
+                lagrange_last.as_slice()[i],
+            );
+            stark.eval_ext(&vars, &mut consumer);
+            assert_eq!(consumer.accumulators(), lane(&packed_accs));
+        }
+    }
+
     #[test]
     fn test_fibonacci_stark_circuit() -> Result<()> {
         let num_rows = 1 << 5;
@@ -91,7 +91,7 @@ where
             commit_phase_merkle_caps,
             final_poly,
             pow_witness,
-            degree_bits,
+            config.committed_degree_bits(degree_bits),
             &config.fri_config,
             final_poly_coeff_len,
             max_num_query_steps,
@@ -189,6 +189,42 @@ where
     }
 }
 
+impl<F, C, const D: usize, const N: usize> MultiStarkProof<F, C, D, N>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Computes all Fiat-Shamir challenges used in the multi-STARK proof.
+    ///
+    /// The public inputs and trace caps of all tables are observed first, before
+    /// sampling the cross-table lookup challenges shared by all tables. The
+    /// individual challenges of each table are then derived sequentially.
+    pub fn get_challenges(&self, config: &StarkConfig) -> MultiProofChallenges<F, D, N> {
+        let mut challenger = Challenger::<F, C::Hasher>::new();
+
+        for proof_with_pis in &self.stark_proofs {
+            challenger.observe_elements(&proof_with_pis.public_inputs);
+            challenger.observe_cap(&proof_with_pis.proof.trace_cap);
+        }
+
+        let ctl_challenges =
+            get_grand_product_challenge_set(&mut challenger, config.num_challenges);
+
+        MultiProofChallenges {
+            stark_challenges: core::array::from_fn(|i| {
+                self.stark_proofs[i].proof.get_challenges(
+                    &mut challenger,
+                    Some(&ctl_challenges),
+                    true,
+                    config,
+                    None,
+                )
+            }),
+            ctl_challenges,
+        }
+    }
+}
+
 /// Circuit version of `get_challenges`, with the same flexibility around
 /// `trace_cap` being passed as an `Option`.
 fn get_challenges_target<F, C, const D: usize>(
@@ -335,3 +371,41 @@ impl<const D: usize> StarkProofWithPublicInputsTarget<D> {
             .get_challenges::<F, C>(builder, challenger, challenges, ignore_trace_cap, config)
     }
 }
+
+impl<const D: usize, const N: usize> MultiStarkProofTarget<D, N> {
+    /// Creates all Fiat-Shamir `Target` challenges used in the multi-STARK proof,
+    /// following the same transcript as [`MultiStarkProof::get_challenges`].
+    pub fn get_challenges<F, C>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+    ) -> MultiProofChallengesTarget<D, N>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+
+        for proof_with_pis in &self.stark_proofs {
+            challenger.observe_elements(&proof_with_pis.public_inputs);
+            challenger.observe_cap(&proof_with_pis.proof.trace_cap);
+        }
+
+        let ctl_challenges =
+            get_grand_product_challenge_set_target(builder, &mut challenger, config.num_challenges);
+
+        MultiProofChallengesTarget {
+            stark_challenges: core::array::from_fn(|i| {
+                self.stark_proofs[i].proof.get_challenges::<F, C>(
+                    builder,
+                    &mut challenger,
+                    Some(&ctl_challenges),
+                    true,
+                    config,
+                )
+            }),
+            ctl_challenges,
+        }
+    }
+}
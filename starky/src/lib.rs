@@ -201,6 +201,34 @@
 //! }
 //! ```
 //!
+//! # Defining a multi-STARK system
+//!
+//! Several STARK statements can be proven together, and connected through
+//! [`CrossTableLookup`][crate::cross_table_lookup::CrossTableLookup]s, by
+//! gathering them in a [`MultiStark`][crate::multi_stark::MultiStark].
+//! Each table taking part in a cross-table lookup must return `true` in
+//! [`Stark::requires_ctls`][crate::stark::Stark::requires_ctls]. The system can
+//! then be proven with [`prove_multi`][crate::prover::prove_multi] and verified
+//! with [`verify_multi`][crate::verifier::verify_multi], or recursively with
+//! [`verify_multi_stark_proof_circuit`][crate::recursive_verifier::verify_multi_stark_proof_circuit].
+//!
+//! # Preprocessed columns
+//!
+//! Columns which do not depend on the witness, like selectors or round constants,
+//! can be declared as preprocessed columns through a
+//! [`StarkPreprocessedFrame`][crate::evaluation_frame::StarkPreprocessedFrame] and
+//! [`Stark::generate_preprocessed_columns`][crate::stark::Stark::generate_preprocessed_columns].
+//! They are committed to once with [`setup`][crate::preprocessed::setup], and proofs are then
+//! generated with [`prove_with_preprocessed`][crate::prover::prove_with_preprocessed] and
+//! verified against the resulting [`StarkVerifyingKey`][crate::preprocessed::StarkVerifyingKey].
+//!
+//! # Debugging traces
+//!
+//! When a proof fails to verify because of an invalid trace, the failing constraints,
+//! lookups and cross-table lookups can be located with `debug_trace::debug_trace` or
+//! `debug_trace::debug_multi_trace`, including in release builds. These are only available
+//! with the `debug_trace` feature, so that the prover does not pay for recording constraints.
+//!
 
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::needless_range_loop)]
@@ -218,20 +246,29 @@ mod get_challenges;
 pub mod config;
 pub mod constraint_consumer;
 pub mod cross_table_lookup;
+#[cfg(any(test, feature = "debug_trace"))]
+pub mod debug_trace;
 pub mod evaluation_frame;
 pub mod lookup;
+pub mod multi_stark;
+pub mod preprocessed;
 pub mod proof;
 pub mod prover;
 pub mod recursive_verifier;
 pub mod stark;
 pub mod stark_testing;
 pub mod util;
+pub mod validate_shape;
 mod vanishing_poly;
 pub mod verifier;
 
+#[cfg(test)]
+pub mod ctl_stark;
 #[cfg(test)]
 pub mod fibonacci_stark;
 #[cfg(test)]
 pub mod permutation_stark;
 #[cfg(test)]
+pub mod preprocessed_stark;
+#[cfg(test)]
 pub mod unconstrained_stark;
@@ -0,0 +1,418 @@
+//! Support for multi-STARK systems, i.e. a collection of STARK tables of possibly
+//! different types, connected together through [`CrossTableLookup`]s.
+//!
+//! Since the [`Stark`] trait is not object-safe, each table of a [`MultiStark`] is
+//! stored behind the [`StarkTable`] trait, which is automatically implemented for
+//! any [`Stark`] and exposes the per-table operations needed to prove and verify
+//! the whole system, both natively and recursively.
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::Debug;
+use core::iter::once;
+
+use anyhow::Result;
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::fri::oracle::PolynomialBatch;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::Challenger;
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::util::timing::TimingTree;
+
+use crate::config::StarkConfig;
+use crate::cross_table_lookup::{
+    CrossTableLookup, CtlCheckVars, CtlCheckVarsTarget, CtlData, TableIdx,
+};
+#[cfg(any(test, feature = "debug_trace"))]
+use crate::debug_trace::{debug_trace, TraceFailure};
+use crate::lookup::GrandProductChallengeSet;
+use crate::proof::{
+    StarkProof, StarkProofChallenges, StarkProofChallengesTarget, StarkProofTarget,
+    StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
+};
+use crate::prover::prove_with_commitment;
+use crate::recursive_verifier::{
+    add_virtual_stark_proof_with_pis, verify_stark_proof_with_challenges_circuit,
+};
+use crate::stark::Stark;
+use crate::validate_shape::validate_stark_proof_shape;
+use crate::verifier::verify_stark_proof_with_challenges;
+
+/// An object-safe view of a [`Stark`], allowing STARKs of different types to be
+/// stored together within a [`MultiStark`].
+///
+/// This trait is implemented for any type implementing [`Stark`], and is not
+/// meant to be implemented manually.
+pub trait StarkTable<F, C, const D: usize>: Sync
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// The total number of columns in the trace of this table.
+    fn num_columns(&self) -> usize;
+
+    /// The total number of public inputs of this table.
+    fn num_public_inputs(&self) -> usize;
+
+    /// The number of preprocessed columns of this table.
+    fn num_preprocessed_columns(&self) -> usize;
+
+    /// Outputs the maximum constraint degree of this table.
+    fn constraint_degree(&self) -> usize;
+
+    /// Outputs the number of lookup helper columns of this table.
+    fn num_lookup_helper_columns(&self, config: &StarkConfig) -> usize;
+
+    /// Indicates whether this table is connected to other tables through cross-table lookups.
+    fn requires_ctls(&self) -> bool;
+
+    /// Generates a proof for this table, given its already committed trace.
+    /// See [`prove_with_commitment`].
+    fn prove_with_commitment(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        ctl_data: Option<&CtlData<F>>,
+        ctl_challenges: Option<&GrandProductChallengeSet<F>>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        public_inputs: &[F],
+        timing: &mut TimingTree,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>>;
+
+    /// Checks that a proof for this table has the expected shape.
+    /// See [`validate_stark_proof_shape`].
+    fn validate_proof_shape(
+        &self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        config: &StarkConfig,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+    ) -> Result<()>;
+
+    /// Verifies a proof for this table with the provided challenges.
+    /// See [`verify_stark_proof_with_challenges`].
+    fn verify_with_challenges(
+        &self,
+        proof: &StarkProof<F, C, D>,
+        challenges: &StarkProofChallenges<F, D>,
+        ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
+        public_inputs: &[F],
+        config: &StarkConfig,
+    ) -> Result<()>;
+
+    /// Checks a trace against the constraints and lookups of this table.
+    /// See [`debug_trace`].
+    #[cfg(any(test, feature = "debug_trace"))]
+    fn debug_trace(
+        &self,
+        trace_poly_values: &[PolynomialValues<F>],
+        public_inputs: &[F],
+    ) -> Vec<TraceFailure<F>>;
+
+    /// Adds a new [`StarkProofWithPublicInputsTarget`] for this table to the circuit.
+    /// See [`add_virtual_stark_proof_with_pis`].
+    fn add_virtual_proof_with_pis(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+        degree_bits: usize,
+        num_ctl_helper_zs: usize,
+        num_ctl_zs: usize,
+    ) -> StarkProofWithPublicInputsTarget<D>;
+
+    /// Recursively verifies a proof for this table with the provided challenges.
+    /// See [`verify_stark_proof_with_challenges_circuit`].
+    fn verify_with_challenges_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        proof: &StarkProofTarget<D>,
+        public_inputs: &[Target],
+        challenges: StarkProofChallengesTarget<D>,
+        ctl_vars: Option<&[CtlCheckVarsTarget<F, D>]>,
+        inner_config: &StarkConfig,
+        degree_bits: usize,
+    ) where
+        C::Hasher: AlgebraicHasher<F>;
+}
+
+impl<F, C, S, const D: usize> StarkTable<F, C, D> for S
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    fn num_columns(&self) -> usize {
+        S::COLUMNS
+    }
+
+    fn num_public_inputs(&self) -> usize {
+        S::PUBLIC_INPUTS
+    }
+
+    fn num_preprocessed_columns(&self) -> usize {
+        S::PREPROCESSED_COLUMNS
+    }
+
+    fn constraint_degree(&self) -> usize {
+        Stark::constraint_degree(self)
+    }
+
+    fn num_lookup_helper_columns(&self, config: &StarkConfig) -> usize {
+        Stark::num_lookup_helper_columns(self, config)
+    }
+
+    fn requires_ctls(&self) -> bool {
+        Stark::requires_ctls(self)
+    }
+
+    fn prove_with_commitment(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        ctl_data: Option<&CtlData<F>>,
+        ctl_challenges: Option<&GrandProductChallengeSet<F>>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        public_inputs: &[F],
+        timing: &mut TimingTree,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        prove_with_commitment(
+            self,
+            config,
+            trace_poly_values,
+            trace_commitment,
+            None,
+            ctl_data,
+            ctl_challenges,
+            challenger,
+            public_inputs,
+            None,
+            None,
+            timing,
+        )
+    }
+
+    fn validate_proof_shape(
+        &self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        config: &StarkConfig,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+    ) -> Result<()> {
+        validate_stark_proof_shape(
+            self,
+            &proof_with_pis.proof,
+            &proof_with_pis.public_inputs,
+            None,
+            config,
+            num_ctl_helpers,
+            num_ctl_zs,
+        )
+    }
+
+    fn verify_with_challenges(
+        &self,
+        proof: &StarkProof<F, C, D>,
+        challenges: &StarkProofChallenges<F, D>,
+        ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
+        public_inputs: &[F],
+        config: &StarkConfig,
+    ) -> Result<()> {
+        verify_stark_proof_with_challenges(
+            self,
+            proof,
+            None,
+            challenges,
+            ctl_vars,
+            public_inputs,
+            config,
+        )
+    }
+
+    #[cfg(any(test, feature = "debug_trace"))]
+    fn debug_trace(
+        &self,
+        trace_poly_values: &[PolynomialValues<F>],
+        public_inputs: &[F],
+    ) -> Vec<TraceFailure<F>> {
+        debug_trace(self, trace_poly_values, public_inputs)
+    }
+
+    fn add_virtual_proof_with_pis(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+        degree_bits: usize,
+        num_ctl_helper_zs: usize,
+        num_ctl_zs: usize,
+    ) -> StarkProofWithPublicInputsTarget<D> {
+        add_virtual_stark_proof_with_pis(
+            builder,
+            self,
+            config,
+            degree_bits,
+            num_ctl_helper_zs,
+            num_ctl_zs,
+        )
+    }
+
+    fn verify_with_challenges_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        proof: &StarkProofTarget<D>,
+        public_inputs: &[Target],
+        challenges: StarkProofChallengesTarget<D>,
+        ctl_vars: Option<&[CtlCheckVarsTarget<F, D>]>,
+        inner_config: &StarkConfig,
+        degree_bits: usize,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
+            builder,
+            self,
+            proof,
+            None,
+            public_inputs,
+            challenges,
+            ctl_vars,
+            inner_config,
+            degree_bits,
+            None,
+        )
+    }
+}
+
+/// A multi-STARK system, made of `N` STARK tables connected together
+/// through a list of [`CrossTableLookup`]s.
+///
+/// All tables taking part in a cross-table lookup must share the same
+/// constraint degree, which must be at least 3 to accommodate the
+/// cross-table lookup constraints.
+///
+/// Tables with preprocessed columns are not supported within a `MultiStark`.
+pub struct MultiStark<F, C, const D: usize, const N: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// The STARK tables of this system.
+    pub(crate) starks: [Box<dyn StarkTable<F, C, D>>; N],
+    /// The cross-table lookups connecting the tables of this system.
+    pub(crate) cross_table_lookups: Vec<CrossTableLookup<F>>,
+}
+
+impl<F, C, const D: usize, const N: usize> MultiStark<F, C, D, N>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    /// Creates a new `MultiStark` from the provided tables and cross-table lookups.
+    ///
+    /// The `i`-th table of `starks` is referred to by the index `i` in the
+    /// [`TableWithColumns`][crate::cross_table_lookup::TableWithColumns] of the
+    /// `cross_table_lookups`. Tables with preprocessed columns are not supported.
+    pub fn new(
+        starks: [Box<dyn StarkTable<F, C, D>>; N],
+        cross_table_lookups: Vec<CrossTableLookup<F>>,
+    ) -> Self {
+        let mut in_ctls = [false; N];
+        for ctl in &cross_table_lookups {
+            for twc in once(&ctl.looked_table).chain(&ctl.looking_tables) {
+                assert!(
+                    twc.table < N,
+                    "Cross-table lookup refers to table {} but there are only {} tables.",
+                    twc.table,
+                    N
+                );
+                in_ctls[twc.table] = true;
+            }
+        }
+        for (table, stark) in starks.iter().enumerate() {
+            assert_eq!(
+                in_ctls[table],
+                stark.requires_ctls(),
+                "Table {} should require CTLs if and only if it appears in a cross-table lookup.",
+                table
+            );
+            assert_eq!(
+                stark.num_preprocessed_columns(),
+                0,
+                "Table {} has preprocessed columns, which are not supported in multi-STARK systems.",
+                table
+            );
+        }
+
+        let res = Self {
+            starks,
+            cross_table_lookups,
+        };
+        let ctl_constraint_degree = res.ctl_constraint_degree();
+        assert!(
+            res.starks
+                .iter()
+                .filter(|stark| stark.requires_ctls())
+                .all(|stark| stark.constraint_degree() == ctl_constraint_degree),
+            "All tables taking part in cross-table lookups must have the same constraint degree."
+        );
+        assert!(
+            res.cross_table_lookups.is_empty() || ctl_constraint_degree >= 3,
+            "Tables taking part in cross-table lookups must have a constraint degree of at least 3."
+        );
+
+        res
+    }
+
+    /// Returns the STARK table at index `table`.
+    pub fn stark(&self, table: TableIdx) -> &dyn StarkTable<F, C, D> {
+        self.starks[table].as_ref()
+    }
+
+    /// Returns the cross-table lookups of this system.
+    pub fn cross_table_lookups(&self) -> &[CrossTableLookup<F>] {
+        &self.cross_table_lookups
+    }
+
+    /// Returns the constraint degree used to build cross-table lookup helper columns,
+    /// i.e. the common constraint degree of all tables taking part in CTLs.
+    pub(crate) fn ctl_constraint_degree(&self) -> usize {
+        self.starks
+            .iter()
+            .filter(|stark| stark.requires_ctls())
+            .map(|stark| stark.constraint_degree())
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Given a table, returns the total number of CTL helper columns, the total number of
+    /// CTL `Z` polynomials and the number of helper columns for each cross-table lookup.
+    pub(crate) fn num_ctl_helpers_zs(
+        &self,
+        table: TableIdx,
+        config: &StarkConfig,
+    ) -> (usize, usize, Vec<usize>) {
+        CrossTableLookup::num_ctl_helpers_zs_all(
+            &self.cross_table_lookups,
+            table,
+            config.num_challenges,
+            self.ctl_constraint_degree(),
+        )
+    }
+}
+
+impl<F, C, const D: usize, const N: usize> Debug for MultiStark<F, C, D, N>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MultiStark")
+            .field("num_tables", &N)
+            .field("cross_table_lookups", &self.cross_table_lookups)
+            .finish()
+    }
+}
@@ -111,6 +111,7 @@ mod tests {
     use plonky2::util::timing::TimingTree;
 
     use crate::config::StarkConfig;
+    use crate::debug_trace::{debug_trace, TraceFailure};
     use crate::permutation_stark::PermutationStark;
     use crate::proof::StarkProofWithPublicInputs;
     use crate::prover::prove;
@@ -148,6 +149,44 @@ mod tests {
         verify_stark_proof(stark, proof, &config, None)
     }
 
+    #[test]
+    fn test_permutation_stark_debug_trace() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PermutationStark<F, D>;
+
+        let num_rows = 1 << 5;
+        let public_input = F::ZERO;
+
+        let stark = S::new(num_rows);
+        let mut trace = stark.generate_trace(public_input);
+        assert!(debug_trace(&stark, &trace, &[public_input]).is_empty());
+
+        // Look up a value which is not part of the table.
+        let invalid_value = F::from_canonical_usize(2 * num_rows);
+        trace[0].values[3] = invalid_value;
+        assert_eq!(
+            debug_trace(&stark, &trace, &[public_input]),
+            [
+                TraceFailure::Lookup {
+                    lookup: 0,
+                    row: 2,
+                    value: F::from_canonical_usize(3),
+                    looking_count: F::ZERO,
+                    table_count: F::ONE,
+                },
+                TraceFailure::Lookup {
+                    lookup: 0,
+                    row: 3,
+                    value: invalid_value,
+                    looking_count: F::ONE,
+                    table_count: F::ZERO,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_permutation_stark_degree() -> Result<()> {
         const D: usize = 2;
@@ -199,6 +238,62 @@ mod tests {
         recursive_proof::<F, C, S, C, D>(stark, proof, &config, true)
     }
 
+    #[test]
+    fn test_recursive_stark_verifier_zk() -> Result<()> {
+        init_logger();
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PermutationStark<F, D>;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 8;
+        let public_input = F::ZERO;
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_input);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &[public_input],
+            None,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof.clone(), &config, None)?;
+
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false)
+    }
+
+    #[test]
+    fn test_permutation_stark_serialization_zk() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PermutationStark<F, D>;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 8;
+        let public_input = F::ZERO;
+
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_input);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &[public_input],
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        let bytes = proof.to_bytes();
+        let decoded =
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(bytes.clone(), &stark, &config)?;
+        assert_eq!(bytes, decoded.to_bytes());
+        verify_stark_proof(stark, decoded, &config, None)
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
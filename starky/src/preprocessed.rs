@@ -0,0 +1,104 @@
+//! Support for preprocessed columns, i.e. fixed columns of a [`Stark`] which do not
+//! depend on the witness, like selectors, round constants or lookup tables.
+//!
+//! Preprocessed columns are committed to once during a setup phase, yielding a
+//! [`StarkProvingKey`] for the prover and a [`StarkVerifyingKey`] for the verifier.
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::fri::oracle::PolynomialBatch;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::timed;
+use plonky2::util::log2_strict;
+use plonky2::util::timing::TimingTree;
+use serde::{Deserialize, Serialize};
+
+use crate::config::StarkConfig;
+use crate::prover::commit_to_columns;
+use crate::stark::Stark;
+
+/// Data used by the prover of a [`Stark`] with preprocessed columns.
+#[derive(Debug)]
+pub struct StarkProvingKey<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    /// Commitment to the LDEs of the preprocessed columns.
+    pub preprocessed_commitment: PolynomialBatch<F, C, D>,
+    /// The log of the length of the preprocessed columns.
+    pub degree_bits: usize,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProvingKey<F, C, D>
+{
+    /// Returns the [`StarkVerifyingKey`] associated to this proving key.
+    pub fn verifying_key(&self) -> StarkVerifyingKey<F, C, D> {
+        StarkVerifyingKey {
+            preprocessed_cap: self.preprocessed_commitment.merkle_tree.cap.clone(),
+            degree_bits: self.degree_bits,
+        }
+    }
+}
+
+/// Data used by the verifier of a [`Stark`] with preprocessed columns.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(bound = "")]
+pub struct StarkVerifyingKey<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// Merkle cap of LDEs of preprocessed values.
+    pub preprocessed_cap: MerkleCap<F, C::Hasher>,
+    /// The log of the length of the preprocessed columns, which must match the trace length.
+    pub degree_bits: usize,
+}
+
+/// Commits to the preprocessed columns of the provided [`Stark`].
+///
+/// The resulting [`StarkProvingKey`] can be reused across all proofs of traces of the
+/// same length.
+pub fn setup<F, C, S, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> Result<StarkProvingKey<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    let preprocessed_columns = stark.generate_preprocessed_columns();
+    ensure!(
+        S::PREPROCESSED_COLUMNS > 0,
+        "This STARK has no preprocessed columns."
+    );
+    ensure!(
+        preprocessed_columns.len() == S::PREPROCESSED_COLUMNS,
+        "Invalid number of preprocessed columns."
+    );
+    let degree = preprocessed_columns[0].len();
+    ensure!(
+        degree.is_power_of_two() && preprocessed_columns.iter().all(|c| c.len() == degree),
+        "Preprocessed columns must all have the same power-of-two length."
+    );
+    let degree_bits = log2_strict(degree);
+    let fri_params = config.fri_params(degree_bits);
+    ensure!(
+        fri_params.total_arities() <= fri_params.lde_bits() - config.fri_config.cap_height,
+        "FRI total reduction arity is too large."
+    );
+
+    // Preprocessed columns are public, so they do not need to be blinded.
+    let preprocessed_commitment = timed!(
+        timing,
+        "compute preprocessed commitment",
+        commit_to_columns::<F, C, D>(preprocessed_columns, false, config, timing)
+    );
+
+    Ok(StarkProvingKey {
+        preprocessed_commitment,
+        degree_bits,
+    })
+}
@@ -0,0 +1,374 @@
+//! An example of generating and verifying STARK proofs for a STARK with preprocessed
+//! columns, computing the factorials of all integers up to the trace length.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::evaluation_frame::{StarkEvaluationFrame, StarkPreprocessedFrame};
+use crate::stark::Stark;
+use crate::util::trace_rows_to_poly_values;
+
+/// Toy STARK system used for testing.
+/// Computes the sequence of factorials `x` using the state transition `x' <- x * c'`,
+/// where `c` is a preprocessed column holding the row index. An additional column
+/// `y` is constrained to be equal to `x * c` on every row.
+#[derive(Copy, Clone)]
+struct FactorialStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FactorialStark<F, D> {
+    // The first public input is `x0`.
+    const PI_INDEX_X0: usize = 0;
+    // The second public input is the value of `x` in the last row, which should be equal
+    // to the factorial of `num_rows - 1`.
+    const PI_INDEX_RES: usize = 1;
+
+    const fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace using `x0` as initial state value.
+    fn generate_trace(&self, x0: F) -> Vec<PolynomialValues<F>> {
+        let trace_rows = (0..self.num_rows)
+            .scan(x0, |x, i| {
+                let c = F::from_canonical_usize(i);
+                if i > 0 {
+                    *x *= c;
+                }
+                Some([*x, *x * c])
+            })
+            .collect::<Vec<_>>();
+        trace_rows_to_poly_values(trace_rows)
+    }
+}
+
+const FACTORIAL_COLUMNS: usize = 2;
+const FACTORIAL_PUBLIC_INPUTS: usize = 2;
+const FACTORIAL_PREPROCESSED_COLUMNS: usize = 1;
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FactorialStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize>
+        = StarkPreprocessedFrame<
+        P,
+        P::Scalar,
+        FACTORIAL_COLUMNS,
+        FACTORIAL_PUBLIC_INPUTS,
+        FACTORIAL_PREPROCESSED_COLUMNS,
+    >
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    type EvaluationFrameTarget = StarkPreprocessedFrame<
+        ExtensionTarget<D>,
+        ExtensionTarget<D>,
+        FACTORIAL_COLUMNS,
+        FACTORIAL_PUBLIC_INPUTS,
+        FACTORIAL_PREPROCESSED_COLUMNS,
+    >;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let local_preprocessed = vars.get_local_preprocessed_values();
+        let next_preprocessed = vars.get_next_preprocessed_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Check public inputs.
+        yield_constr.constraint_first_row(local_values[0] - public_inputs[Self::PI_INDEX_X0]);
+        yield_constr.constraint_last_row(local_values[0] - public_inputs[Self::PI_INDEX_RES]);
+
+        // x' <- x * c'
+        yield_constr.constraint_transition(local_values[0] * next_preprocessed[0] - next_values[0]);
+        // y = x * c
+        yield_constr.constraint(local_values[0] * local_preprocessed[0] - local_values[1]);
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let local_preprocessed = vars.get_local_preprocessed_values();
+        let next_preprocessed = vars.get_next_preprocessed_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Check public inputs.
+        let pis_constraints = [
+            builder.sub_extension(local_values[0], public_inputs[Self::PI_INDEX_X0]),
+            builder.sub_extension(local_values[0], public_inputs[Self::PI_INDEX_RES]),
+        ];
+        yield_constr.constraint_first_row(builder, pis_constraints[0]);
+        yield_constr.constraint_last_row(builder, pis_constraints[1]);
+
+        // x' <- x * c'
+        let transition_constraint =
+            builder.mul_sub_extension(local_values[0], next_preprocessed[0], next_values[0]);
+        yield_constr.constraint_transition(builder, transition_constraint);
+        // y = x * c
+        let y_constraint =
+            builder.mul_sub_extension(local_values[0], local_preprocessed[0], local_values[1]);
+        yield_constr.constraint(builder, y_constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        2
+    }
+
+    fn generate_preprocessed_columns(&self) -> Vec<PolynomialValues<F>> {
+        vec![PolynomialValues::new(
+            (0..self.num_rows).map(F::from_canonical_usize).collect(),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "std"))]
+    use alloc::{boxed::Box, vec};
+
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::multi_stark::MultiStark;
+    use crate::preprocessed::setup;
+    use crate::preprocessed_stark::FactorialStark;
+    use crate::proof::StarkProofWithPublicInputs;
+    use crate::prover::{prove, prove_with_preprocessed};
+    use crate::recursive_verifier::{
+        add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
+        verify_stark_proof_with_preprocessed_circuit,
+    };
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::verifier::verify_stark_proof_with_preprocessed;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = FactorialStark<F, D>;
+
+    fn factorial(n: usize) -> F {
+        (1..=n).map(F::from_canonical_usize).product()
+    }
+
+    #[test]
+    fn test_preprocessed_stark() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ONE, factorial(num_rows - 1)];
+
+        let stark = S::new(num_rows);
+        let proving_key = setup::<F, C, S, D>(&stark, &config, &mut TimingTree::default())?;
+        let trace = stark.generate_trace(public_inputs[0]);
+        let proof = prove_with_preprocessed::<F, C, S, D>(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof_with_preprocessed(
+            stark,
+            &proving_key.verifying_key(),
+            proof,
+            &config,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_preprocessed_stark_zk() -> Result<()> {
+        let config = StarkConfig::standard_fast_zk_config();
+        let num_rows = 1 << 9;
+        let public_inputs = [F::ONE, factorial(num_rows - 1)];
+
+        let stark = S::new(num_rows);
+        let proving_key = setup::<F, C, S, D>(&stark, &config, &mut TimingTree::default())?;
+        assert_eq!(proving_key.verifying_key().degree_bits, 9);
+        let trace = stark.generate_trace(public_inputs[0]);
+        let proof = prove_with_preprocessed::<F, C, S, D>(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        verify_stark_proof_with_preprocessed(
+            stark,
+            &proving_key.verifying_key(),
+            proof,
+            &config,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_preprocessed_stark_serialization() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ONE, factorial(num_rows - 1)];
+
+        let stark = S::new(num_rows);
+        let proving_key = setup::<F, C, S, D>(&stark, &config, &mut TimingTree::default())?;
+        let trace = stark.generate_trace(public_inputs[0]);
+        let proof = prove_with_preprocessed::<F, C, S, D>(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        let bytes = proof.to_bytes();
+        let decoded =
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(bytes.clone(), &stark, &config)?;
+        assert_eq!(bytes, decoded.to_bytes());
+        verify_stark_proof_with_preprocessed(
+            stark,
+            &proving_key.verifying_key(),
+            decoded,
+            &config,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_preprocessed_stark_invalid_key() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ONE, factorial(num_rows - 1)];
+
+        let stark = S::new(num_rows);
+        let proving_key = setup::<F, C, S, D>(&stark, &config, &mut TimingTree::default())?;
+        let trace = stark.generate_trace(public_inputs[0]);
+        let proof = prove_with_preprocessed::<F, C, S, D>(
+            stark,
+            &proving_key,
+            &config,
+            trace.clone(),
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        let mut verifying_key = proving_key.verifying_key();
+        verifying_key.preprocessed_cap.0.reverse();
+        assert!(
+            verify_stark_proof_with_preprocessed(stark, &verifying_key, proof, &config, None)
+                .is_err()
+        );
+
+        // Proving without the preprocessed commitment must fail.
+        assert!(prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "has preprocessed columns")]
+    fn test_preprocessed_stark_in_multi_stark() {
+        let _ = MultiStark::<F, C, D, 1>::new([Box::new(S::new(1 << 5))], vec![]);
+    }
+
+    #[test]
+    fn test_preprocessed_stark_degree() -> Result<()> {
+        let num_rows = 1 << 5;
+        let stark = S::new(num_rows);
+        test_stark_low_degree(stark)
+    }
+
+    #[test]
+    fn test_preprocessed_stark_circuit() -> Result<()> {
+        let num_rows = 1 << 5;
+        let stark = S::new(num_rows);
+        test_stark_circuit_constraints::<F, C, S, D>(stark)
+    }
+
+    #[test]
+    fn test_recursive_preprocessed_stark_verifier() -> Result<()> {
+        let config = StarkConfig::standard_fast_config();
+        let degree_bits = 5;
+        let num_rows = 1 << degree_bits;
+        let public_inputs = [F::ONE, factorial(num_rows - 1)];
+
+        let stark = S::new(num_rows);
+        let proving_key = setup::<F, C, S, D>(&stark, &config, &mut TimingTree::default())?;
+        let trace = stark.generate_trace(public_inputs[0]);
+        let proof = prove_with_preprocessed::<F, C, S, D>(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &public_inputs,
+            None,
+            &mut TimingTree::default(),
+        )?;
+
+        let circuit_config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
+        let mut pw = PartialWitness::new();
+        let preprocessed_cap =
+            builder.constant_merkle_cap(&proving_key.verifying_key().preprocessed_cap);
+        let pt = add_virtual_stark_proof_with_pis(&mut builder, &stark, &config, degree_bits, 0, 0);
+        set_stark_proof_with_pis_target(&mut pw, &pt, &proof, degree_bits, builder.zero())?;
+
+        verify_stark_proof_with_preprocessed_circuit::<F, C, S, D>(
+            &mut builder,
+            stark,
+            &preprocessed_cap,
+            pt,
+            &config,
+            None,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
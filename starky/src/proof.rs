@@ -5,24 +5,32 @@
 #[cfg(not(feature = "std"))]
 use alloc::{vec, vec::Vec};
 
+use anyhow::ensure;
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::polynomial::PolynomialCoeffs;
 use plonky2::fri::oracle::PolynomialBatch;
-use plonky2::fri::proof::{FriChallenges, FriChallengesTarget, FriProof, FriProofTarget};
+use plonky2::fri::proof::{
+    FriChallenges, FriChallengesTarget, FriInitialTreeProof, FriProof, FriProofTarget,
+    FriQueryRound, FriQueryStep,
+};
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
+use plonky2::hash::merkle_proofs::MerkleProof;
 use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::config::{GenericConfig, Hasher};
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::util::serialization::{Buffer, IoError, IoResult, Read, Write};
 use plonky2_maybe_rayon::*;
 use serde::{Deserialize, Serialize};
 
 use crate::config::StarkConfig;
 use crate::lookup::GrandProductChallengeSet;
+use crate::stark::Stark;
+use crate::validate_shape::{fri_oracle_leaf_lengths, fri_params_from_lde_bits};
 
 /// Merkle caps and openings that form the proof of a single STARK.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -48,8 +56,129 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> S
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - config.zero_knowledge as usize
+    }
+
+    /// Serializes a STARK proof.
+    ///
+    /// The encoding only contains the data which cannot be inferred from the
+    /// [`Stark`] and [`StarkConfig`] used to generate the proof, along with
+    /// the log of the size of the LDE domain, from which the length of the
+    /// trace is recovered when deserializing.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        let initial_merkle_proof = &self
+            .opening_proof
+            .query_round_proofs
+            .first()
+            .ok_or(IoError)?
+            .initial_trees_proof
+            .evals_proofs
+            .first()
+            .ok_or(IoError)?
+            .1;
+        buffer.write_usize(self.trace_cap.height() + initial_merkle_proof.siblings.len())?;
+        buffer.write_merkle_cap(&self.trace_cap)?;
+        if let Some(cap) = &self.auxiliary_polys_cap {
+            buffer.write_merkle_cap(cap)?;
+        }
+        if let Some(cap) = &self.quotient_polys_cap {
+            buffer.write_merkle_cap(cap)?;
+        }
+        self.openings.to_buffer(buffer)?;
+        buffer.write_fri_proof::<F, C, D>(&self.opening_proof)
+    }
+
+    /// Deserializes a STARK proof for the provided [`Stark`], with `num_ctl_helpers`
+    /// cross-table lookup helper columns and `num_ctl_zs` cross-table lookup `Z` polynomials.
+    ///
+    /// Fails if the data does not describe a proof with the shape expected by the verifier.
+    pub fn from_buffer<S: Stark<F, D>>(
+        buffer: &mut Buffer,
+        stark: &S,
+        config: &StarkConfig,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+    ) -> IoResult<Self> {
+        let cap_height = config.fri_config.cap_height;
+        let lde_bits = buffer.read_usize()?;
+        let fri_params = fri_params_from_lde_bits::<F>(lde_bits, config).map_err(|_| IoError)?;
+
+        let trace_cap = buffer.read_merkle_cap(cap_height)?;
+        let auxiliary_polys_cap = (stark.uses_lookups() || stark.requires_ctls())
+            .then(|| buffer.read_merkle_cap(cap_height))
+            .transpose()?;
+        let quotient_polys_cap = (stark.num_quotient_polys(config) > 0)
+            .then(|| buffer.read_merkle_cap(cap_height))
+            .transpose()?;
+        let openings =
+            StarkOpeningSet::from_buffer(buffer, stark, config, num_ctl_helpers, num_ctl_zs)?;
+
+        let leaf_lengths =
+            fri_oracle_leaf_lengths(stark, config, &fri_params, num_ctl_helpers, num_ctl_zs);
+
+        let commit_phase_merkle_caps = (0..fri_params.reduction_arity_bits.len())
+            .map(|_| buffer.read_merkle_cap(cap_height))
+            .collect::<IoResult<Vec<_>>>()?;
+        let query_round_proofs = (0..fri_params.config.num_query_rounds)
+            .map(|_| {
+                let evals_proofs = leaf_lengths
+                    .iter()
+                    .map(|&leaf_length| {
+                        let leaf = buffer.read_field_vec(leaf_length)?;
+                        let merkle_proof = read_merkle_proof(buffer, lde_bits - cap_height)?;
+                        Ok((leaf, merkle_proof))
+                    })
+                    .collect::<IoResult<Vec<_>>>()?;
+                let mut codeword_len_bits = lde_bits;
+                let steps = fri_params
+                    .reduction_arity_bits
+                    .iter()
+                    .map(|&arity_bits| {
+                        codeword_len_bits -= arity_bits;
+                        let evals = buffer.read_field_ext_vec::<F, D>(1 << arity_bits)?;
+                        let merkle_proof =
+                            read_merkle_proof(buffer, codeword_len_bits - cap_height)?;
+                        Ok(FriQueryStep {
+                            evals,
+                            merkle_proof,
+                        })
+                    })
+                    .collect::<IoResult<Vec<_>>>()?;
+                Ok(FriQueryRound {
+                    initial_trees_proof: FriInitialTreeProof { evals_proofs },
+                    steps,
+                })
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        let final_poly =
+            PolynomialCoeffs::new(buffer.read_field_ext_vec::<F, D>(fri_params.final_poly_len())?);
+        let pow_witness = buffer.read_field()?;
+
+        Ok(Self {
+            trace_cap,
+            auxiliary_polys_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: FriProof {
+                commit_phase_merkle_caps,
+                query_round_proofs,
+                final_poly,
+                pow_witness,
+            },
+        })
+    }
+}
+
+/// Reads a Merkle proof from `buffer`, failing if it doesn't have `expected_len` siblings.
+fn read_merkle_proof<F: RichField, H: Hasher<F>>(
+    buffer: &mut Buffer,
+    expected_len: usize,
+) -> IoResult<MerkleProof<F, H>> {
+    let merkle_proof: MerkleProof<F, H> = buffer.read_merkle_proof()?;
+    if merkle_proof.siblings.len() != expected_len {
+        return Err(IoError);
     }
+    Ok(merkle_proof)
 }
 
 /// Circuit version of [`StarkProof`].
@@ -122,7 +251,7 @@ impl<const D: usize> StarkProofTarget<D> {
             .evals_proofs[0]
             .1;
         let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+        lde_bits - config.fri_config.rate_bits - config.zero_knowledge as usize
     }
 }
 
@@ -141,6 +270,58 @@ pub struct StarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProofWithPublicInputs<F, C, D>
+{
+    /// Serializes a STARK proof along with its public inputs.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        self.proof.to_buffer(buffer)?;
+        buffer.write_field_vec(&self.public_inputs)
+    }
+
+    /// Deserializes a STARK proof along with its public inputs for the provided [`Stark`].
+    /// See [`StarkProof::from_buffer`].
+    pub fn from_buffer<S: Stark<F, D>>(
+        buffer: &mut Buffer,
+        stark: &S,
+        config: &StarkConfig,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+    ) -> IoResult<Self> {
+        let proof = StarkProof::from_buffer(buffer, stark, config, num_ctl_helpers, num_ctl_zs)?;
+        let public_inputs = buffer.read_field_vec(S::PUBLIC_INPUTS)?;
+        Ok(Self {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Serializes a STARK proof along with its public inputs into a byte vector.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.to_buffer(&mut buffer)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Deserializes a proof of the provided [`Stark`], which must not be part of
+    /// a multi-STARK system. The bytes must be entirely consumed.
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(&bytes);
+        let proof =
+            Self::from_buffer(&mut buffer, stark, config, 0, 0).map_err(anyhow::Error::msg)?;
+        ensure!(
+            buffer.unread_bytes().is_empty(),
+            "Trailing bytes after STARK proof."
+        );
+        Ok(proof)
+    }
+}
+
 /// Circuit version of [`StarkProofWithPublicInputs`].
 #[derive(Debug, Clone)]
 pub struct StarkProofWithPublicInputsTarget<const D: usize> {
@@ -150,6 +331,36 @@ pub struct StarkProofWithPublicInputsTarget<const D: usize> {
     pub public_inputs: Vec<Target>,
 }
 
+/// Proofs for all the STARK tables of a multi-STARK system, which share the
+/// same cross-table lookup challenges.
+#[derive(Debug, Clone)]
+pub struct MultiStarkProof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+    const N: usize,
+> {
+    /// Proofs, along with their public inputs, of each STARK table.
+    pub stark_proofs: [StarkProofWithPublicInputs<F, C, D>; N],
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize, const N: usize>
+    MultiStarkProof<F, C, D, N>
+{
+    /// Recover the length of the trace of each STARK table from a multi-STARK proof
+    /// and a STARK config.
+    pub fn recover_degree_bits(&self, config: &StarkConfig) -> [usize; N] {
+        core::array::from_fn(|i| self.stark_proofs[i].proof.recover_degree_bits(config))
+    }
+}
+
+/// Circuit version of [`MultiStarkProof`].
+#[derive(Debug, Clone)]
+pub struct MultiStarkProofTarget<const D: usize, const N: usize> {
+    /// `Target` proofs, along with their public inputs, of each STARK table.
+    pub stark_proofs: [StarkProofWithPublicInputsTarget<D>; N],
+}
+
 /// A [`StarkProof`] along with metadata about the initial Fiat-Shamir state, which is used when
 /// creating a recursive wrapper proof around a STARK proof.
 #[derive(Debug, Clone)]
@@ -190,7 +401,7 @@ pub struct StarkProofChallengesTarget<const D: usize> {
     pub fri_challenges: FriChallengesTarget<D>,
 }
 
-/// Randomness for all STARK proofs contained in a [`MultiProof`]`.
+/// Randomness for all STARK proofs contained in a [`MultiStarkProof`].
 #[derive(Debug)]
 pub struct MultiProofChallenges<F: RichField + Extendable<D>, const D: usize, const N: usize> {
     /// Randomness used in each STARK proof.
@@ -199,6 +410,15 @@ pub struct MultiProofChallenges<F: RichField + Extendable<D>, const D: usize, co
     pub ctl_challenges: GrandProductChallengeSet<F>,
 }
 
+/// Circuit version of [`MultiProofChallenges`].
+#[derive(Debug)]
+pub struct MultiProofChallengesTarget<const D: usize, const N: usize> {
+    /// `Target`s for the randomness used in each STARK proof.
+    pub stark_challenges: [StarkProofChallengesTarget<D>; N],
+    /// `Target`s for the randomness used for cross-table lookups. It is shared by all STARKs.
+    pub ctl_challenges: GrandProductChallengeSet<Target>,
+}
+
 /// Purported values of each polynomial at the challenge point.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(bound = "")]
@@ -207,6 +427,10 @@ pub struct StarkOpeningSet<F: RichField + Extendable<D>, const D: usize> {
     pub local_values: Vec<F::Extension>,
     /// Openings of trace polynomials at `g * zeta`.
     pub next_values: Vec<F::Extension>,
+    /// Openings of preprocessed polynomials at `zeta`.
+    pub preprocessed_values: Option<Vec<F::Extension>>,
+    /// Openings of preprocessed polynomials at `g * zeta`.
+    pub preprocessed_next_values: Option<Vec<F::Extension>>,
     /// Openings of lookups and cross-table lookups `Z` polynomials at `zeta`.
     pub auxiliary_polys: Option<Vec<F::Extension>>,
     /// Openings of lookups and cross-table lookups `Z` polynomials at `g * zeta`.
@@ -228,6 +452,7 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
         trace_commitment: &PolynomialBatch<F, C, D>,
         auxiliary_polys_commitment: Option<&PolynomialBatch<F, C, D>>,
         quotient_commitment: Option<&PolynomialBatch<F, C, D>>,
+        preprocessed_commitment: Option<&PolynomialBatch<F, C, D>>,
         num_lookup_columns: usize,
         requires_ctl: bool,
         num_ctl_polys: &[usize],
@@ -253,6 +478,9 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
         Self {
             local_values: eval_commitment(zeta, trace_commitment),
             next_values: eval_commitment(zeta_next, trace_commitment),
+            preprocessed_values: preprocessed_commitment.map(|c| eval_commitment(zeta, c)),
+            preprocessed_next_values: preprocessed_commitment
+                .map(|c| eval_commitment(zeta_next, c)),
             auxiliary_polys: auxiliary_polys_commitment.map(|c| eval_commitment(zeta, c)),
             auxiliary_polys_next: auxiliary_polys_commitment.map(|c| eval_commitment(zeta_next, c)),
             ctl_zs_first: requires_ctl.then(|| {
@@ -263,6 +491,81 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
         }
     }
 
+    /// Serializes a STARK's opening set.
+    ///
+    /// Optional openings are written without any presence flag, as they can be
+    /// inferred from the [`Stark`] when deserializing.
+    pub fn to_buffer(&self, buffer: &mut Vec<u8>) -> IoResult<()> {
+        buffer.write_field_ext_vec::<F, D>(&self.local_values)?;
+        buffer.write_field_ext_vec::<F, D>(&self.next_values)?;
+        for values in [
+            &self.preprocessed_values,
+            &self.preprocessed_next_values,
+            &self.auxiliary_polys,
+            &self.auxiliary_polys_next,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            buffer.write_field_ext_vec::<F, D>(values)?;
+        }
+        if let Some(ctl_zs_first) = &self.ctl_zs_first {
+            buffer.write_field_vec(ctl_zs_first)?;
+        }
+        if let Some(quotient_polys) = &self.quotient_polys {
+            buffer.write_field_ext_vec::<F, D>(quotient_polys)?;
+        }
+        Ok(())
+    }
+
+    /// Deserializes a STARK's opening set for the provided [`Stark`], with `num_ctl_helpers`
+    /// cross-table lookup helper columns and `num_ctl_zs` cross-table lookup `Z` polynomials.
+    pub fn from_buffer<S: Stark<F, D>>(
+        buffer: &mut Buffer,
+        stark: &S,
+        config: &StarkConfig,
+        num_ctl_helpers: usize,
+        num_ctl_zs: usize,
+    ) -> IoResult<Self> {
+        let num_preprocessed = (S::PREPROCESSED_COLUMNS > 0).then_some(S::PREPROCESSED_COLUMNS);
+        let num_auxiliary = (stark.uses_lookups() || stark.requires_ctls())
+            .then(|| stark.num_lookup_helper_columns(config) + num_ctl_helpers + num_ctl_zs);
+        let num_quotient_polys = stark.num_quotient_polys(config);
+
+        let local_values = buffer.read_field_ext_vec::<F, D>(S::COLUMNS)?;
+        let next_values = buffer.read_field_ext_vec::<F, D>(S::COLUMNS)?;
+        let preprocessed_values = num_preprocessed
+            .map(|n| buffer.read_field_ext_vec::<F, D>(n))
+            .transpose()?;
+        let preprocessed_next_values = num_preprocessed
+            .map(|n| buffer.read_field_ext_vec::<F, D>(n))
+            .transpose()?;
+        let auxiliary_polys = num_auxiliary
+            .map(|n| buffer.read_field_ext_vec::<F, D>(n))
+            .transpose()?;
+        let auxiliary_polys_next = num_auxiliary
+            .map(|n| buffer.read_field_ext_vec::<F, D>(n))
+            .transpose()?;
+        let ctl_zs_first = stark
+            .requires_ctls()
+            .then(|| buffer.read_field_vec(num_ctl_zs))
+            .transpose()?;
+        let quotient_polys = (num_quotient_polys > 0)
+            .then(|| buffer.read_field_ext_vec::<F, D>(num_quotient_polys))
+            .transpose()?;
+
+        Ok(Self {
+            local_values,
+            next_values,
+            preprocessed_values,
+            preprocessed_next_values,
+            auxiliary_polys,
+            auxiliary_polys_next,
+            ctl_zs_first,
+            quotient_polys,
+        })
+    }
+
     /// Constructs the openings required by FRI.
     /// All openings but `ctl_zs_first` are grouped together.
     pub(crate) fn to_fri_openings(&self) -> FriOpenings<F, D> {
@@ -272,6 +575,7 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
                 .iter()
                 .chain(self.auxiliary_polys.iter().flatten())
                 .chain(self.quotient_polys.iter().flatten())
+                .chain(self.preprocessed_values.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
@@ -280,6 +584,7 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
                 .next_values
                 .iter()
                 .chain(self.auxiliary_polys_next.iter().flatten())
+                .chain(self.preprocessed_next_values.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
@@ -314,6 +619,10 @@ pub struct StarkOpeningSetTarget<const D: usize> {
     pub local_values: Vec<ExtensionTarget<D>>,
     /// `ExtensionTarget`s for the opening of trace polynomials at `g * zeta`.
     pub next_values: Vec<ExtensionTarget<D>>,
+    /// `ExtensionTarget`s for the opening of preprocessed polynomials at `zeta`.
+    pub preprocessed_values: Option<Vec<ExtensionTarget<D>>>,
+    /// `ExtensionTarget`s for the opening of preprocessed polynomials at `g * zeta`.
+    pub preprocessed_next_values: Option<Vec<ExtensionTarget<D>>>,
     /// `ExtensionTarget`s for the opening of lookups and cross-table lookups `Z` polynomials at `zeta`.
     pub auxiliary_polys: Option<Vec<ExtensionTarget<D>>>,
     /// `ExtensionTarget`s for the opening of lookups and cross-table lookups `Z` polynomials at `g * zeta`.
@@ -351,6 +660,14 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
         if let Some(quotient_polys) = &self.quotient_polys {
             buffer.write_target_ext_vec(quotient_polys)?;
         }
+        buffer.write_bool(self.preprocessed_values.is_some())?;
+        if let Some(preprocessed_values) = &self.preprocessed_values {
+            buffer.write_target_ext_vec(preprocessed_values)?;
+        }
+        buffer.write_bool(self.preprocessed_next_values.is_some())?;
+        if let Some(preprocessed_next_values) = &self.preprocessed_next_values {
+            buffer.write_target_ext_vec(preprocessed_next_values)?;
+        }
         Ok(())
     }
 
@@ -378,10 +695,22 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
         } else {
             None
         };
+        let preprocessed_values = if buffer.read_bool()? {
+            Some(buffer.read_target_ext_vec::<D>()?)
+        } else {
+            None
+        };
+        let preprocessed_next_values = if buffer.read_bool()? {
+            Some(buffer.read_target_ext_vec::<D>()?)
+        } else {
+            None
+        };
 
         Ok(Self {
             local_values,
             next_values,
+            preprocessed_values,
+            preprocessed_next_values,
             auxiliary_polys,
             auxiliary_polys_next,
             ctl_zs_first,
@@ -397,6 +726,7 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
                 .iter()
                 .chain(self.auxiliary_polys.iter().flatten())
                 .chain(self.quotient_polys.iter().flatten())
+                .chain(self.preprocessed_values.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
@@ -405,6 +735,7 @@ impl<const D: usize> StarkOpeningSetTarget<D> {
                 .next_values
                 .iter()
                 .chain(self.auxiliary_polys_next.iter().flatten())
+                .chain(self.preprocessed_next_values.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
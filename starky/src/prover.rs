@@ -26,13 +26,15 @@ use plonky2_maybe_rayon::*;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
-use crate::cross_table_lookup::{get_ctl_auxiliary_polys, CtlCheckVars, CtlData};
+use crate::cross_table_lookup::{get_ctl_auxiliary_polys, get_ctl_data, CtlCheckVars, CtlData};
 use crate::evaluation_frame::StarkEvaluationFrame;
 use crate::lookup::{
     get_grand_product_challenge_set, lookup_helper_columns, GrandProductChallengeSet, Lookup,
     LookupCheckVars,
 };
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
+use crate::multi_stark::MultiStark;
+use crate::preprocessed::StarkProvingKey;
+use crate::proof::{MultiStarkProof, StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 
@@ -45,6 +47,58 @@ pub fn prove<F, C, S, const D: usize>(
     verifier_circuit_fri_params: Option<FriParams>,
     timing: &mut TimingTree,
 ) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    prove_with_optional_preprocessed(
+        stark,
+        None,
+        config,
+        trace_poly_values,
+        public_inputs,
+        verifier_circuit_fri_params,
+        timing,
+    )
+}
+
+/// From a STARK trace and the [`StarkProvingKey`] of its preprocessed columns, computes
+/// a STARK proof to attest its correctness.
+pub fn prove_with_preprocessed<F, C, S, const D: usize>(
+    stark: S,
+    proving_key: &StarkProvingKey<F, C, D>,
+    config: &StarkConfig,
+    trace_poly_values: Vec<PolynomialValues<F>>,
+    public_inputs: &[F],
+    verifier_circuit_fri_params: Option<FriParams>,
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    prove_with_optional_preprocessed(
+        stark,
+        Some(&proving_key.preprocessed_commitment),
+        config,
+        trace_poly_values,
+        public_inputs,
+        verifier_circuit_fri_params,
+        timing,
+    )
+}
+
+fn prove_with_optional_preprocessed<F, C, S, const D: usize>(
+    stark: S,
+    preprocessed_commitment: Option<&PolynomialBatch<F, C, D>>,
+    config: &StarkConfig,
+    trace_poly_values: Vec<PolynomialValues<F>>,
+    public_inputs: &[F],
+    verifier_circuit_fri_params: Option<FriParams>,
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -53,12 +107,12 @@ where
     let degree = trace_poly_values[0].len();
     let degree_bits = log2_strict(degree);
     let fri_params = config.fri_params(degree_bits);
-    let rate_bits = config.fri_config.rate_bits;
     let cap_height = config.fri_config.cap_height;
     assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
+        fri_params.total_arities() <= fri_params.lde_bits() - cap_height,
         "FRI total reduction arity is too large.",
     );
+    check_blinding_degree::<D>(stark.constraint_degree(), degree, config)?;
     let (final_poly_coeff_len, max_num_query_steps) =
         if let Some(verifier_circuit_fri_params) = verifier_circuit_fri_params {
             assert_eq!(verifier_circuit_fri_params.config, fri_params.config);
@@ -83,18 +137,14 @@ where
     let trace_commitment = timed!(
         timing,
         "compute trace commitment",
-        PolynomialBatch::<F, C, D>::from_values(
-            trace_poly_values.clone(),
-            rate_bits,
-            false,
-            cap_height,
-            timing,
-            None,
-        )
+        commit_to_columns::<F, C, D>(trace_poly_values.clone(), true, config, timing)
     );
 
     let trace_cap = trace_commitment.merkle_tree.cap.clone();
     let mut challenger = Challenger::new();
+    if let Some(commitment) = preprocessed_commitment {
+        challenger.observe_cap(&commitment.merkle_tree.cap);
+    }
     challenger.observe_elements(public_inputs);
     challenger.observe_cap(&trace_cap);
     prove_with_commitment(
@@ -102,6 +152,7 @@ where
         config,
         &trace_poly_values,
         &trace_commitment,
+        preprocessed_commitment,
         None,
         None,
         &mut challenger,
@@ -112,6 +163,91 @@ where
     )
 }
 
+/// From the traces of all the tables of a [`MultiStark`], computes a [`MultiStarkProof`]
+/// attesting to their correctness, as well as to the validity of all the cross-table
+/// lookups connecting them.
+pub fn prove_multi<F, C, const D: usize, const N: usize>(
+    multi_stark: &MultiStark<F, C, D, N>,
+    config: &StarkConfig,
+    trace_poly_values: [Vec<PolynomialValues<F>>; N],
+    public_inputs: &[Vec<F>; N],
+    timing: &mut TimingTree,
+) -> Result<MultiStarkProof<F, C, D, N>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    for (table, trace) in trace_poly_values.iter().enumerate() {
+        let stark = multi_stark.stark(table);
+        ensure!(
+            trace.len() == stark.num_columns(),
+            "Invalid number of trace columns for table {}.",
+            table
+        );
+        ensure!(
+            public_inputs[table].len() == stark.num_public_inputs(),
+            "Invalid number of public inputs for table {}.",
+            table
+        );
+        check_blinding_degree::<D>(stark.constraint_degree(), trace[0].len(), config)?;
+    }
+
+    let trace_commitments = timed!(
+        timing,
+        "compute all trace commitments",
+        trace_poly_values
+            .iter()
+            .map(|trace| {
+                timed!(
+                    timing,
+                    "compute trace commitment",
+                    commit_to_columns::<F, C, D>(trace.clone(), true, config, timing)
+                )
+            })
+            .collect::<Vec<_>>()
+    );
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for (pis, commitment) in public_inputs.iter().zip(&trace_commitments) {
+        challenger.observe_elements(pis);
+        challenger.observe_cap(&commitment.merkle_tree.cap);
+    }
+
+    let (ctl_challenges, ctl_data_per_table) = timed!(
+        timing,
+        "compute CTL data",
+        get_ctl_data::<F, C, D, N>(
+            config,
+            &trace_poly_values,
+            multi_stark.cross_table_lookups(),
+            &mut challenger,
+            multi_stark.ctl_constraint_degree(),
+        )
+    );
+
+    let stark_proofs = (0..N)
+        .map(|table| {
+            let stark = multi_stark.stark(table);
+            stark.prove_with_commitment(
+                config,
+                &trace_poly_values[table],
+                &trace_commitments[table],
+                stark.requires_ctls().then_some(&ctl_data_per_table[table]),
+                Some(&ctl_challenges),
+                &mut challenger,
+                &public_inputs[table],
+                timing,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(MultiStarkProof {
+        stark_proofs: stark_proofs
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("There is exactly one proof per table.")),
+    })
+}
+
 /// Generates a proof for a single STARK table, including:
 ///
 /// - the initial state of the challenger,
@@ -119,11 +255,16 @@ where
 /// - all the required polynomial and FRI argument openings.
 /// - individual `ctl_data` and common `ctl_challenges` if the STARK is part
 ///   of a multi-STARK system.
+///
+/// The `preprocessed_commitment` must be provided if and only if the STARK has
+/// preprocessed columns, in which case its Merkle cap is expected to have already
+/// been observed by the `challenger`.
 pub fn prove_with_commitment<F, C, S, const D: usize>(
     stark: &S,
     config: &StarkConfig,
     trace_poly_values: &[PolynomialValues<F>],
     trace_commitment: &PolynomialBatch<F, C, D>,
+    preprocessed_commitment: Option<&PolynomialBatch<F, C, D>>,
     ctl_data: Option<&CtlData<F>>,
     ctl_challenges: Option<&GrandProductChallengeSet<F>>,
     challenger: &mut Challenger<F, C::Hasher>,
@@ -143,15 +284,26 @@ where
     let rate_bits = config.fri_config.rate_bits;
     let cap_height = config.fri_config.cap_height;
     assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
+        fri_params.total_arities() <= fri_params.lde_bits() - cap_height,
         "FRI total reduction arity is too large.",
     );
 
+    ensure!(
+        preprocessed_commitment.map_or(0, |c| c.polynomials.len()) == S::PREPROCESSED_COLUMNS,
+        "Invalid number of preprocessed columns."
+    );
+    ensure!(
+        preprocessed_commitment
+            .is_none_or(|c| c.degree_log == config.committed_degree_bits(degree_bits)),
+        "Mismatch between the lengths of the trace and of the preprocessed columns."
+    );
+
     let constraint_degree = stark.constraint_degree();
     assert!(
         constraint_degree <= (1 << rate_bits) + 1,
         "The degree of the Stark constraints must be <= blowup_factor + 1"
     );
+    check_blinding_degree::<D>(constraint_degree, degree, config)?;
 
     // Permutation arguments.
     let lookup_challenges = stark.uses_lookups().then(|| {
@@ -210,14 +362,7 @@ where
         timed!(
             timing,
             "compute auxiliary polynomials commitment",
-            PolynomialBatch::from_values(
-                aux_polys,
-                rate_bits,
-                false,
-                config.fri_config.cap_height,
-                timing,
-                None,
-            )
+            commit_to_columns::<F, C, D>(aux_polys, true, config, timing)
         )
     });
 
@@ -240,6 +385,7 @@ where
         check_constraints(
             stark,
             trace_commitment,
+            preprocessed_commitment,
             public_inputs,
             &auxiliary_polys_commitment,
             lookup_challenges.as_ref(),
@@ -258,6 +404,7 @@ where
         compute_quotient_polys::<F, <F as Packable>::Packing, C, S, D>(
             stark,
             trace_commitment,
+            preprocessed_commitment,
             &auxiliary_polys_commitment,
             lookup_challenges.as_ref(),
             &lookups,
@@ -271,6 +418,7 @@ where
         )
     );
     let (quotient_commitment, quotient_polys_cap) = if let Some(quotient_polys) = quotient_polys {
+        let num_quotient_chunks = stark.num_quotient_chunks(config);
         let all_quotient_chunks = timed!(
             timing,
             "split quotient polys",
@@ -278,12 +426,17 @@ where
                 .into_par_iter()
                 .flat_map(|mut quotient_poly| {
                     quotient_poly
-                        .trim_to_len(degree * stark.quotient_degree_factor())
+                        .trim_to_len(degree * num_quotient_chunks)
                         .expect(
                             "Quotient has failed, the vanishing polynomial is not divisible by Z_H",
                         );
                     // Split quotient into degree-n chunks.
-                    quotient_poly.chunks(degree)
+                    let chunks = quotient_poly.chunks(degree);
+                    if config.zero_knowledge {
+                        blind_quotient_chunks::<F, D>(chunks, config)
+                    } else {
+                        chunks
+                    }
                 })
                 .collect()
         );
@@ -294,7 +447,7 @@ where
             PolynomialBatch::from_coeffs(
                 all_quotient_chunks,
                 rate_bits,
-                false,
+                config.zero_knowledge,
                 config.fri_config.cap_height,
                 timing,
                 None,
@@ -326,6 +479,7 @@ where
         trace_commitment,
         auxiliary_polys_commitment.as_ref(),
         quotient_commitment.as_ref(),
+        preprocessed_commitment,
         stark.num_lookup_helper_columns(config),
         stark.requires_ctls(),
         &num_ctl_polys,
@@ -336,6 +490,7 @@ where
     let initial_merkle_trees = once(trace_commitment)
         .chain(&auxiliary_polys_commitment)
         .chain(&quotient_commitment)
+        .chain(preprocessed_commitment)
         .collect_vec();
 
     let opening_proof = timed!(
@@ -366,11 +521,100 @@ where
     })
 }
 
+/// Commits to the given columns, i.e. polynomials given by their values on the trace domain `H`.
+///
+/// In zero-knowledge mode, the committed polynomials have degree less than `2n`, and if
+/// `blinding` is set, each column `p` is replaced by `p + Z_H r` for a random polynomial `r`
+/// with [`StarkConfig::num_blinding_coeffs`] coefficients, which agrees with `p` on `H` but
+/// hides its values outside of `H`. Otherwise, the columns are committed to as they are.
+pub fn commit_to_columns<F, C, const D: usize>(
+    columns: Vec<PolynomialValues<F>>,
+    blinding: bool,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+    if !config.zero_knowledge {
+        return PolynomialBatch::from_values(columns, rate_bits, false, cap_height, timing, None);
+    }
+
+    let num_blinding_coeffs = config.num_blinding_coeffs::<D>();
+    let coeffs = timed!(
+        timing,
+        "IFFT and blinding",
+        columns
+            .into_par_iter()
+            .map(|values| {
+                let degree = values.len();
+                let mut coeffs = values.ifft().padded(2 * degree);
+                if blinding {
+                    assert!(
+                        num_blinding_coeffs <= degree,
+                        "The trace is too short to be blinded."
+                    );
+                    // Add `Z_H(X) r(X) = X^n r(X) - r(X)`.
+                    for (i, r) in F::rand_vec(num_blinding_coeffs).into_iter().enumerate() {
+                        coeffs.coeffs[i] -= r;
+                        coeffs.coeffs[degree + i] += r;
+                    }
+                }
+                coeffs
+            })
+            .collect()
+    );
+
+    PolynomialBatch::from_coeffs(coeffs, rate_bits, blinding, cap_height, timing, None)
+}
+
+/// Checks that a trace of length `degree` is long enough to be blinded in zero-knowledge mode,
+/// which requires the blinded constraints to still fit in the quotient chunks.
+fn check_blinding_degree<const D: usize>(
+    constraint_degree: usize,
+    degree: usize,
+    config: &StarkConfig,
+) -> Result<()> {
+    let min_degree = constraint_degree.max(1) * config.num_blinding_coeffs::<D>();
+    ensure!(
+        !config.zero_knowledge || degree >= min_degree,
+        "The trace is too short to be blinded, it should have at least {} rows.",
+        min_degree
+    );
+    Ok(())
+}
+
+/// Masks the degree-`n` chunks `t_i` of a quotient polynomial `t(X) = sum_i X^{in} t_i(X)`,
+/// replacing each `t_i` by `t_i + X^n s_i - s_{i-1}` for some random low-degree polynomials
+/// `s_i`. The masks cancel out in `t`, but hide the openings of the individual chunks.
+fn blind_quotient_chunks<F: RichField, const D: usize>(
+    chunks: Vec<PolynomialCoeffs<F>>,
+    config: &StarkConfig,
+) -> Vec<PolynomialCoeffs<F>> {
+    let degree = chunks[0].len();
+    let mut chunks = chunks
+        .into_iter()
+        .map(|chunk| chunk.padded(2 * degree))
+        .collect::<Vec<_>>();
+    for i in 1..chunks.len() {
+        let mask = F::rand_vec(config.num_blinding_coeffs::<D>());
+        for (j, s) in mask.into_iter().enumerate() {
+            chunks[i - 1].coeffs[degree + j] += s;
+            chunks[i].coeffs[j] -= s;
+        }
+    }
+    chunks
+}
+
 /// Computes the quotient polynomials `(sum alpha^i C_i(x)) / Z_H(x)` for `alpha` in `alphas`,
 /// where the `C_i`s are the STARK constraints.
 fn compute_quotient_polys<'a, F, P, C, S, const D: usize>(
     stark: &S,
     trace_commitment: &'a PolynomialBatch<F, C, D>,
+    preprocessed_commitment: Option<&'a PolynomialBatch<F, C, D>>,
     auxiliary_polys_commitment: &'a Option<PolynomialBatch<F, C, D>>,
     lookup_challenges: Option<&'a Vec<F>>,
     lookups: &[Lookup<F>],
@@ -393,10 +637,12 @@ where
     }
 
     let degree = 1 << degree_bits;
-    let rate_bits = config.fri_config.rate_bits;
+    // The LDEs of committed polynomials are `2^rate_bits` times larger than the trace, and twice
+    // as large in zero-knowledge mode, where the degree bound of committed polynomials is doubled.
+    let rate_bits = config.committed_degree_bits(config.fri_config.rate_bits);
     let total_num_helper_cols: usize = num_ctl_columns.iter().sum();
 
-    let quotient_degree_bits = log2_ceil(stark.quotient_degree_factor());
+    let quotient_degree_bits = log2_ceil(stark.num_quotient_chunks(config));
     assert!(
         quotient_degree_bits <= rate_bits,
         "Having constraints of degree higher than the rate is not supported yet."
@@ -416,6 +662,11 @@ where
     // Retrieve the LDE values at index `i`.
     let get_trace_values_packed =
         |i_start| -> Vec<P> { trace_commitment.get_lde_values_packed(i_start, step) };
+    let get_preprocessed_values_packed = |i_start| -> Vec<P> {
+        preprocessed_commitment
+            .map(|c| c.get_lde_values_packed(i_start, step))
+            .unwrap_or_default()
+    };
 
     // Last element of the subgroup.
     let last = F::primitive_root_of_unity(degree_bits).inverse();
@@ -448,9 +699,11 @@ where
             );
             // Get the local and next row evaluations for the current STARK,
             // as well as the public inputs.
-            let vars = S::EvaluationFrame::from_values(
+            let vars = S::EvaluationFrame::from_values_with_preprocessed(
                 &get_trace_values_packed(i_start),
                 &get_trace_values_packed(i_next_start),
+                &get_preprocessed_values_packed(i_start),
+                &get_preprocessed_values_packed(i_next_start),
                 public_inputs,
             );
             // Get the local and next row evaluations for the permutation argument,
@@ -565,6 +818,7 @@ where
 fn check_constraints<'a, F, C, S, const D: usize>(
     stark: &S,
     trace_commitment: &'a PolynomialBatch<F, C, D>,
+    preprocessed_commitment: Option<&'a PolynomialBatch<F, C, D>>,
     public_inputs: &[F],
     auxiliary_commitment: &'a Option<PolynomialBatch<F, C, D>>,
     lookup_challenges: Option<&'a Vec<F>>,
@@ -595,18 +849,29 @@ fn check_constraints<'a, F, C, S, const D: usize>(
 
     let subgroup = F::two_adic_subgroup(degree_bits + rate_bits);
 
-    // Get the evaluations of a batch of polynomials over our subgroup.
+    // Get the evaluations of a batch of polynomials over our subgroup. In zero-knowledge mode,
+    // committed polynomials have a larger degree than the trace, so we only keep their values
+    // on the subgroup.
     let get_subgroup_evals = |comm: &PolynomialBatch<F, C, D>| -> Vec<Vec<F>> {
         let values = comm
             .polynomials
             .par_iter()
-            .map(|coeffs| coeffs.clone().fft().values)
+            .map(|coeffs| {
+                coeffs
+                    .clone()
+                    .fft()
+                    .values
+                    .into_iter()
+                    .step_by(coeffs.len() / degree)
+                    .collect()
+            })
             .collect::<Vec<_>>();
         transpose(&values)
     };
 
     // Get batch evaluations of the trace and permutation polynomials over our subgroup.
     let trace_subgroup_evals = get_subgroup_evals(trace_commitment);
+    let preprocessed_subgroup_evals = preprocessed_commitment.map(get_subgroup_evals);
     let auxiliary_subgroup_evals = auxiliary_commitment.as_ref().map(get_subgroup_evals);
 
     // Last element of the subgroup.
@@ -628,9 +893,15 @@ fn check_constraints<'a, F, C, S, const D: usize>(
                 lagrange_basis_last,
             );
             // Get the local and next row evaluations for the current STARK's trace.
-            let vars = S::EvaluationFrame::from_values(
+            let (preprocessed_local, preprocessed_next) = preprocessed_subgroup_evals
+                .as_ref()
+                .map(|evals| (evals[i].as_slice(), evals[i_next].as_slice()))
+                .unwrap_or_default();
+            let vars = S::EvaluationFrame::from_values_with_preprocessed(
                 &trace_subgroup_evals[i],
                 &trace_subgroup_evals[i_next],
+                preprocessed_local,
+                preprocessed_next,
                 public_inputs,
             );
             // Get the local and next row evaluations for the current STARK's permutation argument.
@@ -2,31 +2,35 @@
 //! verification if encoded in a plonky2 circuit.
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{format, vec::Vec};
 use core::iter::once;
 
 use anyhow::{ensure, Result};
+use hashbrown::HashMap;
 use itertools::Itertools;
 use plonky2::field::extension::Extendable;
 use plonky2::fri::witness_util::set_fri_proof_target;
-use plonky2::hash::hash_types::RichField;
+use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::WitnessWrite;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::plonk_common::salt_size;
 use plonky2::util::reducing::ReducingFactorTarget;
 use plonky2::with_context;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::RecursiveConstraintConsumer;
-use crate::cross_table_lookup::CtlCheckVarsTarget;
+use crate::cross_table_lookup::{verify_cross_table_lookups_circuit, CtlCheckVarsTarget};
 use crate::evaluation_frame::StarkEvaluationFrame;
 use crate::lookup::LookupCheckVarsTarget;
+use crate::multi_stark::MultiStark;
 use crate::proof::{
-    StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget, StarkProofTarget,
-    StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
+    MultiProofChallengesTarget, MultiStarkProof, MultiStarkProofTarget, StarkOpeningSetTarget,
+    StarkProof, StarkProofChallengesTarget, StarkProofTarget, StarkProofWithPublicInputs,
+    StarkProofWithPublicInputsTarget,
 };
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly_circuit;
@@ -46,11 +50,69 @@ pub fn verify_stark_proof_circuit<
     min_degree_bits_to_support: Option<usize>,
 ) where
     C::Hasher: AlgebraicHasher<F>,
+{
+    verify_stark_proof_with_optional_preprocessed_circuit::<F, C, S, D>(
+        builder,
+        stark,
+        None,
+        proof_with_pis,
+        inner_config,
+        min_degree_bits_to_support,
+    )
+}
+
+/// Encodes the verification of a [`StarkProofWithPublicInputsTarget`]
+/// for some statement with preprocessed columns in a circuit.
+///
+/// The `preprocessed_cap` is the Merkle cap of the preprocessed columns, i.e. the
+/// circuit version of the cap of a [`StarkVerifyingKey`][crate::preprocessed::StarkVerifyingKey].
+pub fn verify_stark_proof_with_preprocessed_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: S,
+    preprocessed_cap: &MerkleCapTarget,
+    proof_with_pis: StarkProofWithPublicInputsTarget<D>,
+    inner_config: &StarkConfig,
+    min_degree_bits_to_support: Option<usize>,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    verify_stark_proof_with_optional_preprocessed_circuit::<F, C, S, D>(
+        builder,
+        stark,
+        Some(preprocessed_cap),
+        proof_with_pis,
+        inner_config,
+        min_degree_bits_to_support,
+    )
+}
+
+fn verify_stark_proof_with_optional_preprocessed_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: S,
+    preprocessed_cap: Option<&MerkleCapTarget>,
+    proof_with_pis: StarkProofWithPublicInputsTarget<D>,
+    inner_config: &StarkConfig,
+    min_degree_bits_to_support: Option<usize>,
+) where
+    C::Hasher: AlgebraicHasher<F>,
 {
     assert_eq!(proof_with_pis.public_inputs.len(), S::PUBLIC_INPUTS);
     let max_degree_bits_to_support = proof_with_pis.proof.recover_degree_bits(inner_config);
 
     let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+    if let Some(cap) = preprocessed_cap {
+        challenger.observe_cap(cap);
+    }
     let challenges = with_context!(
         builder,
         "compute challenges",
@@ -61,6 +123,7 @@ pub fn verify_stark_proof_circuit<
         builder,
         &stark,
         &proof_with_pis.proof,
+        preprocessed_cap,
         &proof_with_pis.public_inputs,
         challenges,
         None,
@@ -70,7 +133,102 @@ pub fn verify_stark_proof_circuit<
     );
 }
 
+/// Encodes the verification of a [`MultiStarkProofTarget`] for a [`MultiStark`]
+/// system in a circuit, including the verification of all its cross-table lookups.
+pub fn verify_multi_stark_proof_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+    const N: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    multi_stark: &MultiStark<F, C, D, N>,
+    proof: &MultiStarkProofTarget<D, N>,
+    inner_config: &StarkConfig,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    for (table, proof_with_pis) in proof.stark_proofs.iter().enumerate() {
+        assert_eq!(
+            proof_with_pis.public_inputs.len(),
+            multi_stark.stark(table).num_public_inputs()
+        );
+    }
+
+    let MultiProofChallengesTarget {
+        stark_challenges,
+        ctl_challenges,
+    } = with_context!(
+        builder,
+        "compute challenges",
+        proof.get_challenges::<F, C>(builder, inner_config)
+    );
+
+    let cross_table_lookups = multi_stark.cross_table_lookups();
+    let mut ctl_zs_first: [Vec<Target>; N] = core::array::from_fn(|_| Vec::new());
+
+    for (table, (proof_with_pis, challenges)) in
+        proof.stark_proofs.iter().zip(stark_challenges).enumerate()
+    {
+        let stark = multi_stark.stark(table);
+        let StarkProofWithPublicInputsTarget {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+
+        let ctl_vars = stark.requires_ctls().then(|| {
+            let (total_num_helpers, _, num_helpers_by_ctl) =
+                multi_stark.num_ctl_helpers_zs(table, inner_config);
+            ctl_zs_first[table] = proof
+                .openings
+                .ctl_zs_first
+                .clone()
+                .expect("We cannot have CTLs without `ctl_zs_first` openings.");
+
+            CtlCheckVarsTarget::from_proof(
+                table,
+                proof,
+                cross_table_lookups,
+                &ctl_challenges,
+                stark.num_lookup_helper_columns(inner_config),
+                total_num_helpers,
+                &num_helpers_by_ctl,
+            )
+        });
+
+        let degree_bits = proof.recover_degree_bits(inner_config);
+        with_context!(
+            builder,
+            &format!("verify table {}", table),
+            stark.verify_with_challenges_circuit(
+                builder,
+                proof,
+                public_inputs,
+                challenges,
+                ctl_vars.as_deref(),
+                inner_config,
+                degree_bits,
+            )
+        );
+    }
+
+    with_context!(
+        builder,
+        "verify cross-table lookups",
+        verify_cross_table_lookups_circuit::<F, D, N>(
+            builder,
+            cross_table_lookups.to_vec(),
+            ctl_zs_first,
+            &HashMap::new(),
+            inner_config,
+        )
+    );
+}
+
 /// Recursively verifies an inner STARK proof.
+///
+/// The `preprocessed_cap` must be provided if and only if the STARK has
+/// preprocessed columns.
 pub fn verify_stark_proof_with_challenges_circuit<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -80,6 +238,7 @@ pub fn verify_stark_proof_with_challenges_circuit<
     builder: &mut CircuitBuilder<F, D>,
     stark: &S,
     proof: &StarkProofTarget<D>,
+    preprocessed_cap: Option<&MerkleCapTarget>,
     public_inputs: &[Target],
     challenges: StarkProofChallengesTarget<D>,
     ctl_vars: Option<&[CtlCheckVarsTarget<F, D>]>,
@@ -90,6 +249,7 @@ pub fn verify_stark_proof_with_challenges_circuit<
     C::Hasher: AlgebraicHasher<F>,
 {
     check_lookup_options(stark, proof, &challenges).unwrap();
+    check_preprocessed_options::<F, S, D>(proof, preprocessed_cap).unwrap();
 
     let zero = builder.zero();
     let one = builder.one_extension();
@@ -102,15 +262,19 @@ pub fn verify_stark_proof_with_challenges_circuit<
     let StarkOpeningSetTarget {
         local_values,
         next_values,
+        preprocessed_values,
+        preprocessed_next_values,
         auxiliary_polys,
         auxiliary_polys_next,
         ctl_zs_first,
         quotient_polys,
     } = &proof.openings;
 
-    let vars = S::EvaluationFrameTarget::from_values(
+    let vars = S::EvaluationFrameTarget::from_values_with_preprocessed(
         local_values,
         next_values,
+        preprocessed_values.as_deref().unwrap_or_default(),
+        preprocessed_next_values.as_deref().unwrap_or_default(),
         &public_inputs
             .iter()
             .map(|&t| builder.convert_to_ext(t))
@@ -186,7 +350,7 @@ pub fn verify_stark_proof_with_challenges_circuit<
     let mut scale = ReducingFactorTarget::new(zeta_pow_deg);
     if let Some(quotient_polys) = quotient_polys {
         for (i, chunk) in quotient_polys
-            .chunks(stark.quotient_degree_factor())
+            .chunks(stark.num_quotient_chunks(inner_config))
             .enumerate()
         {
             let recombined_quotient = scale.reduce(chunk, builder);
@@ -198,6 +362,7 @@ pub fn verify_stark_proof_with_challenges_circuit<
     let merkle_caps = once(proof.trace_cap.clone())
         .chain(proof.auxiliary_polys_cap.clone())
         .chain(proof.quotient_polys_cap.clone())
+        .chain(preprocessed_cap.cloned())
         .collect_vec();
 
     let fri_instance = stark.fri_instance_target(
@@ -209,10 +374,22 @@ pub fn verify_stark_proof_with_challenges_circuit<
         inner_config,
     );
 
+    // In zero-knowledge mode, the committed polynomials have twice the degree of the trace.
+    let (committed_degree_bits, committed_degree) = if inner_config.zero_knowledge {
+        (
+            builder.add_const(proof.degree_bits, F::ONE),
+            builder.mul_const(F::TWO, degree),
+        )
+    } else {
+        (proof.degree_bits, degree)
+    };
     let one = builder.one();
-    let degree_sub_one = builder.sub(degree, one);
+    let degree_sub_one = builder.sub(committed_degree, one);
     // Used to check if we want to skip a Fri query step.
-    let degree_sub_one_bits_vec = builder.split_le(degree_sub_one, degree_bits);
+    let degree_sub_one_bits_vec = builder.split_le(
+        degree_sub_one,
+        inner_config.committed_degree_bits(degree_bits),
+    );
 
     if let Some(min_degree_bits_to_support) = min_degree_bits_to_support {
         builder.verify_fri_proof_with_multiple_degree_bits::<C>(
@@ -222,9 +399,9 @@ pub fn verify_stark_proof_with_challenges_circuit<
             &merkle_caps,
             &proof.opening_proof,
             &inner_config.fri_params(degree_bits),
-            proof.degree_bits,
+            committed_degree_bits,
             &degree_sub_one_bits_vec,
-            min_degree_bits_to_support,
+            inner_config.committed_degree_bits(min_degree_bits_to_support),
         );
     } else {
         builder.verify_fri_proof::<C>(
@@ -284,6 +461,33 @@ pub fn add_virtual_stark_proof_with_pis<
     }
 }
 
+/// Adds a new `MultiStarkProofTarget` to this circuit, for a [`MultiStark`] system
+/// whose tables have the provided `degree_bits`.
+pub fn add_virtual_multi_stark_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+    const N: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    multi_stark: &MultiStark<F, C, D, N>,
+    config: &StarkConfig,
+    degree_bits: [usize; N],
+) -> MultiStarkProofTarget<D, N> {
+    MultiStarkProofTarget {
+        stark_proofs: core::array::from_fn(|table| {
+            let (num_ctl_helpers, num_ctl_zs, _) = multi_stark.num_ctl_helpers_zs(table, config);
+            multi_stark.stark(table).add_virtual_proof_with_pis(
+                builder,
+                config,
+                degree_bits[table],
+                num_ctl_helpers + num_ctl_zs,
+                num_ctl_zs,
+            )
+        }),
+    }
+}
+
 /// Adds a new `StarkProofTarget` to this circuit.
 pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
@@ -296,15 +500,15 @@ pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, con
     let fri_params = config.fri_params(degree_bits);
     let cap_height = fri_params.config.cap_height;
 
+    let salt = salt_size(fri_params.hiding);
     let num_leaves_per_oracle = once(S::COLUMNS)
         .chain(
             (stark.uses_lookups() || stark.requires_ctls())
                 .then(|| stark.num_lookup_helper_columns(config) + num_ctl_helper_zs),
         )
-        .chain(
-            (stark.quotient_degree_factor() > 0)
-                .then(|| stark.quotient_degree_factor() * config.num_challenges),
-        )
+        .chain((stark.quotient_degree_factor() > 0).then(|| stark.num_quotient_polys(config)))
+        .map(|num_polys| num_polys + salt)
+        .chain((S::PREPROCESSED_COLUMNS > 0).then_some(S::PREPROCESSED_COLUMNS))
         .collect_vec();
 
     let auxiliary_polys_cap = (stark.uses_lookups() || stark.requires_ctls())
@@ -339,6 +543,10 @@ fn add_virtual_stark_opening_set<F: RichField + Extendable<D>, S: Stark<F, D>, c
     StarkOpeningSetTarget {
         local_values: builder.add_virtual_extension_targets(S::COLUMNS),
         next_values: builder.add_virtual_extension_targets(S::COLUMNS),
+        preprocessed_values: (S::PREPROCESSED_COLUMNS > 0)
+            .then(|| builder.add_virtual_extension_targets(S::PREPROCESSED_COLUMNS)),
+        preprocessed_next_values: (S::PREPROCESSED_COLUMNS > 0)
+            .then(|| builder.add_virtual_extension_targets(S::PREPROCESSED_COLUMNS)),
         auxiliary_polys: (stark.uses_lookups() || stark.requires_ctls()).then(|| {
             builder.add_virtual_extension_targets(
                 stark.num_lookup_helper_columns(config) + num_ctl_helper_zs,
@@ -352,11 +560,8 @@ fn add_virtual_stark_opening_set<F: RichField + Extendable<D>, S: Stark<F, D>, c
         ctl_zs_first: stark
             .requires_ctls()
             .then(|| builder.add_virtual_targets(num_ctl_zs)),
-        quotient_polys: (stark.constraint_degree() > 0).then(|| {
-            builder.add_virtual_extension_targets(
-                stark.quotient_degree_factor() * config.num_challenges,
-            )
-        }),
+        quotient_polys: (stark.constraint_degree() > 0)
+            .then(|| builder.add_virtual_extension_targets(stark.num_quotient_polys(config))),
     }
 }
 
@@ -391,6 +596,44 @@ where
     set_stark_proof_target(witness, pt, proof, pis_degree_bits, zero)
 }
 
+/// Set the targets in a [`MultiStarkProofTarget`] to their corresponding values in a
+/// [`MultiStarkProof`].
+pub fn set_multi_stark_proof_target<
+    F,
+    C: GenericConfig<D, F = F>,
+    W,
+    const D: usize,
+    const N: usize,
+>(
+    witness: &mut W,
+    multi_stark_proof_target: &MultiStarkProofTarget<D, N>,
+    multi_stark_proof: &MultiStarkProof<F, C, D, N>,
+    inner_config: &StarkConfig,
+    zero: Target,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C::Hasher: AlgebraicHasher<F>,
+    W: WitnessWrite<F>,
+{
+    for (proof_with_pis_target, proof_with_pis) in multi_stark_proof_target
+        .stark_proofs
+        .iter()
+        .zip(&multi_stark_proof.stark_proofs)
+    {
+        let degree_bits = proof_with_pis.proof.recover_degree_bits(inner_config);
+        set_stark_proof_with_pis_target(
+            witness,
+            proof_with_pis_target,
+            proof_with_pis,
+            degree_bits,
+            zero,
+        )?;
+    }
+
+    Ok(())
+}
+
 /// Set the targets in a [`StarkProofTarget`] to their corresponding values in a
 /// [`StarkProof`].
 pub fn set_stark_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
@@ -452,3 +695,23 @@ fn check_lookup_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: u
     );
     Ok(())
 }
+
+/// Utility function to check that all preprocessed data wrapped in `Option`s are `Some` iff
+/// the STARK has preprocessed columns.
+fn check_preprocessed_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    proof: &StarkProofTarget<D>,
+    preprocessed_cap: Option<&MerkleCapTarget>,
+) -> Result<()> {
+    let options_is_some = [
+        preprocessed_cap.is_some(),
+        proof.openings.preprocessed_values.is_some(),
+        proof.openings.preprocessed_next_values.is_some(),
+    ];
+    ensure!(
+        options_is_some
+            .iter()
+            .all(|&b| b == (S::PREPROCESSED_COLUMNS > 0)),
+        "Preprocessed data doesn't match with STARK configuration."
+    );
+    Ok(())
+}
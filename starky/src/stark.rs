@@ -6,6 +6,7 @@ use alloc::{vec, vec::Vec};
 
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
 use plonky2::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
@@ -27,6 +28,9 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     const COLUMNS: usize = Self::EvaluationFrameTarget::COLUMNS;
     /// The total number of public inputs.
     const PUBLIC_INPUTS: usize = Self::EvaluationFrameTarget::PUBLIC_INPUTS;
+    /// The total number of preprocessed columns, i.e. fixed columns which do not depend on
+    /// the witness and are committed to once during setup.
+    const PREPROCESSED_COLUMNS: usize = Self::EvaluationFrameTarget::PREPROCESSED_COLUMNS;
 
     /// This is used to evaluate constraints natively.
     type EvaluationFrame<FE, P, const D2: usize>: StarkEvaluationFrame<P, FE>
@@ -91,10 +95,20 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         }
     }
 
+    /// Outputs the number of degree-`n` chunks each quotient polynomial of this [`Stark`] is
+    /// split into with the provided [`StarkConfig`]. In zero-knowledge mode, blinding the trace
+    /// increases the degree of the constraints, which requires an extra chunk.
+    fn num_quotient_chunks(&self, config: &StarkConfig) -> usize {
+        match self.quotient_degree_factor() {
+            0 => 0,
+            factor => factor + config.zero_knowledge as usize,
+        }
+    }
+
     /// Outputs the number of quotient polynomials this [`Stark`] would require with
     /// the provided [`StarkConfig`]
     fn num_quotient_polys(&self, config: &StarkConfig) -> usize {
-        self.quotient_degree_factor() * config.num_challenges
+        self.num_quotient_chunks(config) * config.num_challenges
     }
 
     /// Computes the FRI instance used to prove this Stark.
@@ -110,7 +124,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let num_lookup_columns = self.num_lookup_helper_columns(config);
@@ -119,7 +133,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             let aux_polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_auxiliary_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_auxiliary_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
             });
             aux_polys
         } else {
@@ -132,25 +146,38 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
                 FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_quotient_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
             });
             quotient_polys
         } else {
             vec![]
         };
 
+        let preprocessed_info = if Self::PREPROCESSED_COLUMNS > 0 {
+            let preprocessed_polys =
+                FriPolynomialInfo::from_range(oracles.len(), 0..Self::PREPROCESSED_COLUMNS);
+            oracles.push(FriOracleInfo {
+                num_polys: Self::PREPROCESSED_COLUMNS,
+                blinding: false,
+            });
+            preprocessed_polys
+        } else {
+            vec![]
+        };
+
         let zeta_batch = FriBatchInfo {
             point: zeta,
             polynomials: [
                 trace_info.clone(),
                 auxiliary_polys_info.clone(),
                 quotient_info,
+                preprocessed_info.clone(),
             ]
             .concat(),
         };
         let zeta_next_batch = FriBatchInfo {
             point: zeta.scalar_mul(g),
-            polynomials: [trace_info, auxiliary_polys_info].concat(),
+            polynomials: [trace_info, auxiliary_polys_info, preprocessed_info].concat(),
         };
 
         let mut batches = vec![zeta_batch, zeta_next_batch];
@@ -185,7 +212,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
         oracles.push(FriOracleInfo {
             num_polys: Self::COLUMNS,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let num_lookup_columns = self.num_lookup_helper_columns(config);
@@ -194,7 +221,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             let aux_polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_auxiliary_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_auxiliary_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
             });
             aux_polys
         } else {
@@ -207,19 +234,32 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
                 FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
             oracles.push(FriOracleInfo {
                 num_polys: num_quotient_polys,
-                blinding: false,
+                blinding: config.zero_knowledge,
             });
             quotient_polys
         } else {
             vec![]
         };
 
+        let preprocessed_info = if Self::PREPROCESSED_COLUMNS > 0 {
+            let preprocessed_polys =
+                FriPolynomialInfo::from_range(oracles.len(), 0..Self::PREPROCESSED_COLUMNS);
+            oracles.push(FriOracleInfo {
+                num_polys: Self::PREPROCESSED_COLUMNS,
+                blinding: false,
+            });
+            preprocessed_polys
+        } else {
+            vec![]
+        };
+
         let zeta_batch = FriBatchInfoTarget {
             point: zeta,
             polynomials: [
                 trace_info.clone(),
                 auxiliary_polys_info.clone(),
                 quotient_info,
+                preprocessed_info.clone(),
             ]
             .concat(),
         };
@@ -227,7 +267,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let zeta_next = builder.mul_extension(g_ext, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
             point: zeta_next,
-            polynomials: [trace_info, auxiliary_polys_info].concat(),
+            polynomials: [trace_info, auxiliary_polys_info, preprocessed_info].concat(),
         };
 
         let mut batches = vec![zeta_batch, zeta_next_batch];
@@ -248,6 +288,15 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         FriInstanceInfoTarget { oracles, batches }
     }
 
+    /// Generates the values of the preprocessed columns of this STARK, which must all
+    /// have the same length as the trace. These are committed to once by
+    /// [`setup`][crate::preprocessed::setup].
+    ///
+    /// It defaults to no preprocessed columns.
+    fn generate_preprocessed_columns(&self) -> Vec<PolynomialValues<F>> {
+        vec![]
+    }
+
     /// Outputs all the [`Lookup`] this STARK table needs to perform across its columns.
     fn lookups(&self) -> Vec<Lookup<F>> {
         vec![]
@@ -28,6 +28,7 @@ pub fn test_stark_low_degree<F: RichField + Extendable<D>, S: Stark<F, D>, const
     let rate_bits = log2_ceil(stark.constraint_degree() + 1);
 
     let trace_ldes = random_low_degree_matrix::<F>(S::COLUMNS, rate_bits);
+    let preprocessed_ldes = random_low_degree_matrix::<F>(S::PREPROCESSED_COLUMNS, rate_bits);
     let size = trace_ldes.len();
     let public_inputs = F::rand_vec(S::PUBLIC_INPUTS);
 
@@ -40,9 +41,12 @@ pub fn test_stark_low_degree<F: RichField + Extendable<D>, S: Stark<F, D>, const
     let alpha = F::rand();
     let constraint_evals = (0..size)
         .map(|i| {
-            let vars = S::EvaluationFrame::from_values(
+            let i_next = (i + (1 << rate_bits)) % size;
+            let vars = S::EvaluationFrame::from_values_with_preprocessed(
                 &trace_ldes[i],
-                &trace_ldes[(i + (1 << rate_bits)) % size],
+                &trace_ldes[i_next],
+                &preprocessed_ldes[i],
+                &preprocessed_ldes[i_next],
                 &public_inputs,
             );
 
@@ -82,9 +86,11 @@ pub fn test_stark_circuit_constraints<
     stark: S,
 ) -> Result<()> {
     // Compute native constraint evaluation on random values.
-    let vars = S::EvaluationFrame::from_values(
+    let vars = S::EvaluationFrame::from_values_with_preprocessed(
         &F::Extension::rand_vec(S::COLUMNS),
         &F::Extension::rand_vec(S::COLUMNS),
+        &F::Extension::rand_vec(S::PREPROCESSED_COLUMNS),
+        &F::Extension::rand_vec(S::PREPROCESSED_COLUMNS),
         &F::Extension::rand_vec(S::PUBLIC_INPUTS),
     );
     let alphas = F::rand_vec(1);
@@ -112,6 +118,10 @@ pub fn test_stark_circuit_constraints<
     pw.set_extension_targets(&locals_t, vars.get_local_values())?;
     let nexts_t = builder.add_virtual_extension_targets(S::COLUMNS);
     pw.set_extension_targets(&nexts_t, vars.get_next_values())?;
+    let preprocessed_locals_t = builder.add_virtual_extension_targets(S::PREPROCESSED_COLUMNS);
+    pw.set_extension_targets(&preprocessed_locals_t, vars.get_local_preprocessed_values())?;
+    let preprocessed_nexts_t = builder.add_virtual_extension_targets(S::PREPROCESSED_COLUMNS);
+    pw.set_extension_targets(&preprocessed_nexts_t, vars.get_next_preprocessed_values())?;
     let pis_t = builder.add_virtual_extension_targets(S::PUBLIC_INPUTS);
     pw.set_extension_targets(&pis_t, vars.get_public_inputs())?;
     let alphas_t = builder.add_virtual_targets(1);
@@ -123,7 +133,13 @@ pub fn test_stark_circuit_constraints<
     let lagrange_last_t = builder.add_virtual_extension_target();
     pw.set_extension_target(lagrange_last_t, lagrange_last)?;
 
-    let vars = S::EvaluationFrameTarget::from_values(&locals_t, &nexts_t, &pis_t);
+    let vars = S::EvaluationFrameTarget::from_values_with_preprocessed(
+        &locals_t,
+        &nexts_t,
+        &preprocessed_locals_t,
+        &preprocessed_nexts_t,
+        &pis_t,
+    );
     let mut consumer = RecursiveConstraintConsumer::<F, D>::new(
         builder.zero_extension(),
         alphas_t,
@@ -142,6 +158,10 @@ pub fn test_stark_circuit_constraints<
 }
 
 fn random_low_degree_matrix<F: Field>(num_polys: usize, rate_bits: usize) -> Vec<Vec<F>> {
+    if num_polys == 0 {
+        return vec![vec![]; WITNESS_SIZE << rate_bits];
+    }
+
     let polys = (0..num_polys)
         .map(|_| random_low_degree_values(rate_bits))
         .collect::<Vec<_>>();
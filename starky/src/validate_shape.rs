@@ -0,0 +1,215 @@
+//! Checks that STARK proofs have the shape expected by the verifier, so that malformed
+//! proofs are rejected with an error before any of their components get accessed.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use anyhow::{anyhow, ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::fri::structure::FriInstanceInfo;
+use plonky2::fri::validate_shape::validate_fri_proof_shape;
+use plonky2::fri::FriParams;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::plonk_common::salt_size;
+
+use crate::config::StarkConfig;
+use crate::proof::{StarkOpeningSet, StarkProof};
+use crate::stark::Stark;
+
+/// Checks that a [`StarkProof`] and its public inputs have the shape expected by the
+/// verifier of the provided [`Stark`], with `num_ctl_helpers` cross-table lookup helper
+/// columns and `num_ctl_zs` cross-table lookup `Z` polynomials.
+///
+/// The `preprocessed_cap` must be provided if and only if the STARK has preprocessed columns.
+pub fn validate_stark_proof_shape<F, C, S, const D: usize>(
+    stark: &S,
+    proof: &StarkProof<F, C, D>,
+    public_inputs: &[F],
+    preprocessed_cap: Option<&MerkleCap<F, C::Hasher>>,
+    config: &StarkConfig,
+    num_ctl_helpers: usize,
+    num_ctl_zs: usize,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    let StarkProof {
+        trace_cap,
+        auxiliary_polys_cap,
+        quotient_polys_cap,
+        openings,
+        opening_proof,
+    } = proof;
+
+    let StarkOpeningSet {
+        local_values,
+        next_values,
+        preprocessed_values,
+        preprocessed_next_values,
+        auxiliary_polys,
+        auxiliary_polys_next,
+        ctl_zs_first,
+        quotient_polys,
+    } = openings;
+
+    ensure!(
+        public_inputs.len() == S::PUBLIC_INPUTS,
+        "Invalid number of public inputs."
+    );
+
+    let num_cap_elements = config.fri_config.num_cap_elements();
+    let check_cap = |cap: Option<&MerkleCap<F, C::Hasher>>, expected: bool, name: &str| {
+        match cap {
+            Some(cap) if expected => ensure!(cap.len() == num_cap_elements, "Invalid {}", name),
+            None if expected => return Err(anyhow!("Missing {}", name)),
+            Some(_) => return Err(anyhow!("Unexpected {}", name)),
+            None => (),
+        }
+        Ok(())
+    };
+    let check_openings = |values: Option<usize>, expected: Option<usize>, name: &str| {
+        match (values, expected) {
+            (Some(len), Some(expected_len)) => {
+                ensure!(len == expected_len, "Invalid number of {}", name)
+            }
+            (None, Some(_)) => return Err(anyhow!("Missing {}", name)),
+            (Some(_), None) => return Err(anyhow!("Unexpected {}", name)),
+            (None, None) => (),
+        }
+        Ok(())
+    };
+
+    let uses_auxiliary_polys = stark.uses_lookups() || stark.requires_ctls();
+    let num_auxiliary = uses_auxiliary_polys
+        .then(|| stark.num_lookup_helper_columns(config) + num_ctl_helpers + num_ctl_zs);
+    let num_preprocessed = (S::PREPROCESSED_COLUMNS > 0).then_some(S::PREPROCESSED_COLUMNS);
+    let num_quotient_polys =
+        Some(stark.num_quotient_polys(config)).filter(|&num_polys| num_polys > 0);
+
+    check_cap(Some(trace_cap), true, "trace_cap")?;
+    check_cap(
+        auxiliary_polys_cap.as_ref(),
+        uses_auxiliary_polys,
+        "auxiliary_polys_cap",
+    )?;
+    check_cap(
+        quotient_polys_cap.as_ref(),
+        num_quotient_polys.is_some(),
+        "quotient_polys_cap",
+    )?;
+    check_cap(
+        preprocessed_cap,
+        num_preprocessed.is_some(),
+        "preprocessed_cap",
+    )?;
+
+    check_openings(Some(local_values.len()), Some(S::COLUMNS), "local_values")?;
+    check_openings(Some(next_values.len()), Some(S::COLUMNS), "next_values")?;
+    check_openings(
+        preprocessed_values.as_ref().map(Vec::len),
+        num_preprocessed,
+        "preprocessed_values",
+    )?;
+    check_openings(
+        preprocessed_next_values.as_ref().map(Vec::len),
+        num_preprocessed,
+        "preprocessed_next_values",
+    )?;
+    check_openings(
+        auxiliary_polys.as_ref().map(Vec::len),
+        num_auxiliary,
+        "auxiliary_polys",
+    )?;
+    check_openings(
+        auxiliary_polys_next.as_ref().map(Vec::len),
+        num_auxiliary,
+        "auxiliary_polys_next",
+    )?;
+    check_openings(
+        ctl_zs_first.as_ref().map(Vec::len),
+        stark.requires_ctls().then_some(num_ctl_zs),
+        "ctl_zs_first",
+    )?;
+    check_openings(
+        quotient_polys.as_ref().map(Vec::len),
+        num_quotient_polys,
+        "quotient_polys",
+    )?;
+
+    let lde_bits = opening_proof
+        .query_round_proofs
+        .first()
+        .and_then(|round| round.initial_trees_proof.evals_proofs.first())
+        .map(|(_, merkle_proof)| config.fri_config.cap_height + merkle_proof.len())
+        .ok_or_else(|| anyhow!("Missing FRI query rounds"))?;
+    let fri_params = fri_params_from_lde_bits::<F>(lde_bits, config)?;
+    let fri_instance = oracles_fri_instance(stark, config, num_ctl_helpers, num_ctl_zs);
+
+    validate_fri_proof_shape::<F, C, D>(opening_proof, &fri_instance, &fri_params)
+}
+
+/// Returns the [`FriParams`] of a proof whose LDE domain has size `2^lde_bits`, after
+/// checking that this size is consistent with the provided [`StarkConfig`].
+pub(crate) fn fri_params_from_lde_bits<F: Field>(
+    lde_bits: usize,
+    config: &StarkConfig,
+) -> Result<FriParams> {
+    let rate_bits = config.fri_config.rate_bits + config.zero_knowledge as usize;
+    let cap_height = config.fri_config.cap_height;
+    ensure!(
+        lde_bits <= F::TWO_ADICITY && lde_bits >= rate_bits + cap_height,
+        "Invalid LDE size."
+    );
+    let fri_params = config.fri_params(lde_bits - rate_bits);
+    ensure!(
+        fri_params.total_arities() <= lde_bits - cap_height,
+        "FRI total reduction arity is too large."
+    );
+    Ok(fri_params)
+}
+
+/// Returns the length of the Merkle leaves of each oracle committed to in a proof
+/// of the provided [`Stark`], including salts.
+pub(crate) fn fri_oracle_leaf_lengths<F, S, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+    fri_params: &FriParams,
+    num_ctl_helpers: usize,
+    num_ctl_zs: usize,
+) -> Vec<usize>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    oracles_fri_instance(stark, config, num_ctl_helpers, num_ctl_zs)
+        .oracles
+        .iter()
+        .map(|oracle| oracle.num_polys + salt_size(oracle.blinding && fri_params.hiding))
+        .collect()
+}
+
+/// Returns the FRI instance of a proof of the provided [`Stark`], with dummy opening points.
+/// Only its oracles are meaningful.
+fn oracles_fri_instance<F, S, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+    num_ctl_helpers: usize,
+    num_ctl_zs: usize,
+) -> FriInstanceInfo<F, D>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    stark.fri_instance(
+        F::Extension::ZERO,
+        F::ONE,
+        num_ctl_helpers,
+        vec![0; num_ctl_zs],
+        config,
+    )
+}
@@ -6,6 +6,7 @@ use core::any::type_name;
 use core::iter::once;
 
 use anyhow::{anyhow, ensure, Result};
+use hashbrown::HashMap;
 use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
@@ -19,11 +20,17 @@ use plonky2::plonk::plonk_common::reduce_with_powers;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
-use crate::cross_table_lookup::CtlCheckVars;
+use crate::cross_table_lookup::{verify_cross_table_lookups, CtlCheckVars};
 use crate::evaluation_frame::StarkEvaluationFrame;
 use crate::lookup::LookupCheckVars;
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs};
+use crate::multi_stark::MultiStark;
+use crate::preprocessed::StarkVerifyingKey;
+use crate::proof::{
+    MultiProofChallenges, MultiStarkProof, StarkOpeningSet, StarkProof, StarkProofChallenges,
+    StarkProofWithPublicInputs,
+};
 use crate::stark::Stark;
+use crate::validate_shape::validate_stark_proof_shape;
 use crate::vanishing_poly::eval_vanishing_poly;
 
 /// Verifies a [`StarkProofWithPublicInputs`] against a STARK statement.
@@ -38,8 +45,71 @@ pub fn verify_stark_proof<
     config: &StarkConfig,
     verifier_circuit_fri_params: Option<FriParams>,
 ) -> Result<()> {
-    ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
+    verify_stark_proof_with_optional_preprocessed(
+        stark,
+        None,
+        proof_with_pis,
+        config,
+        verifier_circuit_fri_params,
+    )
+}
+
+/// Verifies a [`StarkProofWithPublicInputs`] against a STARK statement with preprocessed
+/// columns, committed to in the provided [`StarkVerifyingKey`].
+pub fn verify_stark_proof_with_preprocessed<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: S,
+    verifying_key: &StarkVerifyingKey<F, C, D>,
+    proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
+    config: &StarkConfig,
+    verifier_circuit_fri_params: Option<FriParams>,
+) -> Result<()> {
+    verify_stark_proof_with_optional_preprocessed(
+        stark,
+        Some(verifying_key),
+        proof_with_pis,
+        config,
+        verifier_circuit_fri_params,
+    )
+}
+
+fn verify_stark_proof_with_optional_preprocessed<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: S,
+    verifying_key: Option<&StarkVerifyingKey<F, C, D>>,
+    proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
+    config: &StarkConfig,
+    verifier_circuit_fri_params: Option<FriParams>,
+) -> Result<()> {
+    let preprocessed_cap = verifying_key.map(|vk| &vk.preprocessed_cap);
+    validate_stark_proof_shape(
+        &stark,
+        &proof_with_pis.proof,
+        &proof_with_pis.public_inputs,
+        preprocessed_cap,
+        config,
+        0,
+        0,
+    )?;
+    if let Some(verifying_key) = verifying_key {
+        ensure!(
+            proof_with_pis.proof.recover_degree_bits(config) == verifying_key.degree_bits,
+            "Mismatch between the lengths of the trace and of the preprocessed columns."
+        );
+    }
+
     let mut challenger = Challenger::<F, C::Hasher>::new();
+    if let Some(cap) = preprocessed_cap {
+        challenger.observe_cap(cap);
+    }
 
     let challenges = proof_with_pis.get_challenges(
         &mut challenger,
@@ -52,6 +122,7 @@ pub fn verify_stark_proof<
     verify_stark_proof_with_challenges(
         &stark,
         &proof_with_pis.proof,
+        preprocessed_cap,
         &challenges,
         None,
         &proof_with_pis.public_inputs,
@@ -59,13 +130,92 @@ pub fn verify_stark_proof<
     )
 }
 
+/// Verifies a [`MultiStarkProof`] against a [`MultiStark`] system, i.e. verifies
+/// the proofs of all individual tables along with all their cross-table lookups.
+pub fn verify_multi<F, C, const D: usize, const N: usize>(
+    multi_stark: &MultiStark<F, C, D, N>,
+    proof: &MultiStarkProof<F, C, D, N>,
+    config: &StarkConfig,
+) -> Result<()>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    for (table, proof_with_pis) in proof.stark_proofs.iter().enumerate() {
+        let (num_ctl_helpers, num_ctl_zs, _) = multi_stark.num_ctl_helpers_zs(table, config);
+        multi_stark
+            .stark(table)
+            .validate_proof_shape(proof_with_pis, config, num_ctl_helpers, num_ctl_zs)
+            .map_err(|e| anyhow!("Invalid proof shape for table {}: {}", table, e))?;
+    }
+
+    let MultiProofChallenges {
+        stark_challenges,
+        ctl_challenges,
+    } = proof.get_challenges(config);
+
+    let cross_table_lookups = multi_stark.cross_table_lookups();
+    let mut ctl_zs_first: [Vec<F>; N] = core::array::from_fn(|_| Vec::new());
+
+    for (table, (proof_with_pis, challenges)) in
+        proof.stark_proofs.iter().zip(&stark_challenges).enumerate()
+    {
+        let stark = multi_stark.stark(table);
+        let StarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+
+        let ctl_vars = if stark.requires_ctls() {
+            let (total_num_helpers, _, num_helpers_by_ctl) =
+                multi_stark.num_ctl_helpers_zs(table, config);
+            ctl_zs_first[table] = proof
+                .openings
+                .ctl_zs_first
+                .clone()
+                .ok_or_else(|| anyhow!("Missing ctl_zs_first for table {}", table))?;
+
+            Some(CtlCheckVars::from_proof(
+                table,
+                proof,
+                cross_table_lookups,
+                &ctl_challenges,
+                stark.num_lookup_helper_columns(config),
+                total_num_helpers,
+                &num_helpers_by_ctl,
+            ))
+        } else {
+            None
+        };
+
+        stark.verify_with_challenges(
+            proof,
+            challenges,
+            ctl_vars.as_deref(),
+            public_inputs,
+            config,
+        )?;
+    }
+
+    verify_cross_table_lookups::<F, D, N>(
+        cross_table_lookups,
+        ctl_zs_first,
+        &HashMap::new(),
+        config,
+    )
+}
+
 /// Verifies a [`StarkProofWithPublicInputs`] against a STARK statement,
 /// with the provided [`StarkProofChallenges`].
 /// It also supports optional cross-table lookups data and challenges,
 /// in case this proof is part of a multi-STARK system.
+///
+/// The `preprocessed_cap` must be provided if and only if the STARK has
+/// preprocessed columns.
 pub fn verify_stark_proof_with_challenges<F, C, S, const D: usize>(
     stark: &S,
     proof: &StarkProof<F, C, D>,
+    preprocessed_cap: Option<&MerkleCap<F, C::Hasher>>,
     challenges: &StarkProofChallenges<F, D>,
     ctl_vars: Option<&[CtlCheckVars<F, F::Extension, F::Extension, D>]>,
     public_inputs: &[F],
@@ -87,10 +237,11 @@ where
         })
         .unwrap_or_default();
 
-    validate_proof_shape(
+    validate_stark_proof_shape(
         stark,
         proof,
         public_inputs,
+        preprocessed_cap,
         config,
         num_ctl_polys,
         num_ctl_z_polys,
@@ -99,15 +250,19 @@ where
     let StarkOpeningSet {
         local_values,
         next_values,
+        preprocessed_values,
+        preprocessed_next_values,
         auxiliary_polys,
         auxiliary_polys_next,
         ctl_zs_first: _,
         quotient_polys,
     } = &proof.openings;
 
-    let vars = S::EvaluationFrame::from_values(
+    let vars = S::EvaluationFrame::from_values_with_preprocessed(
         local_values,
         next_values,
+        preprocessed_values.as_deref().unwrap_or_default(),
+        preprocessed_next_values.as_deref().unwrap_or_default(),
         &public_inputs
             .iter()
             .copied()
@@ -167,15 +322,15 @@ where
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let zeta_pow_deg = challenges.stark_zeta.exp_power_of_2(degree_bits);
     let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
-    // `quotient_polys_zeta` holds `num_challenges * quotient_degree_factor` evaluations.
-    // Each chunk of `quotient_degree_factor` holds the evaluations of `t_0(zeta),...,t_{quotient_degree_factor-1}(zeta)`
+    // `quotient_polys_zeta` holds `num_challenges * num_quotient_chunks` evaluations.
+    // Each chunk of `num_quotient_chunks` holds the evaluations of `t_0(zeta),...,t_{num_quotient_chunks-1}(zeta)`
     // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^n + t_2(X)*X^{2n} + ...`.
     // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^n)` for each
-    // `quotient_degree_factor`-sized chunk of the original evaluations.
+    // `num_quotient_chunks`-sized chunk of the original evaluations.
 
     for (i, chunk) in quotient_polys
         .iter()
-        .flat_map(|x| x.chunks(stark.quotient_degree_factor()))
+        .flat_map(|x| x.chunks(stark.num_quotient_chunks(config)))
         .enumerate()
     {
         ensure!(
@@ -187,6 +342,7 @@ where
     let merkle_caps = once(proof.trace_cap.clone())
         .chain(proof.auxiliary_polys_cap.clone())
         .chain(proof.quotient_polys_cap.clone())
+        .chain(preprocessed_cap.cloned())
         .collect_vec();
 
     let num_ctl_zs = ctl_vars
@@ -215,73 +371,6 @@ where
     Ok(())
 }
 
-fn validate_proof_shape<F, C, S, const D: usize>(
-    stark: &S,
-    proof: &StarkProof<F, C, D>,
-    public_inputs: &[F],
-    config: &StarkConfig,
-    num_ctl_helpers: usize,
-    num_ctl_zs: usize,
-) -> anyhow::Result<()>
-where
-    F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
-    S: Stark<F, D>,
-{
-    let degree_bits = proof.recover_degree_bits(config);
-
-    let StarkProof {
-        trace_cap,
-        auxiliary_polys_cap,
-        quotient_polys_cap,
-        openings,
-        // The shape of the opening proof will be checked in the FRI verifier (see
-        // validate_fri_proof_shape), so we ignore it here.
-        opening_proof: _,
-    } = proof;
-
-    let StarkOpeningSet {
-        local_values,
-        next_values,
-        auxiliary_polys,
-        auxiliary_polys_next,
-        ctl_zs_first,
-        quotient_polys,
-    } = openings;
-
-    ensure!(public_inputs.len() == S::PUBLIC_INPUTS);
-
-    let fri_params = config.fri_params(degree_bits);
-    let cap_height = fri_params.config.cap_height;
-
-    ensure!(trace_cap.height() == cap_height);
-    ensure!(
-        quotient_polys_cap.is_none()
-            || quotient_polys_cap.as_ref().map(|q| q.height()) == Some(cap_height)
-    );
-
-    ensure!(local_values.len() == S::COLUMNS);
-    ensure!(next_values.len() == S::COLUMNS);
-    ensure!(if let Some(quotient_polys) = quotient_polys {
-        quotient_polys.len() == stark.num_quotient_polys(config)
-    } else {
-        stark.num_quotient_polys(config) == 0
-    });
-
-    check_lookup_options::<F, C, S, D>(
-        stark,
-        auxiliary_polys_cap,
-        auxiliary_polys,
-        auxiliary_polys_next,
-        num_ctl_helpers,
-        num_ctl_zs,
-        ctl_zs_first,
-        config,
-    )?;
-
-    Ok(())
-}
-
 /// Evaluate the Lagrange polynomials `L_0` and `L_(n-1)` at a point `x`.
 /// `L_0(x) = (x^n - 1)/(n * (x - 1))`
 /// `L_(n-1)(x) = (x^n - 1)/(n * (g * x - 1))`, with `g` the first element of the subgroup.
@@ -294,53 +383,6 @@ fn eval_l_0_and_l_last<F: Field>(log_n: usize, x: F) -> (F, F) {
     (z_x * invs[0], z_x * invs[1])
 }
 
-/// Utility function to check that all lookups data wrapped in `Option`s are `Some` iff
-/// the STARK uses a permutation argument.
-fn check_lookup_options<F, C, S, const D: usize>(
-    stark: &S,
-    auxiliary_polys_cap: &Option<MerkleCap<F, <C as GenericConfig<D>>::Hasher>>,
-    auxiliary_polys: &Option<Vec<<F as Extendable<D>>::Extension>>,
-    auxiliary_polys_next: &Option<Vec<<F as Extendable<D>>::Extension>>,
-    num_ctl_helpers: usize,
-    num_ctl_zs: usize,
-    ctl_zs_first: &Option<Vec<F>>,
-    config: &StarkConfig,
-) -> Result<()>
-where
-    F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
-    S: Stark<F, D>,
-{
-    if stark.uses_lookups() || stark.requires_ctls() {
-        let num_auxiliary = stark.num_lookup_helper_columns(config) + num_ctl_helpers + num_ctl_zs;
-        let cap_height = config.fri_config.cap_height;
-
-        let auxiliary_polys_cap = auxiliary_polys_cap
-            .as_ref()
-            .ok_or_else(|| anyhow!("Missing auxiliary_polys_cap"))?;
-        let auxiliary_polys = auxiliary_polys
-            .as_ref()
-            .ok_or_else(|| anyhow!("Missing auxiliary_polys"))?;
-        let auxiliary_polys_next = auxiliary_polys_next
-            .as_ref()
-            .ok_or_else(|| anyhow!("Missing auxiliary_polys_next"))?;
-
-        if let Some(ctl_zs_first) = ctl_zs_first {
-            ensure!(ctl_zs_first.len() == num_ctl_zs);
-        }
-
-        ensure!(auxiliary_polys_cap.height() == cap_height);
-        ensure!(auxiliary_polys.len() == num_auxiliary);
-        ensure!(auxiliary_polys_next.len() == num_auxiliary);
-    } else {
-        ensure!(auxiliary_polys_cap.is_none());
-        ensure!(auxiliary_polys.is_none());
-        ensure!(auxiliary_polys_next.is_none());
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use plonky2::field::goldilocks_field::GoldilocksField;